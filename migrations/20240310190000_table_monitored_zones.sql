@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS monitored_zones (
+    zone TEXT PRIMARY KEY,
+    added_at TIMESTAMPTZ NOT NULL,
+    removed_at TIMESTAMPTZ NULL,
+    event_id BIGINT NOT NULL,
+    last_updated_at TIMESTAMPTZ NULL DEFAULT clock_timestamp()
+);
@@ -0,0 +1,2 @@
+ALTER TABLE monitored_zones
+ADD COLUMN IF NOT EXISTS zone_type TEXT NOT NULL DEFAULT 'forecast';
@@ -0,0 +1,3 @@
+UPDATE zone_weather
+SET alert = jsonb_build_array(alert)
+WHERE jsonb_typeof(alert) = 'object';
@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS zone_observation_history (
+    zone TEXT NOT NULL,
+    observed_at TIMESTAMPTZ NOT NULL,
+    temperature NUMERIC NULL, temperature_unit TEXT NULL, temperature_qc TEXT NULL,
+    dewpoint NUMERIC NULL, dewpoint_unit TEXT NULL, dewpoint_qc TEXT NULL,
+    wind_direction NUMERIC NULL, wind_direction_unit TEXT NULL, wind_direction_qc TEXT NULL,
+    wind_speed NUMERIC NULL, wind_speed_unit TEXT NULL, wind_speed_qc TEXT NULL,
+    wind_gust NUMERIC NULL, wind_gust_unit TEXT NULL, wind_gust_qc TEXT NULL,
+    barometric_pressure NUMERIC NULL, barometric_pressure_unit TEXT NULL, barometric_pressure_qc TEXT NULL,
+    sea_level_pressure NUMERIC NULL, sea_level_pressure_unit TEXT NULL, sea_level_pressure_qc TEXT NULL,
+    visibility NUMERIC NULL, visibility_unit TEXT NULL, visibility_qc TEXT NULL,
+    max_temperature_last_24_hours NUMERIC NULL, max_temperature_last_24_hours_unit TEXT NULL, max_temperature_last_24_hours_qc TEXT NULL,
+    min_temperature_last_24_hours NUMERIC NULL, min_temperature_last_24_hours_unit TEXT NULL, min_temperature_last_24_hours_qc TEXT NULL,
+    precipitation_last_hour NUMERIC NULL, precipitation_last_hour_unit TEXT NULL, precipitation_last_hour_qc TEXT NULL,
+    precipitation_last_3_hours NUMERIC NULL, precipitation_last_3_hours_unit TEXT NULL, precipitation_last_3_hours_qc TEXT NULL,
+    precipitation_last_6_hours NUMERIC NULL, precipitation_last_6_hours_unit TEXT NULL, precipitation_last_6_hours_qc TEXT NULL,
+    relative_humidity NUMERIC NULL, relative_humidity_unit TEXT NULL, relative_humidity_qc TEXT NULL,
+    wind_chill NUMERIC NULL, wind_chill_unit TEXT NULL, wind_chill_qc TEXT NULL,
+    heat_index NUMERIC NULL, heat_index_unit TEXT NULL, heat_index_qc TEXT NULL,
+    event_id BIGINT NOT NULL,
+    last_updated_at TIMESTAMPTZ NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY ( zone, observed_at )
+);
@@ -0,0 +1,6 @@
+CREATE INDEX IF NOT EXISTS idx_update_weather_history_last_updated
+    ON update_weather_history ( last_updated_at, update_id );
+CREATE INDEX IF NOT EXISTS idx_update_weather_history_state_last_updated
+    ON update_weather_history ( state, last_updated_at, update_id );
+CREATE INDEX IF NOT EXISTS idx_update_weather_history_zones
+    ON update_weather_history USING GIN ( (update_statuses->'location_statuses') );
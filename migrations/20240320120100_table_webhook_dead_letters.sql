@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    dead_letter_id BIGINT PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+    subscription_id TEXT NOT NULL
+        REFERENCES webhook_subscriptions ( subscription_id ) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp()
+);
+CREATE INDEX IF NOT EXISTS idx_webhook_dead_letters_subscription
+    ON webhook_dead_letters ( subscription_id, dead_letter_id );
@@ -0,0 +1,2 @@
+ALTER TABLE zone_weather
+ADD COLUMN IF NOT EXISTS stations JSONB;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS zone_hourly_forecast (
+    zone TEXT NOT NULL,
+    office TEXT NOT NULL,
+    grid_x INTEGER NOT NULL,
+    grid_y INTEGER NOT NULL,
+    updated TIMESTAMPTZ NOT NULL,
+    periods JSONB NOT NULL,
+    event_id BIGINT NOT NULL,
+    last_updated_at TIMESTAMPTZ NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY ( zone )
+);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS zone_metadata (
+    zone TEXT PRIMARY KEY,
+    zone_type TEXT NOT NULL,
+    name TEXT NOT NULL,
+    state TEXT NULL,
+    cwa JSONB NOT NULL DEFAULT '[]',
+    time_zone TEXT NULL,
+    geometry JSONB NULL,
+    min_longitude DOUBLE PRECISION NULL,
+    min_latitude DOUBLE PRECISION NULL,
+    max_longitude DOUBLE PRECISION NULL,
+    max_latitude DOUBLE PRECISION NULL,
+    fetched_at TIMESTAMPTZ NOT NULL,
+    last_updated_at TIMESTAMPTZ NULL DEFAULT clock_timestamp()
+);
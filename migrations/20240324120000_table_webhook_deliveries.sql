@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    subscription_id TEXT NOT NULL
+        REFERENCES webhook_subscriptions ( subscription_id ) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT NULL,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY ( event_id, subscription_id )
+);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_next_attempt
+    ON webhook_deliveries ( next_attempt_at );
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS webhook_alerts (
+    zone TEXT NOT NULL,
+    alert_id TEXT NOT NULL,
+    event_id BIGINT NOT NULL,
+    alert JSONB NOT NULL,
+    PRIMARY KEY ( zone, alert_id )
+);
@@ -0,0 +1,97 @@
+use clap::Parser;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use weather_disintegrate::services::fake_noaa::{
+    FakeNoaa, FakeNoaaFixtures, Fault, FaultInjection,
+};
+
+/// Offline stand-in for the NOAA weather API, serving recorded GeoJSON fixtures. Point the
+/// weather service at it via `--noaa-base-url http://localhost:<port>`.
+#[derive(Debug, Parser)]
+#[clap(author, version, about)]
+struct FakeNoaaOptions {
+    /// Port the fake NOAA API listens on.
+    #[clap(short, long, default_value_t = 8090)]
+    port: u16,
+
+    /// Directory of recorded GeoJSON fixtures.
+    #[clap(
+        short,
+        long,
+        value_name = "FIXTURES_DIR",
+        default_value = "./tests/data"
+    )]
+    fixtures: PathBuf,
+
+    /// Latency added to every response.
+    #[clap(long, value_name = "MILLIS", default_value_t = 0)]
+    latency_ms: u64,
+
+    /// Probability [0, 1] of responding with a 503 server error.
+    #[clap(long, default_value_t = 0.0, value_parser = parse_rate)]
+    server_error_rate: f64,
+
+    /// Probability [0, 1] of responding with 429 Too Many Requests.
+    #[clap(long, default_value_t = 0.0, value_parser = parse_rate)]
+    too_many_requests_rate: f64,
+
+    /// Probability [0, 1] of responding with a truncated GeoJSON payload.
+    #[clap(long, default_value_t = 0.0, value_parser = parse_rate)]
+    malformed_rate: f64,
+
+    /// Faults applied, in order, to the first requests; e.g., `--script server-error,malformed`.
+    #[clap(long, value_delimiter = ',', value_parser = parse_fault)]
+    script: Vec<Fault>,
+}
+
+fn parse_rate(rate: &str) -> Result<f64, String> {
+    match rate.trim().parse::<f64>() {
+        Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+        Ok(rate) => Err(format!("rate {rate} is not a probability in [0, 1]")),
+        Err(err) => Err(format!("bad rate \"{rate}\": {err}")),
+    }
+}
+
+fn parse_fault(fault: &str) -> Result<Fault, String> {
+    match fault.trim().to_lowercase().as_str() {
+        "none" | "ok" => Ok(Fault::None),
+        "server-error" | "5xx" => Ok(Fault::ServerError),
+        "too-many-requests" | "429" => Ok(Fault::TooManyRequests),
+        "malformed" => Ok(Fault::Malformed),
+        "not-found" | "404" => Ok(Fault::NotFound),
+        unknown => Err(format!(
+            "unknown fault \"{unknown}\"; expected none, server-error, too-many-requests, \
+            malformed or not-found"
+        )),
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let subscriber = weather_disintegrate::setup_tracing::get_tracing_subscriber("info");
+    weather_disintegrate::setup_tracing::init_subscriber(subscriber);
+
+    let options = FakeNoaaOptions::parse();
+    let fixtures = FakeNoaaFixtures::load(&options.fixtures)?;
+    if fixtures.is_empty() {
+        tracing::warn!(fixtures_dir=?options.fixtures, "no fake NOAA fixtures found");
+    }
+
+    let faults = FaultInjection {
+        latency: Duration::from_millis(options.latency_ms),
+        scripted: options.script,
+        server_error_rate: options.server_error_rate,
+        too_many_requests_rate: options.too_many_requests_rate,
+        malformed_rate: options.malformed_rate,
+    };
+
+    let address = SocketAddr::from(([0, 0, 0, 0], options.port));
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    tracing::info!(%address, ?faults, "starting fake NOAA weather service...");
+
+    FakeNoaa::new(fixtures, faults)
+        .serve(listener, weather_disintegrate::shutdown())
+        .await?;
+    Ok(())
+}
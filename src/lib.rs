@@ -10,14 +10,17 @@ extern crate utoipa;
 pub mod model;
 mod postgres;
 pub mod server;
-mod services;
+pub mod services;
 mod settings;
 pub mod setup_tracing;
 
 #[cfg(test)]
 mod testing;
 
-pub use settings::{CliOptions, Settings};
+pub use settings::{
+    AggregationSettings, CliOptions, NoaaCaptureSettings, NoaaPointsSettings, NoaaRetrySettings,
+    NoaaSettings, Settings,
+};
 
 pub type EventListenerProcess = tokio::task::JoinHandle<anyhow::Result<()>>;
 
@@ -36,6 +39,24 @@ pub mod errors {
         #[error("{target} expected missing GeoJson Feature property {property}")]
         MissingGeoJsonProperty { target: String, property: String },
 
+        #[error("unrecognized unit of measure: {0}")]
+        UnrecognizedUnit(String),
+
+        #[error("cannot convert {from} to {to}")]
+        IncompatibleUnits {
+            from: crate::model::Unit,
+            to: crate::model::Unit,
+        },
+
+        #[error("invalid geographic point: {0}")]
+        InvalidPoint(String),
+
+        #[error("invalid forecast wind speed: {0}")]
+        InvalidWindSpeed(String),
+
+        #[error("failed to parse timestamp: {0}")]
+        Timestamp(#[from] chrono::ParseError),
+
         #[error("empty quantitative aggregation")]
         EmptyAggregation,
 
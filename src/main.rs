@@ -47,7 +47,9 @@ pub fn load_settings(options: &CliOptions) -> anyhow::Result<weather_disintegrat
         tracing::info!("No environment configuration override provided.");
     }
 
-    weather_disintegrate::Settings::load(options).map_err(|err| err.into())
+    let settings = weather_disintegrate::Settings::load(options)?;
+    settings.aggregation.warn_of_linear_directions();
+    Ok(settings)
 }
 
 fn setup_event_tracing(app: &AppState, task_tracker: &TaskTracker) {
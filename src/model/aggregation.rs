@@ -0,0 +1,261 @@
+use super::{CircularStatistics, Direction, QualityControl, QuantitativeValue};
+use iso8601_timestamp::Timestamp;
+use rust_decimal::prelude::*;
+use std::borrow::Cow;
+use std::fmt;
+use strum_macros::{Display, EnumString, VariantNames};
+
+/// A value of a quantitative property observed by one station.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertySample {
+    pub value: Decimal,
+    pub unit_code: Cow<'static, str>,
+    pub quality_control: QualityControl,
+    pub timestamp: Option<Timestamp>,
+}
+
+/// Folds the samples of a quantitative property observed across a zone's stations into the
+/// property's value for the zone.
+pub trait AggregationStrategy: fmt::Debug + Send + Sync {
+    /// Aggregates the samples, or `None` if there are none to aggregate.
+    fn aggregate(&self, samples: &[PropertySample]) -> Option<QuantitativeValue>;
+}
+
+/// Aggregation strategies selectable in settings.
+#[derive(
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    VariantNames,
+    ToSchema,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationMethod {
+    Mean,
+    Median,
+    MostRecent,
+    HighestQuality,
+    CircularMean,
+}
+
+impl AggregationMethod {
+    /// Whether the method averages values on a line, which misrepresents directions that wrap
+    /// around at 360°.
+    pub const fn is_linear(&self) -> bool {
+        matches!(self, Self::Mean | Self::Median | Self::HighestQuality)
+    }
+
+    pub fn strategy(&self) -> &'static dyn AggregationStrategy {
+        match self {
+            Self::Mean => &Mean,
+            Self::Median => &Median,
+            Self::MostRecent => &MostRecent,
+            Self::HighestQuality => &HighestQuality,
+            Self::CircularMean => &CircularMean,
+        }
+    }
+}
+
+/// Arithmetic mean of every sample, reported at the lowest quality control of the samples.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mean;
+
+impl AggregationStrategy for Mean {
+    fn aggregate(&self, samples: &[PropertySample]) -> Option<QuantitativeValue> {
+        let mean = mean_of(samples)?;
+        summarize(mean, samples)
+    }
+}
+
+/// Median of every sample, which is robust to an outlying station.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Median;
+
+impl AggregationStrategy for Median {
+    fn aggregate(&self, samples: &[PropertySample]) -> Option<QuantitativeValue> {
+        let mut values: Vec<_> = samples.iter().map(|s| s.value).collect();
+        values.sort();
+
+        let middle = values.len() / 2;
+        let median = match values.len() {
+            0 => return None,
+            n if n % 2 == 0 => (values[middle - 1] + values[middle]) / Decimal::TWO,
+            _ => values[middle],
+        };
+        summarize(median, samples)
+    }
+}
+
+/// The most recently observed sample; samples without an observation time are only used if no
+/// sample has one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MostRecent;
+
+impl AggregationStrategy for MostRecent {
+    fn aggregate(&self, samples: &[PropertySample]) -> Option<QuantitativeValue> {
+        // -- max_by_key keeps the last maximum, so equally recent samples favor the last reported
+        let latest = samples.iter().max_by_key(|s| s.timestamp)?;
+        summarize(latest.value, std::slice::from_ref(latest))
+    }
+}
+
+/// Mean of the samples at the highest quality control observed; lower quality samples are ignored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HighestQuality;
+
+impl AggregationStrategy for HighestQuality {
+    fn aggregate(&self, samples: &[PropertySample]) -> Option<QuantitativeValue> {
+        let best = samples.iter().map(|s| s.quality_control).max()?;
+        let best: Vec<_> = samples.iter().filter(|s| s.quality_control == best).cloned().collect();
+        Mean.aggregate(&best)
+    }
+}
+
+/// Circular mean of directional samples in degrees, so that, e.g., 350° and 10° average to 0°
+/// rather than 180°. Samples that cancel out, as for variable winds, have no mean.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CircularMean;
+
+impl CircularMean {
+    /// Whether the samples cancel out so that no direction prevails, which sets variable winds
+    /// apart from winds that are not reported.
+    pub fn is_variable(samples: &[PropertySample]) -> bool {
+        Self::statistics(samples).is_some_and(|stats| stats.is_variable())
+    }
+
+    fn statistics(samples: &[PropertySample]) -> Option<CircularStatistics> {
+        let directions: Vec<_> = samples
+            .iter()
+            .filter_map(|s| s.value.to_f32())
+            .map(Direction::new)
+            .collect();
+
+        CircularStatistics::of(&directions)
+    }
+}
+
+impl AggregationStrategy for CircularMean {
+    fn aggregate(&self, samples: &[PropertySample]) -> Option<QuantitativeValue> {
+        let stats = Self::statistics(samples)?;
+        let Some(mean) = stats.prevailing() else {
+            debug!(
+                resultant_length=%stats.resultant_length, nr_samples=%samples.len(),
+                "no prevailing direction in variable samples"
+            );
+            return None;
+        };
+
+        let mean = Decimal::from_f32(mean.into())?.round_dp(2) % Decimal::from(360);
+        summarize(mean, samples)
+    }
+}
+
+fn mean_of(samples: &[PropertySample]) -> Option<Decimal> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let sum: Decimal = samples.iter().map(|s| s.value).sum();
+    Some(sum / Decimal::from(samples.len()))
+}
+
+/// Reports the aggregated value with the range and lowest quality control of the samples.
+fn summarize(value: Decimal, samples: &[PropertySample]) -> Option<QuantitativeValue> {
+    let first = samples.first()?;
+    let (min_value, max_value) = samples.iter().fold((first.value, first.value), |(lo, hi), s| {
+        (lo.min(s.value), hi.max(s.value))
+    });
+    let quality_control = samples.iter().map(|s| s.quality_control).min()?;
+
+    Some(QuantitativeValue {
+        value,
+        max_value,
+        min_value,
+        unit_code: first.unit_code.clone(),
+        quality_control,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::assert_some;
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    fn sample(value: Decimal, quality_control: QualityControl, minute: u8) -> PropertySample {
+        let timestamp = format!("2024-03-07T00:{minute:02}:00Z");
+        PropertySample {
+            value,
+            unit_code: Cow::Borrowed("wmoUnit:degC"),
+            quality_control,
+            timestamp: Timestamp::parse(&timestamp),
+        }
+    }
+
+    fn samples() -> Vec<PropertySample> {
+        vec![
+            sample(dec!(4), QualityControl::V, 0),
+            sample(dec!(6), QualityControl::V, 15),
+            sample(dec!(20), QualityControl::Z, 5),
+        ]
+    }
+
+    #[test]
+    fn test_mean_and_median() {
+        let mean = assert_some!(Mean.aggregate(&samples()));
+        assert_eq!(mean.value, dec!(10));
+        assert_eq!((mean.min_value, mean.max_value), (dec!(4), dec!(20)));
+        assert_eq!(mean.quality_control, QualityControl::Z);
+
+        let median = assert_some!(Median.aggregate(&samples()));
+        assert_eq!(median.value, dec!(6));
+
+        let even = samples()[..2].to_vec();
+        assert_eq!(assert_some!(Median.aggregate(&even)).value, dec!(5));
+    }
+
+    #[test]
+    fn test_most_recent_and_highest_quality() {
+        let latest = assert_some!(MostRecent.aggregate(&samples()));
+        assert_eq!(latest.value, dec!(6));
+        assert_eq!((latest.min_value, latest.max_value), (dec!(6), dec!(6)));
+
+        let best = assert_some!(HighestQuality.aggregate(&samples()));
+        assert_eq!(best.value, dec!(5));
+        assert_eq!(best.quality_control, QualityControl::V);
+
+        assert_eq!(Mean.aggregate(&[]), None);
+    }
+
+    #[test]
+    fn test_circular_mean_wraps_north() {
+        let samples = vec![
+            sample(dec!(350), QualityControl::V, 0),
+            sample(dec!(10), QualityControl::V, 0),
+        ];
+        let mean = assert_some!(CircularMean.aggregate(&samples));
+        assert!(
+            mean.value < dec!(0.01) || dec!(359.99) < mean.value,
+            "mean direction: {}",
+            mean.value
+        );
+
+        let variable = vec![
+            sample(dec!(0), QualityControl::V, 0),
+            sample(dec!(120), QualityControl::V, 0),
+            sample(dec!(240), QualityControl::V, 0),
+        ];
+        assert_eq!(CircularMean.aggregate(&variable), None);
+        assert!(CircularMean::is_variable(&variable));
+        assert!(!CircularMean::is_variable(&[]));
+    }
+}
@@ -0,0 +1,284 @@
+use super::{AlertMessageType, WeatherAlert};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt::{Display, Write};
+
+pub const CAP_MEDIA_TYPE: &str = "application/cap+xml";
+pub const ATOM_MEDIA_TYPE: &str = "application/atom+xml";
+
+const CAP_NAMESPACE: &str = "urn:oasis:names:tc:emergency:cap:1.2";
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+/// Sender of NOAA's alerts, which are all issued by the National Weather Service.
+pub const NWS_SENDER: &str = "w-nws.webmaster@noaa.gov";
+
+/// Renders the alert as an OASIS CAP v1.2 document following the IPAWS profile, with an `<info>`
+/// block whose `<area>` lists the affected zones as `UGC` geocodes.
+pub fn cap_document(alert: &WeatherAlert) -> String {
+    let mut xml = XmlWriter::new();
+    write_alert(&mut xml, alert);
+    xml.finish()
+}
+
+/// Renders the alerts as an Atom feed whose entries each carry one CAP `<alert>`, in the manner of
+/// NWS's own alert feeds. The feed is updated as of its latest alert, or `as_of` if it has none.
+pub fn cap_feed(id: &str, title: &str, alerts: &[WeatherAlert], as_of: DateTime<Utc>) -> String {
+    let updated = alerts.iter().map(|alert| alert.sent).max().unwrap_or(as_of);
+
+    let mut xml = XmlWriter::new();
+    xml.open("feed", &[("xmlns", ATOM_NAMESPACE)]);
+    xml.element("id", id);
+    xml.element("title", title);
+    xml.element("updated", atom_time(&updated));
+    xml.open("author", &[]);
+    xml.element("name", NWS_SENDER);
+    xml.close("author");
+
+    for alert in alerts {
+        xml.open("entry", &[]);
+        xml.element("id", &alert.id);
+        xml.element("title", alert.headline.as_deref().unwrap_or(&alert.event));
+        xml.element("updated", atom_time(&alert.sent));
+        xml.open("content", &[("type", CAP_MEDIA_TYPE)]);
+        write_alert(&mut xml, alert);
+        xml.close("content");
+        xml.close("entry");
+    }
+
+    xml.close("feed");
+    xml.finish()
+}
+
+fn write_alert(xml: &mut XmlWriter, alert: &WeatherAlert) {
+    xml.open("alert", &[("xmlns", CAP_NAMESPACE)]);
+    xml.element("identifier", &alert.id);
+    xml.element("sender", NWS_SENDER);
+    xml.element("sent", cap_time(&alert.sent));
+    xml.element("status", alert.status);
+    xml.element("msgType", cap_message_type(alert.message_type));
+    xml.element("scope", "Public");
+    xml.element("code", "IPAWSv1.0");
+
+    xml.open("info", &[]);
+    xml.element("language", "en-US");
+    xml.element("category", alert.category);
+    xml.element("event", &alert.event);
+    xml.element("responseType", alert.response);
+    xml.element("urgency", alert.urgency);
+    xml.element("severity", alert.severity);
+    xml.element("certainty", alert.certainty);
+    xml.element("effective", cap_time(&alert.effective));
+    if let Some(onset) = &alert.onset {
+        xml.element("onset", cap_time(onset));
+    }
+    xml.element("expires", cap_time(&alert.expires));
+    if let Some(headline) = &alert.headline {
+        xml.element("headline", headline);
+    }
+    xml.element("description", &alert.description);
+    if let Some(instruction) = &alert.instruction {
+        xml.element("instruction", instruction);
+    }
+    if let Some(ends) = &alert.ends {
+        // -- CAP has no end of the subject event, which NWS conveys as a parameter
+        xml.open("parameter", &[]);
+        xml.element("valueName", "eventEndingTime");
+        xml.element("value", cap_time(ends));
+        xml.close("parameter");
+    }
+
+    if !alert.affected_zones.is_empty() {
+        xml.open("area", &[]);
+        let area_desc: Vec<_> = alert.affected_zones.iter().map(|zone| zone.to_string()).collect();
+        xml.element("areaDesc", area_desc.join("; "));
+        for zone in &alert.affected_zones {
+            xml.open("geocode", &[]);
+            xml.element("valueName", "UGC");
+            xml.element("value", zone);
+            xml.close("geocode");
+        }
+        xml.close("area");
+    }
+    xml.close("info");
+
+    xml.close("alert");
+}
+
+/// CAP only knows `Alert`, `Update`, `Cancel`, `Ack` and `Error` message types; NOAA's `Actual`
+/// and `Test` message types are initial alerts, with `status` telling them apart.
+fn cap_message_type(message_type: AlertMessageType) -> &'static str {
+    match message_type {
+        AlertMessageType::Actual | AlertMessageType::Alert | AlertMessageType::Test => "Alert",
+        AlertMessageType::Update => "Update",
+        AlertMessageType::Cancel => "Cancel",
+    }
+}
+
+/// CAP times carry an explicit offset and must not use `Z`; UTC is written as `-00:00`.
+fn cap_time(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H:%M:%S-00:00").to_string()
+}
+
+fn atom_time(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Writes indented XML elements, escaping their text and attribute values.
+struct XmlWriter {
+    xml: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn new() -> Self {
+        Self { xml: format!("{XML_DECLARATION}\n"), depth: 0 }
+    }
+
+    fn open(&mut self, tag: &str, attributes: &[(&str, &str)]) {
+        self.indent();
+        self.xml.push('<');
+        self.xml.push_str(tag);
+        for (name, value) in attributes {
+            let _ = write!(self.xml, r#" {name}="{}""#, escape(value));
+        }
+        self.xml.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth = self.depth.saturating_sub(1);
+        self.indent();
+        let _ = writeln!(self.xml, "</{tag}>");
+    }
+
+    fn element(&mut self, tag: &str, text: impl Display) {
+        self.indent();
+        let _ = writeln!(self.xml, "<{tag}>{}</{tag}>", escape(&text.to_string()));
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.depth {
+            self.xml.push_str("  ");
+        }
+    }
+
+    fn finish(self) -> String {
+        self.xml
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        AlertCategory, AlertCertainty, AlertResponse, AlertSeverity, AlertStatus, AlertUrgency,
+        LocationZoneCode,
+    };
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    fn alert() -> WeatherAlert {
+        let sent = Utc.with_ymd_and_hms(2024, 3, 22, 12, 0, 0).unwrap();
+        WeatherAlert {
+            id: "urn:oid:2.49.0.1.840.0.1".to_string(),
+            affected_zones: vec![
+                LocationZoneCode::new("WAZ558"),
+                LocationZoneCode::new("WAZ559"),
+            ],
+            status: AlertStatus::Actual,
+            message_type: AlertMessageType::Actual,
+            sent,
+            effective: sent,
+            onset: None,
+            expires: sent + chrono::Duration::hours(6),
+            ends: Some(sent + chrono::Duration::hours(12)),
+            category: AlertCategory::Met,
+            severity: AlertSeverity::Moderate,
+            certainty: AlertCertainty::Likely,
+            urgency: AlertUrgency::Expected,
+            event: "Wind Advisory".to_string(),
+            headline: Some("Wind Advisory issued March 22 by NWS Seattle WA".to_string()),
+            description: "Winds 25 to 35 mph & gusts up to 50 mph <locally higher>.".to_string(),
+            instruction: None,
+            response: AlertResponse::Execute,
+        }
+    }
+
+    #[test]
+    fn test_cap_document() {
+        let xml = cap_document(&alert());
+        assert!(xml.starts_with(XML_DECLARATION));
+
+        for expected in [
+            r#"<alert xmlns="urn:oasis:names:tc:emergency:cap:1.2">"#,
+            "  <identifier>urn:oid:2.49.0.1.840.0.1</identifier>",
+            "  <sent>2024-03-22T12:00:00-00:00</sent>",
+            "  <status>Actual</status>",
+            "  <msgType>Alert</msgType>",
+            "    <category>Met</category>",
+            "    <responseType>Execute</responseType>",
+            "    <expires>2024-03-22T18:00:00-00:00</expires>",
+            concat!(
+                "    <description>Winds 25 to 35 mph &amp; gusts up to 50 mph ",
+                "&lt;locally higher&gt;.</description>"
+            ),
+            "      <value>2024-03-23T00:00:00-00:00</value>",
+            "      <areaDesc>WAZ558; WAZ559</areaDesc>",
+            "        <valueName>UGC</valueName>",
+            "        <value>WAZ559</value>",
+        ] {
+            assert!(xml.contains(expected), "missing {expected} in:\n{xml}");
+        }
+        assert!(!xml.contains("<onset>"));
+        assert!(!xml.contains("<instruction>"));
+        assert_eq!(xml.matches("<geocode>").count(), 2);
+        assert!(xml.ends_with("</alert>\n"));
+    }
+
+    #[test]
+    fn test_cap_feed() {
+        let as_of = Utc.with_ymd_and_hms(2024, 3, 23, 0, 0, 0).unwrap();
+        let empty = cap_feed("urn:alerts", "Alerts", &[], as_of);
+        assert!(empty.contains("  <updated>2024-03-23T00:00:00Z</updated>"));
+        assert!(!empty.contains("<entry>"));
+
+        let mut update = alert();
+        update.id = "urn:oid:2.49.0.1.840.0.2".to_string();
+        update.message_type = AlertMessageType::Update;
+        update.sent += chrono::Duration::hours(1);
+        update.headline = None;
+
+        let feed = cap_feed("urn:alerts", "Alerts", &[alert(), update], as_of);
+        assert!(feed.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#));
+        assert!(feed.contains("  <updated>2024-03-22T13:00:00Z</updated>"));
+        assert!(feed.contains("    <title>Wind Advisory</title>"));
+        assert!(feed.contains(r#"    <content type="application/cap+xml">"#));
+        assert!(feed.contains("        <msgType>Update</msgType>"));
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert_eq!(feed.matches("</alert>").count(), 2);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"a < b & "c" > 'd'"#),
+            "a &lt; b &amp; &quot;c&quot; &gt; &apos;d&apos;"
+        );
+        assert_eq!(escape("plain"), "plain");
+    }
+}
@@ -0,0 +1,167 @@
+use super::Direction;
+
+/// Mean resultant length below which the directions are taken to cancel out, so no direction
+/// prevails; e.g., three stations reporting wind from 0°, 120° and 240°.
+pub const VARIABLE_RESULTANT_LENGTH: f32 = 0.05;
+
+/// Mean of a set of directions.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MeanDirection {
+    /// The directions prevail toward this mean, in degrees normalized to [0, 360).
+    Prevailing(Direction),
+
+    /// The directions cancel out, as for calm or variable winds, so there is no meaningful mean.
+    Variable,
+}
+
+/// Circular statistics of directions in degrees, which unlike linear statistics account for
+/// directions wrapping around at 360°.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CircularStatistics {
+    pub mean: MeanDirection,
+
+    /// Length of the mean resultant vector, from 0 when the directions cancel out to 1 when they
+    /// all agree.
+    pub resultant_length: f32,
+
+    /// Circular standard deviation in degrees, `sqrt(-2 ln R)`, which grows without bound as the
+    /// resultant length `R` approaches 0.
+    pub std_dev: f32,
+}
+
+impl CircularStatistics {
+    /// Statistics of the directions, or `None` if there are none.
+    pub fn of(directions: &[Direction]) -> Option<Self> {
+        if directions.is_empty() {
+            return None;
+        }
+
+        // -- sum unit vectors in f64 so opposing directions cancel out cleanly
+        let n = directions.len() as f64;
+        let (sum_sin, sum_cos) = directions
+            .iter()
+            .map(|d| f64::from(d.0).to_radians())
+            .fold((0.0_f64, 0.0_f64), |(s, c), r| (s + r.sin(), c + r.cos()));
+        let (mean_sin, mean_cos) = (sum_sin / n, sum_cos / n);
+
+        let resultant_length = mean_sin.hypot(mean_cos).min(1.0);
+        let std_dev = (-2.0 * resultant_length.ln()).max(0.0).sqrt().to_degrees();
+        let mean = if resultant_length < f64::from(VARIABLE_RESULTANT_LENGTH) {
+            MeanDirection::Variable
+        } else {
+            MeanDirection::Prevailing(Direction::normalized(mean_sin.atan2(mean_cos).to_degrees()))
+        };
+
+        Some(Self {
+            mean,
+            resultant_length: resultant_length as f32,
+            std_dev: std_dev as f32,
+        })
+    }
+
+    /// The prevailing direction, or `None` if the directions are variable.
+    pub fn prevailing(&self) -> Option<Direction> {
+        match self.mean {
+            MeanDirection::Prevailing(direction) => Some(direction),
+            MeanDirection::Variable => None,
+        }
+    }
+
+    pub fn is_variable(&self) -> bool {
+        self.mean == MeanDirection::Variable
+    }
+}
+
+impl Direction {
+    /// Direction of the angle in degrees, normalized to [0, 360).
+    fn normalized(degrees: f64) -> Self {
+        let degrees = degrees.rem_euclid(360.0) as f32;
+
+        // -- angles just below 0 wrap to 360 once rounded to f32
+        if degrees < 360.0 {
+            Self(degrees)
+        } else {
+            Self(0.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use claims::{assert_none, assert_some};
+    use proptest::prelude::*;
+
+    fn directions(degrees: &[f32]) -> Vec<Direction> {
+        degrees.iter().copied().map(Direction::new).collect()
+    }
+
+    fn angular_distance(a: f32, b: f32) -> f32 {
+        let d = (a - b).rem_euclid(360.0);
+        d.min(360.0 - d)
+    }
+
+    #[test]
+    fn test_circular_statistics_agreeing() {
+        let stats = assert_some!(CircularStatistics::of(&directions(&[270.0, 270.0])));
+        assert_eq!(stats.prevailing(), Some(Direction(270.0)));
+        assert_relative_eq!(stats.resultant_length, 1.0, epsilon = 1e-6);
+        assert_relative_eq!(stats.std_dev, 0.0, epsilon = 1e-3);
+
+        let stats = assert_some!(CircularStatistics::of(&directions(&[-10.0, 370.0])));
+        let mean = assert_some!(stats.prevailing());
+        assert_relative_eq!(mean, Direction(0.0), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_circular_statistics_spread() {
+        let stats = assert_some!(CircularStatistics::of(&directions(&[45.0, 135.0])));
+        assert_relative_eq!(
+            stats.resultant_length,
+            std::f32::consts::FRAC_1_SQRT_2,
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(stats.std_dev, 47.7019, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_circular_statistics_variable() {
+        let stats = assert_some!(CircularStatistics::of(&directions(&[0.0, 120.0, 240.0])));
+        assert!(stats.is_variable());
+        assert_none!(stats.prevailing());
+        assert!(stats.resultant_length < 1e-6);
+
+        assert_none!(CircularStatistics::of(&[]));
+    }
+
+    proptest! {
+        #[test]
+        fn test_mean_is_normalized(degrees in prop::collection::vec(-720.0_f32..720.0, 1..16)) {
+            let stats = CircularStatistics::of(&directions(&degrees)).unwrap();
+            prop_assert!((0.0..=1.0).contains(&stats.resultant_length));
+            prop_assert!(0.0 <= stats.std_dev);
+            if let Some(mean) = stats.prevailing() {
+                let mean: f32 = mean.into();
+                prop_assert!((0.0..360.0).contains(&mean), "mean: {mean}");
+            }
+        }
+
+        #[test]
+        fn test_mean_rotates_with_directions(
+            degrees in prop::collection::vec(0.0_f32..360.0, 1..16),
+            offset in 0.0_f32..360.0,
+        ) {
+            let stats = CircularStatistics::of(&directions(&degrees)).unwrap();
+            let rotated: Vec<_> = degrees.iter().map(|d| d + offset).collect();
+            let rotated = CircularStatistics::of(&directions(&rotated)).unwrap();
+            prop_assert!((stats.resultant_length - rotated.resultant_length).abs() < 1e-4);
+
+            // -- the mean is only stable away from the variable threshold
+            prop_assume!(0.1 < stats.resultant_length);
+            let mean: f32 = stats.prevailing().unwrap().into();
+            let rotated_mean: f32 = rotated.prevailing().unwrap().into();
+            prop_assert!(angular_distance(mean + offset, rotated_mean) < 1e-2);
+        }
+    }
+}
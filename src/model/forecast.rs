@@ -0,0 +1,599 @@
+use super::{Direction, Gridpoint, Unit};
+use crate::errors::WeatherError;
+use chrono::{DateTime, Utc};
+use geojson::Feature;
+use rust_decimal::prelude::*;
+use serde::{Deserializer, Serializer};
+use serde_with::{serde_as, DeserializeAs, NoneAsEmptyString, SerializeAs};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString, VariantNames};
+
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+pub struct ZoneForecast {
+    // #[serde(deserialize_with = "ZoneForecast::deserialize_zone_from_url")]
+    pub zone_code: String,
+
+    /// When NOAA last updated the forecast, or when it was fetched if NOAA doesn't say.
+    pub updated: DateTime<Utc>,
+
+    pub periods: Vec<ForecastDetail>,
+}
+
+impl TryFrom<Feature> for ZoneForecast {
+    type Error = WeatherError;
+
+    fn try_from(feature: Feature) -> Result<Self, Self::Error> {
+        let zone_code = feature
+            .property("zone")
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| Self::Error::MissingFeature("zone".to_string()))?
+            .to_string();
+
+        let updated = match feature.property("updated").and_then(|p| p.as_str()) {
+            Some(updated) => DateTime::parse_from_rfc3339(updated)?.with_timezone(&Utc),
+            None => Utc::now(),
+        };
+
+        let periods: Vec<Result<ForecastDetail, Self::Error>> = feature
+            .property("periods")
+            .and_then(|p| p.as_array())
+            .cloned()
+            .map(|ps| {
+                ps.into_iter()
+                    .map(|detail| serde_json::from_value(detail).map_err(|err| err.into()))
+                    .collect()
+            })
+            .ok_or_else(|| Self::Error::MissingFeature("periods".to_string()))?;
+
+        let nr_periods = periods.len();
+        let periods: Vec<ForecastDetail> =
+            periods.into_iter().try_fold(Vec::with_capacity(nr_periods), |acc, res| {
+                match (acc, res) {
+                    (mut acc_0, Ok(p)) => {
+                        acc_0.push(p);
+                        Ok(acc_0)
+                    },
+
+                    (_, Err(err)) => Err(err),
+                }
+            })?;
+
+        Ok(Self { zone_code, updated, periods })
+    }
+}
+
+/// Hourly forecast of a NOAA gridpoint, which unlike a zone forecast details numeric weather for
+/// each hour over the coming days.
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HourlyForecast {
+    pub gridpoint: Gridpoint,
+
+    /// When NOAA last updated the forecast.
+    pub updated: DateTime<Utc>,
+
+    pub periods: Vec<HourlyForecastPeriod>,
+}
+
+impl HourlyForecast {
+    /// Parses NOAA's `/gridpoints/{office}/{x},{y}/forecast/hourly` feature for the gridpoint.
+    /// NOAA reports when the forecast was last updated as `updateTime`, or `updated` in older
+    /// responses.
+    pub fn from_feature(gridpoint: Gridpoint, feature: Feature) -> Result<Self, WeatherError> {
+        let updated = feature
+            .property("updateTime")
+            .or_else(|| feature.property("updated"))
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| WeatherError::MissingFeature("updateTime".to_string()))?;
+        let updated = DateTime::parse_from_rfc3339(updated)?.with_timezone(&Utc);
+
+        let periods = feature
+            .property("periods")
+            .cloned()
+            .ok_or_else(|| WeatherError::MissingFeature("periods".to_string()))?;
+        let periods = serde_json::from_value(periods)?;
+
+        Ok(Self { gridpoint, updated, periods })
+    }
+
+    /// The forecast's periods overlapping the time range, which is unbounded on the side of a
+    /// missing bound.
+    pub fn periods_between(
+        &self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>,
+    ) -> impl Iterator<Item = &HourlyForecastPeriod> {
+        self.periods.iter().filter(move |p| {
+            let after_from = match from {
+                Some(from) => from < p.end_time,
+                None => true,
+            };
+
+            let before_to = match to {
+                Some(to) => p.start_time < to,
+                None => true,
+            };
+
+            after_from && before_to
+        })
+    }
+}
+
+/// An hour of a gridpoint's hourly forecast.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HourlyForecastPeriod {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub is_daytime: bool,
+    pub temperature: i32,
+    pub temperature_unit: TemperatureUnit,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probability_of_precipitation: Option<ForecastQuantity>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dewpoint: Option<ForecastQuantity>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_humidity: Option<ForecastQuantity>,
+
+    #[serde_as(as = "LenientWindSpeed")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, example = "10 mph")]
+    pub wind_speed: Option<WindSpeed>,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<CompassPoint>)]
+    pub wind_direction: Option<CompassPoint>,
+
+    /// URL of an icon depicting the hour's weather.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_forecast: Option<String>,
+}
+
+/// A period of a NOAA forecast. Zone forecasts only name the period and describe its forecast
+/// in text, whereas gridpoint forecasts also time the period and detail its weather.
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastDetail {
+    /// Sequence number of the period in the forecast, starting at 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<u32>,
+
+    /// Name of the period, e.g., `Tonight` or `Thursday`.
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_daytime: Option<bool>,
+
+    /// Forecast high temperature of a daytime period or low temperature of a nighttime period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_unit: Option<TemperatureUnit>,
+
+    /// Whether the temperature trends against the usual daily cycle over the period.
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<TemperatureTrend>)]
+    pub temperature_trend: Option<TemperatureTrend>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probability_of_precipitation: Option<ForecastQuantity>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dewpoint: Option<ForecastQuantity>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_humidity: Option<ForecastQuantity>,
+
+    #[serde_as(as = "LenientWindSpeed")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>, example = "5 to 10 mph")]
+    pub wind_speed: Option<WindSpeed>,
+
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<CompassPoint>)]
+    pub wind_direction: Option<CompassPoint>,
+
+    /// URL of an icon depicting the period's weather.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_forecast: Option<String>,
+
+    #[serde(alias = "detailedForecast")]
+    pub forecast: String,
+}
+
+impl ForecastDetail {
+    /// Whether the period overlaps the time range, which is unbounded on the side of a missing
+    /// bound. A period without times only overlaps an unbounded range.
+    pub fn overlaps(&self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> bool {
+        let after_from = match (from, self.end_time) {
+            (None, _) => true,
+            (Some(from), Some(end)) => from < end,
+            (Some(_), None) => false,
+        };
+
+        let before_to = match (to, self.start_time) {
+            (None, _) => true,
+            (Some(to), Some(start)) => start < to,
+            (Some(_), None) => false,
+        };
+
+        after_from && before_to
+    }
+}
+
+#[derive(
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    VariantNames,
+    ToSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum TemperatureUnit {
+    F,
+    C,
+}
+
+impl TemperatureUnit {
+    pub fn unit(&self) -> Unit {
+        match self {
+            Self::F => Unit::DegreesFahrenheit,
+            Self::C => Unit::DegreesCelsius,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    VariantNames,
+    ToSchema,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureTrend {
+    Rising,
+    Falling,
+}
+
+/// A forecast value with its WMO unit code; NOAA reports a null value when, e.g., there is no
+/// chance of precipitation.
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastQuantity {
+    pub unit_code: Cow<'static, str>,
+
+    #[serde(default)]
+    pub value: Option<Decimal>,
+}
+
+impl ForecastQuantity {
+    pub fn unit(&self) -> Result<Unit, WeatherError> {
+        Unit::parse(&self.unit_code)
+    }
+}
+
+/// The 16 points of the compass NOAA forecasts wind direction with.
+#[derive(
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    VariantNames,
+    ToSchema,
+    Serialize,
+    Deserialize,
+)]
+#[strum(ascii_case_insensitive)]
+#[rustfmt::skip]
+pub enum CompassPoint {
+    N, NNE, NE, ENE, E, ESE, SE, SSE, S, SSW, SW, WSW, W, WNW, NW, NNW,
+}
+
+impl CompassPoint {
+    /// Direction the wind blows from, in degrees clockwise from true north.
+    pub fn direction(&self) -> Direction {
+        Direction::new(*self as u8 as f32 * 22.5)
+    }
+}
+
+/// Forecast wind speed, which NOAA reports as a single speed, e.g., `10 mph`, or a range, e.g.,
+/// `5 to 10 mph`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WindSpeed {
+    pub low: u32,
+    pub high: u32,
+    pub unit: WindSpeedUnit,
+}
+
+impl fmt::Display for WindSpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.low == self.high {
+            write!(f, "{} {}", self.high, self.unit)
+        } else {
+            write!(f, "{} to {} {}", self.low, self.high, self.unit)
+        }
+    }
+}
+
+impl FromStr for WindSpeed {
+    type Err = WeatherError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let invalid = || WeatherError::InvalidWindSpeed(rep.to_string());
+
+        let (speeds, unit) = rep.trim().rsplit_once(' ').ok_or_else(invalid)?;
+        let unit = WindSpeedUnit::from_str(unit).map_err(|_| invalid())?;
+
+        let speed = |s: &str| u32::from_str(s.trim()).map_err(|_| invalid());
+        let (low, high) = match speeds.split_once(" to ") {
+            Some((low, high)) => (speed(low)?, speed(high)?),
+            None => (speed(speeds)?, speed(speeds)?),
+        };
+
+        if high < low {
+            return Err(invalid());
+        }
+
+        Ok(Self { low, high, unit })
+    }
+}
+
+/// Serde adapter for a period's wind speed. A speed NOAA reports in a form not understood, e.g.,
+/// `light`, is logged and taken as no wind speed rather than failing the whole forecast.
+struct LenientWindSpeed;
+
+impl SerializeAs<Option<WindSpeed>> for LenientWindSpeed {
+    fn serialize_as<S: Serializer>(
+        source: &Option<WindSpeed>, serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        NoneAsEmptyString::serialize_as(source, serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Option<WindSpeed>> for LenientWindSpeed {
+    fn deserialize_as<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<WindSpeed>, D::Error> {
+        let rep: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+        let speed = rep.filter(|rep| !rep.trim().is_empty()).and_then(|rep| {
+            WindSpeed::from_str(&rep)
+                .map_err(|error| warn!(?error, "ignoring unrecognized forecast wind speed"))
+                .ok()
+        });
+        Ok(speed)
+    }
+}
+
+#[derive(
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    VariantNames,
+    ToSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum WindSpeedUnit {
+    #[strum(serialize = "mph")]
+    #[serde(rename = "mph")]
+    MilesPerHour,
+
+    #[strum(serialize = "km/h")]
+    #[serde(rename = "km/h")]
+    KilometersPerHour,
+
+    #[strum(serialize = "kt")]
+    #[serde(rename = "kt")]
+    Knots,
+}
+
+impl WindSpeedUnit {
+    pub fn unit(&self) -> Unit {
+        match self {
+            Self::MilesPerHour => Unit::MilesPerHour,
+            Self::KilometersPerHour => Unit::KilometersPerHour,
+            Self::Knots => Unit::Knots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::{assert_err, assert_ok};
+    use pretty_assertions::assert_eq;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_wind_speed_rep() {
+        let range = assert_ok!(WindSpeed::from_str("5 to 10 mph"));
+        assert_eq!(
+            range,
+            WindSpeed {
+                low: 5,
+                high: 10,
+                unit: WindSpeedUnit::MilesPerHour
+            }
+        );
+        assert_eq!(range.to_string(), "5 to 10 mph");
+
+        let single = assert_ok!(WindSpeed::from_str("15 km/h"));
+        assert_eq!((single.low, single.high), (15, 15));
+        assert_eq!(single.to_string(), "15 km/h");
+
+        assert_err!(WindSpeed::from_str("light"));
+        assert_err!(WindSpeed::from_str("10 to 5 mph"));
+    }
+
+    #[test]
+    fn test_compass_point_direction() {
+        assert_eq!(f32::from(CompassPoint::N.direction()), 0.0);
+        assert_eq!(f32::from(CompassPoint::SW.direction()), 225.0);
+        assert_eq!(f32::from(CompassPoint::NNW.direction()), 337.5);
+    }
+
+    #[test]
+    fn test_gridpoint_period_serde() {
+        let json = serde_json::json!({
+            "number": 1,
+            "name": "Tonight",
+            "startTime": "2024-03-13T18:00:00-07:00",
+            "endTime": "2024-03-14T06:00:00-07:00",
+            "isDaytime": false,
+            "temperature": 36,
+            "temperatureUnit": "F",
+            "temperatureTrend": null,
+            "probabilityOfPrecipitation": { "unitCode": "wmoUnit:percent", "value": 20 },
+            "windSpeed": "5 to 10 mph",
+            "windDirection": "SW",
+            "icon": "https://api.weather.gov/icons/land/night/few?size=medium",
+            "shortForecast": "Mostly Clear",
+            "detailedForecast": "Mostly clear, with a low around 36."
+        });
+
+        let period: ForecastDetail = assert_ok!(serde_json::from_value(json));
+        let start = assert_ok!(DateTime::parse_from_rfc3339("2024-03-14T01:00:00Z"));
+        assert_eq!(period.start_time, Some(start.with_timezone(&Utc)));
+        assert_eq!(
+            period.temperature_unit.map(|u| u.unit()),
+            Some(Unit::DegreesFahrenheit)
+        );
+        assert_eq!(period.temperature_trend, None);
+        assert_eq!(
+            period.probability_of_precipitation.as_ref().and_then(|p| p.value),
+            Some(dec!(20))
+        );
+        assert_eq!(period.wind_direction, Some(CompassPoint::SW));
+        assert_eq!(period.forecast, "Mostly clear, with a low around 36.");
+
+        let start = start.with_timezone(&Utc);
+        assert!(period.overlaps(Some(start), None));
+        assert!(!period.overlaps(None, Some(start)));
+
+        let json = assert_ok!(serde_json::to_value(&period));
+        assert_eq!(json["windSpeed"], "5 to 10 mph");
+        assert_eq!(
+            assert_ok!(serde_json::from_value::<ForecastDetail>(json)),
+            period
+        );
+    }
+
+    #[test]
+    fn test_hourly_forecast_from_feature() {
+        let body = assert_ok!(std::fs::read_to_string(
+            "./tests/data/geojson-sew_126,63-gridpoint-hourly-forecast-1.json"
+        ));
+        let feature: Feature = assert_ok!(body.parse());
+        let gridpoint = assert_ok!(Gridpoint::from_str("SEW/126,63"));
+
+        let forecast = assert_ok!(HourlyForecast::from_feature(gridpoint.clone(), feature));
+        assert_eq!(forecast.gridpoint, gridpoint);
+        assert_eq!(forecast.updated.to_rfc3339(), "2024-03-13T21:14:37+00:00");
+        assert_eq!(forecast.periods.len(), 24);
+
+        let first = &forecast.periods[0];
+        assert_eq!(first.start_time.to_rfc3339(), "2024-03-13T21:00:00+00:00");
+        assert_eq!(
+            first.end_time - first.start_time,
+            chrono::Duration::hours(1)
+        );
+        assert_eq!(first.temperature_unit, TemperatureUnit::F);
+        assert_eq!(
+            first.wind_speed,
+            Some(WindSpeed { low: 7, high: 7, unit: WindSpeedUnit::MilesPerHour })
+        );
+        assert_eq!(first.wind_direction, Some(CompassPoint::SSW));
+
+        let from = first.end_time;
+        let to = from + chrono::Duration::hours(3);
+        let between: Vec<_> = forecast.periods_between(Some(from), Some(to)).collect();
+        assert_eq!(between.len(), 3);
+        assert_eq!(between[0], &forecast.periods[1]);
+        assert_eq!(forecast.periods_between(None, None).count(), 24);
+    }
+
+    #[test]
+    fn test_zone_period_serde() {
+        let json = r#"{"number":1,"name":"Tonight","detailedForecast":"Partly cloudy."}"#;
+        let period: ForecastDetail = assert_ok!(serde_json::from_str(json));
+        assert_eq!(period.start_time, None);
+        assert_eq!(period.wind_speed, None);
+        assert!(period.overlaps(None, None));
+        assert!(!period.overlaps(Some(Utc::now()), None));
+
+        let legacy = r#"{"name":"Tonight","forecast":"Partly cloudy."}"#;
+        let legacy: ForecastDetail = assert_ok!(serde_json::from_str(legacy));
+        assert_eq!(legacy.forecast, period.forecast);
+
+        let light = r#"{"name":"Tonight","windSpeed":"light","detailedForecast":"Calm."}"#;
+        let light: ForecastDetail = assert_ok!(serde_json::from_str(light));
+        assert_eq!(light.wind_speed, None);
+        assert_eq!(light.forecast, "Calm.");
+    }
+
+    #[test]
+    fn test_zone_forecast_without_updated() {
+        let before = Utc::now();
+        let body = serde_json::json!({
+            "type": "Feature",
+            "geometry": null,
+            "properties": {
+                "zone": "https://api.weather.gov/zones/forecast/WAZ558",
+                "periods": [{ "number": 1, "name": "Tonight", "detailedForecast": "Rain." }]
+            }
+        });
+        let feature: Feature = assert_ok!(serde_json::from_value(body));
+
+        let forecast = assert_ok!(ZoneForecast::try_from(feature));
+        assert!(
+            before <= forecast.updated,
+            "falls back to when the forecast was fetched"
+        );
+        assert_eq!(forecast.periods.len(), 1);
+    }
+}
@@ -1,9 +1,8 @@
-use super::{QualityControl, QuantitativeValue};
+use super::{AggregationMethod, CircularMean, PropertySample, QualityControl, QuantitativeValue};
+use crate::settings::AggregationSettings;
 use geojson::{Feature, FeatureCollection};
 use iso8601_timestamp::Timestamp;
 use rust_decimal::prelude::*;
-use std::borrow::Cow;
-use std::cmp::Ordering;
 use std::collections::HashMap;
 use strum::{IntoEnumIterator, VariantNames};
 use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr, VariantNames};
@@ -22,6 +21,11 @@ pub struct WeatherFrame {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub wind_direction: Option<QuantitativeValue>,
 
+    /// Whether the stations report winds from directions that cancel out, in which case there is
+    /// no `wind_direction` because no direction prevails rather than because none was reported.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wind_direction_variable: bool,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub wind_speed: Option<QuantitativeValue>,
 
@@ -62,61 +66,247 @@ pub struct WeatherFrame {
     pub heat_index: Option<QuantitativeValue>,
 }
 
+impl WeatherFrame {
+    pub fn property(&self, property: QuantitativeProperty) -> Option<&QuantitativeValue> {
+        match property {
+            QuantitativeProperty::Temperature => self.temperature.as_ref(),
+            QuantitativeProperty::Dewpoint => self.dewpoint.as_ref(),
+            QuantitativeProperty::WindDirection => self.wind_direction.as_ref(),
+            QuantitativeProperty::WindSpeed => self.wind_speed.as_ref(),
+            QuantitativeProperty::WindGust => self.wind_gust.as_ref(),
+            QuantitativeProperty::BarometricPressure => self.barometric_pressure.as_ref(),
+            QuantitativeProperty::SeaLevelPressure => self.sea_level_pressure.as_ref(),
+            QuantitativeProperty::Visibility => self.visibility.as_ref(),
+            QuantitativeProperty::MaxTemperatureLast24Hours => {
+                self.max_temperature_last_24_hours.as_ref()
+            },
+            QuantitativeProperty::MinTemperatureLast24Hours => {
+                self.min_temperature_last_24_hours.as_ref()
+            },
+            QuantitativeProperty::PrecipitationLastHour => self.precipitation_last_hour.as_ref(),
+            QuantitativeProperty::PrecipitationLast3Hours => {
+                self.precipitation_last_3_hours.as_ref()
+            },
+            QuantitativeProperty::PrecipitationLast6Hours => {
+                self.precipitation_last_6_hours.as_ref()
+            },
+            QuantitativeProperty::RelativeHumidity => self.relative_humidity.as_ref(),
+            QuantitativeProperty::WindChill => self.wind_chill.as_ref(),
+            QuantitativeProperty::HeatIndex => self.heat_index.as_ref(),
+        }
+    }
+}
+
 impl From<FeatureCollection> for WeatherFrame {
     fn from(geojson: FeatureCollection) -> Self {
-        geojson
-            .features
-            .into_iter()
+        Self::from_features(&geojson.features, &AggregationSettings::default())
+    }
+}
+
+impl WeatherFrame {
+    /// Aggregates the station observations of the features into a frame, using the aggregation
+    /// strategy the settings map to each property.
+    pub fn from_features(features: &[Feature], settings: &AggregationSettings) -> Self {
+        features
+            .iter()
             .fold(PropertyAggregations::default(), fold_feature)
-            .into()
+            .into_frame(settings)
+    }
+}
+
+/// A zone's observation: the frame aggregating every station observation reported for the zone,
+/// alongside the latest observation of each station.
+#[derive(Debug, PartialEq, Eq, Clone, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservationReport {
+    pub weather: WeatherFrame,
+    pub stations: Vec<StationObservation>,
+}
+
+impl From<FeatureCollection> for ObservationReport {
+    fn from(geojson: FeatureCollection) -> Self {
+        Self::from_features(&geojson.features, &AggregationSettings::default())
+    }
+}
+
+impl ObservationReport {
+    pub fn from_features(features: &[Feature], settings: &AggregationSettings) -> Self {
+        let mut latest: HashMap<String, (Timestamp, &Feature)> = HashMap::new();
+        for feature in features {
+            let Some((station_id, timestamp)) =
+                StationObservation::station_id(feature).zip(observed_at(feature))
+            else {
+                continue;
+            };
+
+            match latest.get(&station_id) {
+                Some((latest_timestamp, _)) if timestamp <= *latest_timestamp => {},
+                _ => {
+                    latest.insert(station_id, (timestamp, feature));
+                },
+            }
+        }
+
+        let mut stations: Vec<_> = latest
+            .into_iter()
+            .map(|(station_id, (_, feature))| {
+                StationObservation::new(station_id, feature, settings)
+            })
+            .collect();
+        stations.sort_by(|lhs, rhs| lhs.station_id.cmp(&rhs.station_id));
+
+        Self {
+            weather: WeatherFrame::from_features(features, settings),
+            stations,
+        }
     }
 }
 
+/// The observation a single station reported, whose weather frame is stamped with the time the
+/// station observed it.
+#[derive(Debug, PartialEq, Eq, Clone, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StationObservation {
+    pub station_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<StationLocation>,
+
+    pub weather: WeatherFrame,
+}
+
+impl StationObservation {
+    fn new(station_id: String, feature: &Feature, settings: &AggregationSettings) -> Self {
+        let name = feature
+            .property("stationName")
+            .and_then(|n| n.as_str())
+            .map(|n| n.to_string());
+        let location = feature.geometry.as_ref().and_then(|geometry| match &geometry.value {
+            geojson::Value::Point(position) => StationLocation::from_position(position),
+            _ => None,
+        });
+        let weather = fold_feature(PropertyAggregations::default(), feature).into_frame(settings);
+        Self { station_id, name, location, weather }
+    }
+
+    /// Identifies the observing station by its reported id, or else by the last segment of its
+    /// station URL.
+    fn station_id(feature: &Feature) -> Option<String> {
+        if let Some(station_id) = feature.property("stationId").and_then(|id| id.as_str()) {
+            return Some(station_id.to_string());
+        }
+
+        feature
+            .property("station")
+            .and_then(|station| station.as_str())
+            .and_then(|station| station.trim_end_matches('/').rsplit('/').next())
+            .filter(|station_id| !station_id.is_empty())
+            .map(|station_id| station_id.to_string())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StationLocation {
+    pub longitude: Decimal,
+    pub latitude: Decimal,
+}
+
+impl StationLocation {
+    pub(super) fn from_position(position: &[f64]) -> Option<Self> {
+        match position {
+            [longitude, latitude, ..] => Some(Self {
+                longitude: Decimal::from_f64(*longitude)?,
+                latitude: Decimal::from_f64(*latitude)?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn observed_at(feature: &Feature) -> Option<Timestamp> {
+    feature
+        .property("timestamp")
+        .and_then(|ts| ts.as_str())
+        .and_then(Timestamp::parse)
+}
+
 #[derive(Debug)]
 struct PropertyAggregations {
-    timestamp: Timestamp,
-    properties: HashMap<QuantitativeProperty, QuantitativeAggregation>,
+    /// Time of the latest observation aggregated.
+    timestamp: Option<Timestamp>,
+    properties: HashMap<QuantitativeProperty, Vec<PropertySample>>,
 }
 
 impl Default for PropertyAggregations {
     fn default() -> Self {
         Self {
-            timestamp: Timestamp::now_utc(),
+            timestamp: None,
             properties: HashMap::with_capacity(QuantitativeProperty::VARIANTS.len()),
         }
     }
 }
 
 impl PropertyAggregations {
-    pub fn property(&self, q_prop: &QuantitativeProperty) -> Option<QuantitativeValue> {
-        self.properties.get(q_prop).cloned().map(|v| v.into())
+    /// Aggregates the property's samples at or above the quality floor with the property's
+    /// aggregation strategy.
+    pub fn property(
+        &self, q_prop: &QuantitativeProperty, settings: &AggregationSettings,
+    ) -> Option<QuantitativeValue> {
+        let samples = self.samples(q_prop, settings)?;
+        settings.method_for(*q_prop).strategy().aggregate(&samples)
     }
-}
 
-impl From<PropertyAggregations> for WeatherFrame {
-    fn from(agg: PropertyAggregations) -> Self {
-        Self {
-            timestamp: agg.timestamp,
-            temperature: agg.property(&QuantitativeProperty::Temperature),
-            dewpoint: agg.property(&QuantitativeProperty::Dewpoint),
-            wind_direction: agg.property(&QuantitativeProperty::WindDirection),
-            wind_speed: agg.property(&QuantitativeProperty::WindSpeed),
-            wind_gust: agg.property(&QuantitativeProperty::WindGust),
-            barometric_pressure: agg.property(&QuantitativeProperty::BarometricPressure),
-            sea_level_pressure: agg.property(&QuantitativeProperty::SeaLevelPressure),
-            visibility: agg.property(&QuantitativeProperty::Visibility),
-            max_temperature_last_24_hours: agg
-                .property(&QuantitativeProperty::MaxTemperatureLast24Hours),
-            min_temperature_last_24_hours: agg
-                .property(&QuantitativeProperty::MinTemperatureLast24Hours),
-            precipitation_last_hour: agg.property(&QuantitativeProperty::PrecipitationLastHour),
-            precipitation_last_3_hours: agg
-                .property(&QuantitativeProperty::PrecipitationLast3Hours),
-            precipitation_last_6_hours: agg
-                .property(&QuantitativeProperty::PrecipitationLast6Hours),
-            relative_humidity: agg.property(&QuantitativeProperty::RelativeHumidity),
-            wind_chill: agg.property(&QuantitativeProperty::WindChill),
-            heat_index: agg.property(&QuantitativeProperty::HeatIndex),
+    /// Whether the directional property's samples cancel out so that no direction prevails.
+    fn is_variable(&self, q_prop: &QuantitativeProperty, settings: &AggregationSettings) -> bool {
+        settings.method_for(*q_prop) == AggregationMethod::CircularMean
+            && self
+                .samples(q_prop, settings)
+                .is_some_and(|samples| CircularMean::is_variable(&samples))
+    }
+
+    fn samples(
+        &self, q_prop: &QuantitativeProperty, settings: &AggregationSettings,
+    ) -> Option<Vec<PropertySample>> {
+        let samples = self
+            .properties
+            .get(q_prop)?
+            .iter()
+            .filter(|s| settings.quality_floor.map_or(true, |floor| floor <= s.quality_control))
+            .cloned()
+            .collect();
+        Some(samples)
+    }
+
+    pub fn into_frame(self, settings: &AggregationSettings) -> WeatherFrame {
+        WeatherFrame {
+            timestamp: self.timestamp.unwrap_or_else(Timestamp::now_utc),
+            temperature: self.property(&QuantitativeProperty::Temperature, settings),
+            dewpoint: self.property(&QuantitativeProperty::Dewpoint, settings),
+            wind_direction: self.property(&QuantitativeProperty::WindDirection, settings),
+            wind_direction_variable: self
+                .is_variable(&QuantitativeProperty::WindDirection, settings),
+            wind_speed: self.property(&QuantitativeProperty::WindSpeed, settings),
+            wind_gust: self.property(&QuantitativeProperty::WindGust, settings),
+            barometric_pressure: self.property(&QuantitativeProperty::BarometricPressure, settings),
+            sea_level_pressure: self.property(&QuantitativeProperty::SeaLevelPressure, settings),
+            visibility: self.property(&QuantitativeProperty::Visibility, settings),
+            max_temperature_last_24_hours: self
+                .property(&QuantitativeProperty::MaxTemperatureLast24Hours, settings),
+            min_temperature_last_24_hours: self
+                .property(&QuantitativeProperty::MinTemperatureLast24Hours, settings),
+            precipitation_last_hour: self
+                .property(&QuantitativeProperty::PrecipitationLastHour, settings),
+            precipitation_last_3_hours: self
+                .property(&QuantitativeProperty::PrecipitationLast3Hours, settings),
+            precipitation_last_6_hours: self
+                .property(&QuantitativeProperty::PrecipitationLast6Hours, settings),
+            relative_humidity: self.property(&QuantitativeProperty::RelativeHumidity, settings),
+            wind_chill: self.property(&QuantitativeProperty::WindChill, settings),
+            heat_index: self.property(&QuantitativeProperty::HeatIndex, settings),
             // temperature:None,
             // dewpoint: None,
             // wind_direction: None,
@@ -137,11 +327,16 @@ impl From<PropertyAggregations> for WeatherFrame {
 }
 
 // #[tracing::instrument(level = "trace", skip(feature))]
-fn fold_feature(mut acc: PropertyAggregations, feature: Feature) -> PropertyAggregations {
+fn fold_feature(mut acc: PropertyAggregations, feature: &Feature) -> PropertyAggregations {
     if feature.properties.is_none() {
         return acc;
     }
 
+    let observed_at = observed_at(feature);
+    if let Some(timestamp) = observed_at {
+        acc.timestamp = Some(acc.timestamp.map_or(timestamp, |latest| latest.max(timestamp)));
+    }
+
     // debug!(
     //     "QUANTITATIVE_PROPERTIES = {:?}",
     //     QuantitativeProperty::iter()
@@ -152,8 +347,6 @@ fn fold_feature(mut acc: PropertyAggregations, feature: Feature) -> PropertyAggr
     //         .collect::<Vec<_>>()
     // );
 
-    // let acc_props: &mut HashMap<QuantitativeProperty, QuantitativeAggregation> = &mut acc.properties;
-
     for q_prop in QuantitativeProperty::iter() {
         let prop_name: &'static str = q_prop.into();
         // debug!(
@@ -162,13 +355,16 @@ fn fold_feature(mut acc: PropertyAggregations, feature: Feature) -> PropertyAggr
         // );
         if let Some(property) = feature.property(prop_name) {
             match serde_json::from_value::<PropertyDetail>(property.clone()) {
-                Ok(detail) => {
+                Ok(PropertyDetail { value: Some(value), unit_code, quality_control }) => {
                     // debug!("quantitative_properties: property detail = {detail:?}");
-                    acc.properties
-                        .entry(q_prop)
-                        .and_modify(|prop_agg| prop_agg.add_detail(detail.clone()))
-                        .or_insert(QuantitativeAggregation::new(detail));
+                    acc.properties.entry(q_prop).or_default().push(PropertySample {
+                        value,
+                        unit_code: unit_code.into(),
+                        quality_control: quality_control.unwrap_or(QualityControl::X),
+                        timestamp: observed_at,
+                    });
                 },
+                Ok(_) => {},
                 Err(err) => {
                     tracing::error!(error=?err, "failed to parse property detail: {property:?}");
                 },
@@ -215,6 +411,35 @@ pub enum QuantitativeProperty {
     HeatIndex,
 }
 
+impl QuantitativeProperty {
+    /// Whether the property is a direction in degrees, which must be aggregated circularly.
+    pub const fn is_directional(&self) -> bool {
+        matches!(self, Self::WindDirection)
+    }
+
+    /// Snake case name of the property, matching the `WeatherFrame` field and storage columns.
+    pub fn snake_case_name(&self) -> &'static str {
+        match self {
+            Self::Temperature => "temperature",
+            Self::Dewpoint => "dewpoint",
+            Self::WindDirection => "wind_direction",
+            Self::WindSpeed => "wind_speed",
+            Self::WindGust => "wind_gust",
+            Self::BarometricPressure => "barometric_pressure",
+            Self::SeaLevelPressure => "sea_level_pressure",
+            Self::Visibility => "visibility",
+            Self::MaxTemperatureLast24Hours => "max_temperature_last_24_hours",
+            Self::MinTemperatureLast24Hours => "min_temperature_last_24_hours",
+            Self::PrecipitationLastHour => "precipitation_last_hour",
+            Self::PrecipitationLast3Hours => "precipitation_last_3_hours",
+            Self::PrecipitationLast6Hours => "precipitation_last_6_hours",
+            Self::RelativeHumidity => "relative_humidity",
+            Self::WindChill => "wind_chill",
+            Self::HeatIndex => "heat_index",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct PropertyDetail {
@@ -226,105 +451,3 @@ struct PropertyDetail {
     #[serde(default)]
     quality_control: Option<QualityControl>,
 }
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct QuantitativeAggregation {
-    count: usize,
-    value_sum: Decimal,
-    max_value: Decimal,
-    min_value: Decimal,
-    pub unit_code: Cow<'static, str>,
-    pub quality_control: QualityControl,
-}
-
-impl QuantitativeAggregation {
-    pub fn new(detail: PropertyDetail) -> Self {
-        Self {
-            count: 1,
-            value_sum: detail.value.unwrap_or_default(),
-            max_value: detail.value.unwrap_or_default(),
-            min_value: detail.value.unwrap_or_default(),
-            unit_code: detail.unit_code.into(),
-            quality_control: detail.quality_control.unwrap_or(QualityControl::X),
-        }
-    }
-
-    pub fn average_value(&self) -> Decimal {
-        self.value_sum / try_usize_to_decimal(self.count)
-    }
-
-    pub fn add_detail(&mut self, detail: PropertyDetail) {
-        // combination strategy:
-        // lessor rhs quality control => ignore value
-        // same rhs quality control => combine avg and min/max
-        // higher rhs quality control => reset aggregation with rhs
-
-        if let Some((value, quality)) = detail.value.zip(detail.quality_control) {
-            match self.quality_control.cmp(&quality) {
-                Ordering::Less => (),
-
-                Ordering::Greater => {
-                    self.count = 1;
-                    self.value_sum = value;
-                    self.max_value = value;
-                    self.min_value = value;
-                    self.quality_control = quality;
-                },
-
-                Ordering::Equal => {
-                    self.count += 1;
-                    self.value_sum += value;
-                    self.max_value = value.max(self.max_value);
-                    self.min_value = value.min(self.min_value);
-                },
-            }
-        }
-    }
-}
-
-#[inline]
-fn try_usize_to_decimal(value: usize) -> Decimal {
-    Decimal::from(value)
-}
-
-// impl std::ops::Add<PropertyDetail> for QuantitativeAggregation {
-//     type Output = Self;
-//
-//     fn add(self, rhs: PropertyDetail) -> Self::Output {
-//         // combination strategy:
-//         // lessor rhs quality control => ignore value
-//         // same rhs quality control => combine avg and min/max
-//         // higher rhs quality control => reset aggregation with rhs
-//
-//         match self.quality_control.cmp(&rhs.quality_control) {
-//             Ordering::Less => self,
-//             Ordering::Greater => Self {
-//                 count: 1,
-//                 value_sum: rhs.value,
-//                 max_value: rhs.value,
-//                 min_value: rhs.value,
-//                 quality_control: rhs.quality_control,
-//                 ..self
-//             },
-//             Ordering::Equal => Self {
-//                 count: self.count + 1,
-//                 value_sum: self.value_sum + rhs.value,
-//                 max_value: self.max_value.max(rhs.value),
-//                 min_value: self.min_value.min(rhs.value),
-//                 ..self
-//             },
-//         }
-//     }
-// }
-
-impl From<QuantitativeAggregation> for QuantitativeValue {
-    fn from(agg: QuantitativeAggregation) -> Self {
-        Self {
-            value: agg.average_value(),
-            max_value: agg.max_value,
-            min_value: agg.min_value,
-            unit_code: agg.unit_code,
-            quality_control: agg.quality_control,
-        }
-    }
-}
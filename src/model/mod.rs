@@ -1,10 +1,31 @@
+mod aggregation;
+pub mod cap;
+mod circular;
+mod forecast;
 mod frame;
+mod point;
 pub mod registrar;
 mod tracing_processor;
+mod units;
 pub mod weather;
-
-pub use frame::WeatherFrame;
+pub mod webhook;
+pub mod zone_metadata;
+
+pub use aggregation::{
+    AggregationMethod, AggregationStrategy, CircularMean, HighestQuality, Mean, Median, MostRecent,
+    PropertySample,
+};
+pub use circular::{CircularStatistics, MeanDirection, VARIABLE_RESULTANT_LENGTH};
+pub use forecast::{
+    CompassPoint, ForecastDetail, ForecastQuantity, HourlyForecast, HourlyForecastPeriod,
+    TemperatureTrend, TemperatureUnit, WindSpeed, WindSpeedUnit, ZoneForecast,
+};
+pub use frame::{
+    ObservationReport, QuantitativeProperty, StationLocation, StationObservation, WeatherFrame,
+};
+pub use point::{GeoPoint, Gridpoint, NearbyStation, PointLocation};
 pub use tracing_processor::TracingProcessor;
+pub use units::{Dimension, Unit, UnitSystem};
 
 use crate::errors::WeatherError;
 use chrono::{DateTime, Utc};
@@ -37,6 +58,8 @@ pub fn transpose_result<T, E>(
     Clone,
     PartialEq,
     Eq,
+    PartialOrd,
+    Ord,
     Hash,
     IntoParams,
     sqlx::Type,
@@ -160,12 +183,16 @@ impl core::borrow::Borrow<str> for LocationZoneCode {
     }
 }
 
+/// NOAA zone types, named as they appear in the `/zones/{type}/{zoneId}` API paths.
 #[derive(
     Debug,
+    Default,
     Copy,
     Clone,
     PartialEq,
     Eq,
+    PartialOrd,
+    Ord,
     Hash,
     Display,
     IntoStaticStr,
@@ -175,12 +202,98 @@ impl core::borrow::Borrow<str> for LocationZoneCode {
     Serialize,
     Deserialize,
 )]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
 pub enum LocationZoneType {
     Public,
     County,
+    #[default]
     Forecast,
 }
 
+impl<'q, DB> sqlx::Decode<'q, DB> for LocationZoneType
+where
+    String: sqlx::Decode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'q>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let value = <String as sqlx::Decode<DB>>::decode(value)?;
+        let value = LocationZoneType::from_str(&value)?;
+        Ok(value)
+    }
+}
+
+impl<'q, DB> sqlx::Encode<'q, DB> for LocationZoneType
+where
+    String: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self, buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<DB>>::encode_by_ref(&self.to_string(), buf)
+    }
+}
+
+impl<DB> sqlx::Type<DB> for LocationZoneType
+where
+    String: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+}
+
+/// A location zone code qualified by its NOAA zone type.
+///
+/// Deserializes from either the structured form or a bare zone code, which predates zone types
+/// and is taken as a forecast zone.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, Serialize, Deserialize)]
+#[serde(from = "LocationZoneRep")]
+pub struct LocationZone {
+    pub code: LocationZoneCode,
+    pub zone_type: LocationZoneType,
+}
+
+impl fmt::Display for LocationZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.zone_type, self.code)
+    }
+}
+
+impl LocationZone {
+    pub fn new(code: impl Into<LocationZoneCode>, zone_type: LocationZoneType) -> Self {
+        Self { code: code.into(), zone_type }
+    }
+
+    pub fn forecast(code: impl Into<LocationZoneCode>) -> Self {
+        Self::new(code, LocationZoneType::Forecast)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LocationZoneRep {
+    Code(LocationZoneCode),
+    Zone {
+        code: LocationZoneCode,
+        #[serde(default)]
+        zone_type: LocationZoneType,
+    },
+}
+
+impl From<LocationZoneRep> for LocationZone {
+    fn from(rep: LocationZoneRep) -> Self {
+        match rep {
+            LocationZoneRep::Code(code) => Self::forecast(code),
+            LocationZoneRep::Zone { code, zone_type } => Self { code, zone_type },
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuantitativeValue {
@@ -288,68 +401,13 @@ impl Ord for QualityControl {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
-pub struct ZoneForecast {
-    // #[serde(deserialize_with = "ZoneForecast::deserialize_zone_from_url")]
-    pub zone_code: String,
-
-    pub updated: DateTime<Utc>,
-
-    pub periods: Vec<ForecastDetail>,
-}
-
-impl TryFrom<Feature> for ZoneForecast {
-    type Error = WeatherError;
-
-    fn try_from(feature: Feature) -> Result<Self, Self::Error> {
-        let zone_code = feature
-            .property("zone")
-            .and_then(|p| p.as_str())
-            .ok_or_else(|| Self::Error::MissingFeature("zone".to_string()))?
-            .to_string();
-
-        let updated = Utc::now();
-
-        let periods: Vec<Result<ForecastDetail, Self::Error>> = feature
-            .property("periods")
-            .and_then(|p| p.as_array())
-            .cloned()
-            .map(|ps| {
-                ps.into_iter()
-                    .map(|detail| serde_json::from_value(detail).map_err(|err| err.into()))
-                    .collect()
-            })
-            .ok_or_else(|| Self::Error::MissingFeature("periods".to_string()))?;
-
-        let nr_periods = periods.len();
-        let periods: Vec<ForecastDetail> =
-            periods.into_iter().try_fold(Vec::with_capacity(nr_periods), |acc, res| {
-                match (acc, res) {
-                    (mut acc_0, Ok(p)) => {
-                        acc_0.push(p);
-                        Ok(acc_0)
-                    },
-
-                    (_, Err(err)) => Err(err),
-                }
-            })?;
-
-        Ok(Self { zone_code, updated, periods })
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ForecastDetail {
-    pub name: String,
-
-    #[serde(alias = "detailedForecast")]
-    pub forecast: String,
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WeatherAlert {
+    /// NOAA identifier of the alert message, e.g., `urn:oid:2.49.0.1.840.0...`.
+    #[serde(default)]
+    pub id: String,
+
     pub affected_zones: Vec<LocationZoneCode>,
     pub status: AlertStatus,
     pub message_type: AlertMessageType,
@@ -399,6 +457,27 @@ pub struct WeatherAlert {
     pub response: AlertResponse,
 }
 
+impl WeatherAlert {
+    /// The alert remains in force until the expected end of its subject event if given;
+    /// otherwise, until its information expires.
+    pub fn in_force_until(&self) -> DateTime<Utc> {
+        self.ends.unwrap_or(self.expires)
+    }
+
+    #[inline]
+    pub fn is_expired(&self, as_of: DateTime<Utc>) -> bool {
+        self.in_force_until() <= as_of
+    }
+
+    /// The alert as a GeoJSON feature identified by its alert id over the area of the affected
+    /// zones, if known.
+    pub fn to_feature(
+        &self, geometry: Option<geojson::Geometry>,
+    ) -> Result<Feature, serde_json::Error> {
+        zone_metadata::feature_of(self.id.clone(), geometry, self)
+    }
+}
+
 impl TryFrom<Feature> for WeatherAlert {
     type Error = WeatherError;
 
@@ -419,6 +498,7 @@ impl TryFrom<Feature> for WeatherAlert {
         debug!("DMR: affected zones for current alert geo feature: {affected_zones:?}");
 
         Ok(Self {
+            id: extract.property("id")?,
             affected_zones,
             status: extract.property("status")?,
             message_type: extract.property("messageType")?,
@@ -649,7 +729,7 @@ pub enum AlertResponse {
     None,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, ToSchema, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, ToSchema, Serialize, Deserialize)]
 // #[schema(example = json!("360.0"))]
 #[serde(transparent)]
 #[repr(transparent)]
@@ -697,53 +777,34 @@ impl approx::RelativeEq for Direction {
     }
 }
 
-#[allow(dead_code)]
+/// Circular mean of the directions in degrees normalized to [0, 360), or `None` if there are no
+/// directions or they cancel out so no direction prevails.
 pub fn average_direction(directions: &[Direction]) -> Option<Direction> {
-    if directions.is_empty() {
-        return None;
-    }
-    let n = directions.len() as f32;
-    // let sum_x = directions.iter().map(|d| d.0.to_radians().cos()).sum::<f32>();
-    // let sum_y = directions.iter().map(|d| d.0.to_radians().sin()).sum::<f32>();
-    // let avg_x = sum_x / n;
-    // let avg_y = sum_y / n;
-    // Some(Direction((avg_y.atan2(avg_x).to_degrees() + 360.0) % 360.0))
-    let sum_c = directions.iter().map(|d| d.0.to_radians().cos()).sum::<f32>();
-    let sum_s = directions.iter().map(|d| d.0.to_radians().sin()).sum::<f32>();
-    let avg_c = sum_c / n;
-    let avg_s = sum_s / n;
-    let d = avg_s.atan2(avg_c);
-    // let d = match (avg_s, avg_c) {
-    //     (s, c) if s > 0.0 && c > 0.0 => { s.atan2(c) },
-    //     (s, c) if c < 0.0 => { s.atan2(c) + PI},
-    //     (s, c) if s < 0.0 && c > 0.0 => { s.atan2(c) + 2.0 * PI },
-    //     _ => 0.0,
-    // };
-    Some(Direction(d.to_degrees()))
-    // Some(Direction((avg_y.atan2(avg_x).to_degrees() + 360.0) % 360.0))
+    CircularStatistics::of(directions)?.prevailing()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
-    use claims::assert_some;
+    use claims::{assert_none, assert_some};
     use pretty_assertions::assert_eq;
-    // - property test for sane averages
-    // proptest! {
-    //     #[test]
-    //     fn test_average_direction(directions in vec(any::<f32>().prop_filter("valid angle", |d| *d>=0.0 && *d<=360.0), 0..10)) {
-    //         let directions: Vec<Direction> = directions.into_iter().map(Direction::new).collect();
-    //         let result = average_direction(directions.as_slice());
-    //         prop_assert!(
-    //             result.map(|r| r.into()).map(|avg: f32| avg >= 0.0 && avg <= 360.0).unwrap_or_else(|| directions.is_empty())
-    //             // match result {
-    //             //     None => directions.is_empty(),
-    //             //     Some(average) => average >= 0.0 && average <= 360.0,
-    //             // }
-    //         );
-    //     }
-    // }
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_average_direction(directions in prop::collection::vec(0.0_f32..=360.0, 0..10)) {
+            let directions: Vec<Direction> = directions.into_iter().map(Direction::new).collect();
+            let result = average_direction(directions.as_slice());
+            if directions.is_empty() {
+                prop_assert!(result.is_none());
+            }
+            if let Some(average) = result {
+                let average: f32 = average.into();
+                prop_assert!((0.0..360.0).contains(&average), "average: {average}");
+            }
+        }
+    }
 
     #[test]
     fn test_average_direction_single() {
@@ -755,8 +816,7 @@ mod tests {
     #[test]
     fn test_average_direction_opposite() {
         let directions = [Direction(90.0), Direction(270.0)];
-        let actual = assert_some!(average_direction(&directions));
-        assert_relative_eq!(actual, Direction(180.0), epsilon = 1e-9);
+        assert_none!(average_direction(&directions));
     }
 
     #[test]
@@ -767,15 +827,15 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn test_average_direction_three() {
         let directions = [Direction(0.0), Direction(120.0), Direction(240.0)];
-        let actual = assert_some!(average_direction(&directions));
-        assert_relative_eq!(actual, Direction(160.0), epsilon = 1e-9);
+        assert_none!(average_direction(&directions));
+
+        let stats = assert_some!(CircularStatistics::of(&directions));
+        assert_eq!(stats.mean, MeanDirection::Variable);
     }
 
     #[test]
-    #[ignore]
     fn test_average_direction_multiple() {
         let directions = [
             Direction(0.0),
@@ -784,11 +844,10 @@ mod tests {
             Direction(360.0),
         ];
         let actual = assert_some!(average_direction(&directions));
-        assert_relative_eq!(actual, Direction(45.0), epsilon = 1e-9);
+        assert_relative_eq!(actual, Direction(32.2356), epsilon = 1e-3);
     }
 
     #[test]
-    #[ignore]
     fn test_average_direction_across_0_360() {
         let directions = [
             Direction(0.0),
@@ -805,4 +864,29 @@ mod tests {
         let directions: &[Direction] = &[];
         assert_eq!(average_direction(directions), None);
     }
+
+    #[test]
+    fn test_location_zone_type_rep() {
+        assert_eq!(LocationZoneType::County.to_string(), "county");
+        assert_eq!(<&'static str>::from(LocationZoneType::Forecast), "forecast");
+        assert_eq!(
+            LocationZoneType::from_str("Public").unwrap(),
+            LocationZoneType::Public
+        );
+        assert_eq!(
+            serde_json::to_string(&LocationZoneType::County).unwrap(),
+            r#""county""#
+        );
+    }
+
+    #[test]
+    fn test_location_zone_serde() {
+        let zone = LocationZone::new("WAC033", LocationZoneType::County);
+        let json = serde_json::to_string(&zone).unwrap();
+        assert_eq!(json, r#"{"code":"WAC033","zone_type":"county"}"#);
+        assert_eq!(serde_json::from_str::<LocationZone>(&json).unwrap(), zone);
+
+        let legacy: LocationZone = serde_json::from_str(r#""WAZ558""#).unwrap();
+        assert_eq!(legacy, LocationZone::forecast("WAZ558"));
+    }
 }
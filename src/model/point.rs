@@ -0,0 +1,355 @@
+use super::{LocationZoneCode, PropertyExtractor, StationLocation};
+use crate::errors::WeatherError;
+use geojson::{Feature, FeatureCollection, Geometry, PolygonType};
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use std::fmt;
+use std::str::FromStr;
+
+/// Latitude and longitude of a location in decimal degrees. NOAA resolves points to at most four
+/// decimal places, so coordinates are rounded to four places, which also makes nearby lookups
+/// share a resolution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, ToSchema, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub latitude: Decimal,
+    pub longitude: Decimal,
+}
+
+impl GeoPoint {
+    pub const DECIMAL_PLACES: u32 = 4;
+
+    pub fn new(latitude: Decimal, longitude: Decimal) -> Result<Self, WeatherError> {
+        if !(dec!(-90)..=dec!(90)).contains(&latitude) {
+            return Err(WeatherError::InvalidPoint(format!(
+                "latitude {latitude} is not between -90 and 90"
+            )));
+        }
+
+        if !(dec!(-180)..=dec!(180)).contains(&longitude) {
+            return Err(WeatherError::InvalidPoint(format!(
+                "longitude {longitude} is not between -180 and 180"
+            )));
+        }
+
+        Ok(Self {
+            latitude: latitude.round_dp(Self::DECIMAL_PLACES).normalize(),
+            longitude: longitude.round_dp(Self::DECIMAL_PLACES).normalize(),
+        })
+    }
+
+    /// Point representative of an area, such as a zone: the centroid of the geometry's largest
+    /// polygon, or `None` if the geometry has no polygon.
+    pub fn centroid_of(geometry: &Geometry) -> Option<Self> {
+        let polygons: Vec<&PolygonType> = match &geometry.value {
+            geojson::Value::Polygon(polygon) => vec![polygon],
+            geojson::Value::MultiPolygon(polygons) => polygons.iter().collect(),
+            _ => return None,
+        };
+
+        let (longitude, latitude) = polygons
+            .into_iter()
+            .filter_map(|polygon| polygon.first())
+            .filter_map(|exterior| ring_centroid(exterior))
+            .max_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs))
+            .map(|(centroid, _)| centroid)?;
+
+        Self::new(Decimal::from_f64(latitude)?, Decimal::from_f64(longitude)?).ok()
+    }
+}
+
+/// Centroid of a linear ring, as `(longitude, latitude)`, and the ring's area in square degrees.
+/// A degenerate ring without area is represented by the mean of its positions.
+fn ring_centroid(ring: &[Vec<f64>]) -> Option<((f64, f64), f64)> {
+    let positions: Vec<(f64, f64)> = ring
+        .iter()
+        .filter_map(|position| Some((*position.first()?, *position.get(1)?)))
+        .collect();
+    if positions.is_empty() {
+        return None;
+    }
+
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for (&(x0, y0), &(x1, y1)) in positions.iter().zip(positions.iter().cycle().skip(1)) {
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        cx += (x0 + x1) * cross;
+        cy += (y0 + y1) * cross;
+    }
+    area /= 2.0;
+
+    if area.abs() < f64::EPSILON {
+        let n = positions.len() as f64;
+        let (sum_x, sum_y) = positions.iter().fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x, sy + y));
+        return Some(((sum_x / n, sum_y / n), 0.0));
+    }
+
+    Some(((cx / (6.0 * area), cy / (6.0 * area)), area.abs()))
+}
+
+/// Formats as NOAA identifies points, e.g., `39.7456,-97.0892`.
+impl fmt::Display for GeoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.latitude, self.longitude)
+    }
+}
+
+impl FromStr for GeoPoint {
+    type Err = WeatherError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let invalid = || WeatherError::InvalidPoint(format!("{rep:?} is not `<lat>,<lon>`"));
+        let (latitude, longitude) = rep.split_once(',').ok_or_else(invalid)?;
+        let latitude = Decimal::from_str(latitude.trim()).map_err(|_| invalid())?;
+        let longitude = Decimal::from_str(longitude.trim()).map_err(|_| invalid())?;
+        Self::new(latitude, longitude)
+    }
+}
+
+/// NOAA forecast office and grid cell covering a point, as used by the gridpoint endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gridpoint {
+    /// Forecast office identifier, e.g., `TOP`.
+    pub office: String,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Gridpoint {
+    /// The gridpoint NOAA's `/points/{point}` feature resolves the point to.
+    pub fn from_point_feature(point_feature: &Feature) -> Result<Self, WeatherError> {
+        let extract = PropertyExtractor::new("gridpoint", point_feature);
+        Ok(Self {
+            office: extract.property("gridId")?,
+            x: extract.property("gridX")?,
+            y: extract.property("gridY")?,
+        })
+    }
+}
+
+/// Formats as the office and grid cell of the gridpoint endpoints, e.g., `TOP/32,81`.
+impl fmt::Display for Gridpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{},{}", self.office, self.x, self.y)
+    }
+}
+
+impl FromStr for Gridpoint {
+    type Err = WeatherError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let invalid = || WeatherError::InvalidPoint(format!("{rep:?} is not `<office>/<x>,<y>`"));
+        let (office, cell) = rep.split_once('/').ok_or_else(invalid)?;
+        let (x, y) = cell.split_once(',').ok_or_else(invalid)?;
+        if office.is_empty() || !office.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            office: office.to_uppercase(),
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// An observation station near a point.
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearbyStation {
+    pub station_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<StationLocation>,
+}
+
+impl NearbyStation {
+    fn from_feature(feature: &Feature) -> Result<Self, WeatherError> {
+        let extract = PropertyExtractor::new("nearby_station", feature);
+        let location = feature.geometry.as_ref().and_then(|geometry| match &geometry.value {
+            geojson::Value::Point(position) => StationLocation::from_position(position),
+            _ => None,
+        });
+
+        Ok(Self {
+            station_id: extract.property("stationIdentifier")?,
+            name: string_property(feature, "name"),
+            location,
+        })
+    }
+}
+
+/// What NOAA resolves a point to: the zones and forecast gridpoint covering the point and the
+/// observation stations nearest it.
+#[derive(Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointLocation {
+    pub point: GeoPoint,
+    pub forecast_zone: LocationZoneCode,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub county_zone: Option<LocationZoneCode>,
+
+    pub gridpoint: Gridpoint,
+
+    /// IANA time zone of the point, e.g., `America/Chicago`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+
+    /// Observation stations nearest the point, nearest first.
+    #[serde(default)]
+    pub stations: Vec<NearbyStation>,
+}
+
+impl PointLocation {
+    /// Assembles the location from NOAA's `/points/{point}` feature and the point's observation
+    /// stations.
+    pub fn from_geojson(
+        point: GeoPoint, point_feature: &Feature, stations: &FeatureCollection,
+    ) -> Result<Self, WeatherError> {
+        let extract = PropertyExtractor::new("point_location", point_feature);
+
+        let forecast_zone: String = extract.property("forecastZone")?;
+        let county_zone = string_property(point_feature, "county")
+            .map(LocationZoneCode::parse)
+            .transpose()?;
+
+        let stations: Result<Vec<_>, _> =
+            stations.features.iter().map(NearbyStation::from_feature).collect();
+
+        Ok(Self {
+            point,
+            forecast_zone: LocationZoneCode::parse(forecast_zone)?,
+            county_zone,
+            gridpoint: Gridpoint::from_point_feature(point_feature)?,
+            time_zone: string_property(point_feature, "timeZone"),
+            stations: stations?,
+        })
+    }
+}
+
+fn string_property(feature: &Feature, property: &str) -> Option<String> {
+    feature.property(property).and_then(|p| p.as_str()).map(|p| p.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::{assert_err, assert_none, assert_ok, assert_some};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_geo_point_rep() {
+        let point = assert_ok!(GeoPoint::from_str("39.74561234, -97.0892"));
+        assert_eq!(point.latitude, dec!(39.7456));
+        assert_eq!(point.to_string(), "39.7456,-97.0892");
+        assert_eq!(
+            assert_ok!(GeoPoint::from_str("47.6100,-122.3000")).to_string(),
+            "47.61,-122.3"
+        );
+
+        assert_err!(GeoPoint::from_str("91,0"));
+        assert_err!(GeoPoint::from_str("0,-180.5"));
+        assert_err!(GeoPoint::from_str("39.7456"));
+    }
+
+    #[test]
+    fn test_gridpoint_rep() {
+        let gridpoint = assert_ok!(Gridpoint::from_str("sew/125,68"));
+        assert_eq!(
+            gridpoint,
+            Gridpoint { office: "SEW".to_string(), x: 125, y: 68 }
+        );
+        assert_eq!(gridpoint.to_string(), "SEW/125,68");
+
+        assert_err!(Gridpoint::from_str("SEW/125"));
+        assert_err!(Gridpoint::from_str("125,68"));
+        assert_err!(Gridpoint::from_str("SEW/-1,68"));
+    }
+
+    #[test]
+    fn test_centroid_of_largest_polygon() {
+        let square = |x: f64, y: f64, side: f64| {
+            vec![vec![
+                vec![x, y],
+                vec![x + side, y],
+                vec![x + side, y + side],
+                vec![x, y + side],
+                vec![x, y],
+            ]]
+        };
+
+        let geometry = Geometry::new(geojson::Value::MultiPolygon(vec![
+            square(-122.5, 47.5, 0.1),
+            square(-97.5, 39.5, 0.5),
+        ]));
+        let centroid = assert_some!(GeoPoint::centroid_of(&geometry));
+        assert_eq!(centroid.to_string(), "39.75,-97.25");
+
+        let point = Geometry::new(geojson::Value::Point(vec![-97.0892, 39.7456]));
+        assert_none!(GeoPoint::centroid_of(&point));
+    }
+
+    #[test]
+    fn test_point_location_from_geojson() {
+        let point = assert_ok!(GeoPoint::new(dec!(39.7456), dec!(-97.0892)));
+        let point_feature: Feature = assert_ok!(serde_json::from_value(serde_json::json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [-97.0892, 39.7456] },
+            "properties": {
+                "gridId": "TOP",
+                "gridX": 32,
+                "gridY": 81,
+                "forecastZone": "https://api.weather.gov/zones/forecast/KSZ009",
+                "county": "https://api.weather.gov/zones/county/KSC201",
+                "timeZone": "America/Chicago"
+            }
+        })));
+        let stations: FeatureCollection = assert_ok!(serde_json::from_value(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [-97.1, 39.8] },
+                    "properties": { "stationIdentifier": "KCNK", "name": "Concordia" }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": null,
+                    "properties": { "stationIdentifier": "KMYZ" }
+                }
+            ]
+        })));
+
+        let actual = assert_ok!(PointLocation::from_geojson(
+            point,
+            &point_feature,
+            &stations
+        ));
+        assert_eq!(actual.forecast_zone, LocationZoneCode::new("KSZ009"));
+        assert_eq!(actual.county_zone, Some(LocationZoneCode::new("KSC201")));
+        assert_eq!(actual.gridpoint.to_string(), "TOP/32,81");
+        assert_eq!(actual.time_zone.as_deref(), Some("America/Chicago"));
+        assert_eq!(
+            actual.stations,
+            vec![
+                NearbyStation {
+                    station_id: "KCNK".to_string(),
+                    name: Some("Concordia".to_string()),
+                    location: Some(StationLocation {
+                        longitude: dec!(-97.1),
+                        latitude: dec!(39.8)
+                    }),
+                },
+                NearbyStation {
+                    station_id: "KMYZ".to_string(),
+                    name: None,
+                    location: None
+                },
+            ]
+        );
+    }
+}
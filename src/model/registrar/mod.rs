@@ -1,18 +1,20 @@
-use crate::model::LocationZoneCode;
+use crate::model::{LocationZone, LocationZoneCode};
+use disintegrate::decision::Error as DecisionError;
 use disintegrate::serde::json::Json;
-use disintegrate::NoSnapshot;
+use disintegrate::{BoxDynError, NoSnapshot};
 use disintegrate_postgres::{PgDecisionMaker, PgEventStore};
 use std::sync::Arc;
 
 // mod processor;
 pub mod protocol;
 mod read_model;
+pub mod schedule;
 mod services;
 mod state;
 
 use crate::model::registrar::protocol::RegistrarEvent;
 pub use errors::RegistrarError;
-pub use read_model::{MonitoredLocationZonesRef, MonitoredLocationZonesView};
+pub use read_model::{MonitoredLocationZonesView, MonitoredZonesRepository, MONITORED_ZONES_TABLE};
 pub use services::RegistrarServices;
 
 pub type RegistrarEventSerde = Json<protocol::RegistrarEvent>;
@@ -29,21 +31,58 @@ pub async fn clear_monitoring(dm: &RegistrarDecisionMaker) -> Result<(), Registr
 }
 
 #[instrument(level = "debug", skip(dm), err)]
-pub async fn monitor_forecast_zone(
-    zone: LocationZoneCode, dm: &RegistrarDecisionMaker,
+pub async fn monitor_zone(
+    zone: LocationZone, dm: &RegistrarDecisionMaker,
 ) -> Result<(), RegistrarError> {
-    dm.make(protocol::MonitorForecastZone::new(zone.clone()))
+    dm.make(protocol::MonitorZone::new(zone))
         .await
         .map_err(|err| RegistrarError::Decision(Box::new(err)))?;
-    // let _handle = MonitorForecastZone::then_run(&zone, support);
     Ok(())
 }
 
+/// Number of times monitoring a zone is decided while concurrent decisions conflict with it.
+const MONITOR_ZONE_ATTEMPTS: usize = 3;
+
+/// Monitors the zone unless it is already monitored, returning whether this call started
+/// monitoring it. A call that conflicts with a concurrent registration of the zone decides again,
+/// so only one of them reports the zone as newly monitored.
+#[instrument(level = "debug", skip(dm), ret, err)]
+pub async fn ensure_zone_monitored(
+    zone: LocationZone, dm: &RegistrarDecisionMaker,
+) -> Result<bool, RegistrarError> {
+    let mut attempt = 1;
+    loop {
+        match dm.make(protocol::MonitorZone::new(zone.clone())).await {
+            Ok(_) => return Ok(true),
+            Err(DecisionError::Domain(RegistrarError::LocationZoneAlreadyMonitored(_))) => {
+                return Ok(false)
+            },
+            Err(DecisionError::StateStore(err))
+                if attempt < MONITOR_ZONE_ATTEMPTS && is_concurrency_conflict(&err) =>
+            {
+                debug!(
+                    %attempt, code = %zone.code,
+                    "monitoring zone conflicted with a concurrent decision"
+                );
+                attempt += 1;
+            },
+            Err(err) => return Err(RegistrarError::Decision(Box::new(err))),
+        }
+    }
+}
+
+fn is_concurrency_conflict(err: &BoxDynError) -> bool {
+    matches!(
+        err.downcast_ref::<disintegrate_postgres::Error>(),
+        Some(disintegrate_postgres::Error::Concurrency)
+    )
+}
+
 #[instrument(level = "debug", skip(dm), err)]
-pub async fn ignore_forecast_zone(
+pub async fn ignore_zone(
     zone: LocationZoneCode, dm: &RegistrarDecisionMaker,
 ) -> Result<(), RegistrarError> {
-    dm.make(protocol::IgnoreForecastZone::new(zone))
+    dm.make(protocol::IgnoreZone::new(zone))
         .await
         .map_err(|err| RegistrarError::Decision(Box::new(err)))?;
     Ok(())
@@ -79,9 +118,15 @@ mod errors {
         #[error("{0}")]
         UpdateWeather(#[from] crate::model::weather::update::UpdateWeatherError),
 
+        #[error("invalid update weather schedule: {0}")]
+        InvalidSchedule(String),
+
         #[error("failed to execute registrar decision: {0}")]
         Decision(#[source] BoxDynError),
 
+        #[error("{0}")]
+        Sql(#[from] sqlx::Error),
+
         #[error("{0}")]
         Postgres(#[from] disintegrate_postgres::Error),
     }
@@ -90,12 +135,13 @@ mod errors {
 pub mod support {
     use super::services::RegistrarServicesRef;
     use super::{
-        MonitoredLocationZonesRef, RegistrarDecisionMakerRef, RegistrarError, RegistrarEventStore,
+        MonitoredZonesRepository, RegistrarDecisionMakerRef, RegistrarError, RegistrarEventStore,
         RegistrarServices,
     };
     use crate::model::weather::update::UpdateWeatherServicesRef;
     use anyhow::anyhow;
     use disintegrate_postgres::{PgEventListener, PgEventListenerConfig};
+    use sqlx::PgPool;
     use std::fmt;
     use std::sync::Arc;
     use std::time::Duration;
@@ -105,7 +151,7 @@ pub mod support {
     pub struct RegistrarSupport {
         pub decision_maker: RegistrarDecisionMakerRef,
         pub event_store: RegistrarEventStore,
-        pub monitored: MonitoredLocationZonesRef,
+        pub monitored_repository: MonitoredZonesRepository,
         pub services: RegistrarServicesRef,
     }
 
@@ -125,8 +171,8 @@ pub mod support {
             err
         )]
         pub async fn new(
-            event_store: RegistrarEventStore, update_services: UpdateWeatherServicesRef,
-            task_tracker: &TaskTracker,
+            pool: PgPool, event_store: RegistrarEventStore,
+            update_services: UpdateWeatherServicesRef, task_tracker: &TaskTracker,
         ) -> Result<Self, RegistrarError> {
             let decision_maker =
                 Arc::new(disintegrate_postgres::decision_maker(event_store.clone()));
@@ -134,17 +180,19 @@ pub mod support {
 
             let services = Arc::new(RegistrarServices::full(update_services));
 
-            let monitored = super::read_model::MonitoredLocationZones::default();
+            let monitored_repository = MonitoredZonesRepository::new(pool.clone());
             warn!("DMR: RS-BBB");
 
             // let registrar_processor = Arc::new(registrar::processor::RegistrarProcessor::new());
             let event_store_0 = event_store.clone();
-            let monitored_0 = monitored.clone();
 
             task_tracker.spawn(async move {
+                let monitored_projection =
+                    super::read_model::MonitoredZonesProjection::new(pool).await?;
+
                 PgEventListener::builder(event_store_0)
                     .register_listener(
-                        monitored_0,
+                        monitored_projection,
                         PgEventListenerConfig::poller(Duration::from_millis(50)),
                     )
                     .start_with_shutdown(crate::shutdown())
@@ -159,7 +207,7 @@ pub mod support {
             Ok(Self {
                 decision_maker,
                 event_store,
-                monitored: Arc::new(monitored),
+                monitored_repository,
                 services,
             })
         }
@@ -1,27 +1,41 @@
 use super::state::Registrar;
 use crate::model::registrar::errors::RegistrarError;
-use crate::model::LocationZoneCode;
+use crate::model::{LocationZone, LocationZoneCode, LocationZoneType};
 use disintegrate::{Decision, Event};
 use strum_macros::Display;
 
 #[derive(Debug, Display, Clone, PartialEq, Eq, Event, Serialize, Deserialize)]
-// #[group(RegistrarEvent, [ForecastZoneAdded, ForecastZoneRemoved, AllForecastZonesRemoved])]
+// #[group(RegistrarEvent, [ZoneAdded, ZoneRemoved, AllZonesRemoved])]
 pub enum RegistrarEvent {
-    ForecastZoneAdded { zone: LocationZoneCode },
-    ForecastZoneRemoved { zone: LocationZoneCode },
+    ZoneAdded {
+        zone: LocationZoneCode,
+        zone_type: LocationZoneType,
+    },
+    ZoneRemoved {
+        zone: LocationZoneCode,
+    },
+    AllZonesRemoved,
+
+    // -- events recorded before the registrar tracked zone types; forecast zones are implied.
+    ForecastZoneAdded {
+        zone: LocationZoneCode,
+    },
+    ForecastZoneRemoved {
+        zone: LocationZoneCode,
+    },
     AllForecastZonesRemoved,
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct MonitorForecastZone(LocationZoneCode);
+pub struct MonitorZone(LocationZone);
 
-impl MonitorForecastZone {
-    pub fn new(zone: LocationZoneCode) -> Self {
+impl MonitorZone {
+    pub fn new(zone: LocationZone) -> Self {
         Self(zone)
     }
 }
 
-impl Decision for MonitorForecastZone {
+impl Decision for MonitorZone {
     type Event = RegistrarEvent;
     type StateQuery = Registrar;
     type Error = RegistrarError;
@@ -30,30 +44,31 @@ impl Decision for MonitorForecastZone {
         Registrar::default()
     }
 
-    #[instrument(level = "debug", name = "MonitorForecastZone::process", ret, err)]
+    #[instrument(level = "debug", name = "MonitorZone::process", ret, err)]
     fn process(&self, state: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
-        let zone = &self.0;
+        let LocationZone { code, zone_type } = &self.0;
 
-        if state.location_codes.contains(zone) {
-            return Err(RegistrarError::LocationZoneAlreadyMonitored(zone.clone()));
+        if state.zones.contains_key(code) {
+            return Err(RegistrarError::LocationZoneAlreadyMonitored(code.clone()));
         }
 
-        Ok(vec![RegistrarEvent::ForecastZoneAdded {
-            zone: self.0.clone(),
+        Ok(vec![RegistrarEvent::ZoneAdded {
+            zone: code.clone(),
+            zone_type: *zone_type,
         }])
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct IgnoreForecastZone(LocationZoneCode);
+pub struct IgnoreZone(LocationZoneCode);
 
-impl IgnoreForecastZone {
+impl IgnoreZone {
     pub fn new(zone: LocationZoneCode) -> Self {
         Self(zone)
     }
 }
 
-impl Decision for IgnoreForecastZone {
+impl Decision for IgnoreZone {
     type Event = RegistrarEvent;
     type StateQuery = Registrar;
     type Error = RegistrarError;
@@ -62,14 +77,12 @@ impl Decision for IgnoreForecastZone {
         Registrar::default()
     }
 
-    #[instrument(level = "debug", name = "ForgetForecastZone::process", ret, err)]
+    #[instrument(level = "debug", name = "IgnoreZone::process", ret, err)]
     fn process(&self, state: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
         let zone = &self.0;
 
-        if state.location_codes.contains(zone) {
-            Ok(vec![RegistrarEvent::ForecastZoneRemoved {
-                zone: zone.clone(),
-            }])
+        if state.zones.contains_key(zone) {
+            Ok(vec![RegistrarEvent::ZoneRemoved { zone: zone.clone() }])
         } else {
             Ok(Vec::default())
         }
@@ -89,8 +102,8 @@ impl Decision for ClearZoneMonitoring {
     }
 
     fn process(&self, state: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
-        if !state.location_codes.is_empty() {
-            Ok(vec![RegistrarEvent::AllForecastZonesRemoved])
+        if !state.zones.is_empty() {
+            Ok(vec![RegistrarEvent::AllZonesRemoved])
         } else {
             Ok(Vec::default())
         }
@@ -109,43 +122,68 @@ mod tests {
     static STELLA: Lazy<LocationZoneCode> = Lazy::new(|| LocationZoneCode::new("stella"));
     static NEO: Lazy<LocationZoneCode> = Lazy::new(|| LocationZoneCode::new("neo"));
 
+    fn added(zone: &LocationZoneCode, zone_type: LocationZoneType) -> RegistrarEvent {
+        E::ZoneAdded { zone: zone.clone(), zone_type }
+    }
+
     #[test]
     fn it_adds_zone_to_monitor() {
         testing::TestHarness::given([])
-            .when(MonitorForecastZone::new(OTIS.clone()))
-            .then([E::ForecastZoneAdded { zone: OTIS.clone() }]);
+            .when(MonitorZone::new(LocationZone::forecast(OTIS.clone())))
+            .then([added(&OTIS, LocationZoneType::Forecast)]);
+    }
+
+    #[test]
+    fn it_adds_county_zone_to_monitor() {
+        testing::TestHarness::given([])
+            .when(MonitorZone::new(LocationZone::new(
+                OTIS.clone(),
+                LocationZoneType::County,
+            )))
+            .then([added(&OTIS, LocationZoneType::County)]);
     }
 
     #[test]
     fn it_should_not_add_zone_that_is_already_monitored() {
+        let err = testing::TestHarness::given([added(&OTIS, LocationZoneType::Forecast)])
+            .when(MonitorZone::new(LocationZone::new(
+                OTIS.clone(),
+                LocationZoneType::Public,
+            )))
+            .then_err();
+        assert_matches!(err, RegistrarError::LocationZoneAlreadyMonitored(zone) if zone == OTIS.clone());
+    }
+
+    #[test]
+    fn it_should_not_add_zone_monitored_before_zone_types() {
         let err = testing::TestHarness::given([E::ForecastZoneAdded { zone: OTIS.clone() }])
-            .when(MonitorForecastZone::new(OTIS.clone()))
+            .when(MonitorZone::new(LocationZone::forecast(OTIS.clone())))
             .then_err();
         assert_matches!(err, RegistrarError::LocationZoneAlreadyMonitored(zone) if zone == OTIS.clone());
     }
 
     #[test]
     fn it_removes_zone_from_monitoring() {
-        testing::TestHarness::given([E::ForecastZoneAdded { zone: OTIS.clone() }])
-            .when(IgnoreForecastZone::new(OTIS.clone()))
-            .then([E::ForecastZoneRemoved { zone: OTIS.clone() }]);
+        testing::TestHarness::given([added(&OTIS, LocationZoneType::County)])
+            .when(IgnoreZone::new(OTIS.clone()))
+            .then([E::ZoneRemoved { zone: OTIS.clone() }]);
     }
 
     #[test]
     fn it_should_ignore_request_to_remove_zone_that_is_not_monitored() {
-        testing::TestHarness::given([E::ForecastZoneAdded { zone: OTIS.clone() }])
-            .when(IgnoreForecastZone::new(STELLA.clone()))
+        testing::TestHarness::given([added(&OTIS, LocationZoneType::Forecast)])
+            .when(IgnoreZone::new(STELLA.clone()))
             .then([]);
     }
 
     #[test]
     fn it_should_clear_all_zone_monitoring() {
         testing::TestHarness::given([
-            E::ForecastZoneAdded { zone: OTIS.clone() },
-            E::ForecastZoneAdded { zone: STELLA.clone() },
+            added(&OTIS, LocationZoneType::Forecast),
+            added(&STELLA, LocationZoneType::County),
             E::ForecastZoneAdded { zone: NEO.clone() },
         ])
         .when(ClearZoneMonitoring)
-        .then([E::AllForecastZonesRemoved])
+        .then([E::AllZonesRemoved])
     }
 }
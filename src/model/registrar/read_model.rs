@@ -1,78 +1,270 @@
 use crate::model::registrar::protocol::RegistrarEvent;
 use crate::model::registrar::RegistrarError;
-use crate::model::LocationZoneCode;
+use crate::model::{LocationZone, LocationZoneCode, LocationZoneType};
+use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use disintegrate::{query, EventListener, PersistedEvent, StreamQuery};
+use once_cell::sync::{Lazy, OnceCell};
+use sql_query_builder as sql;
+use sqlx::postgres::PgQueryResult;
+use sqlx::{PgConnection, PgPool};
 use std::collections::HashSet;
-use std::sync::{Arc, Mutex};
+use std::str::FromStr;
 
 #[derive(Debug, Default, Clone, ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MonitoredLocationZonesView(HashSet<LocationZoneCode>);
+pub struct MonitoredLocationZonesView(HashSet<LocationZone>);
 
 impl std::ops::Deref for MonitoredLocationZonesView {
-    type Target = HashSet<LocationZoneCode>;
+    type Target = HashSet<LocationZone>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-pub type MonitoredLocationZonesRef = Arc<MonitoredLocationZones>;
+impl FromIterator<LocationZone> for MonitoredLocationZonesView {
+    fn from_iter<T: IntoIterator<Item = LocationZone>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+pub const MONITORED_ZONES_VIEW: &str = "monitored_zones";
+pub static MONITORED_ZONES_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(MONITORED_ZONES_VIEW).unwrap());
+static PRIMARY_KEY: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone").unwrap());
+static ZONE_TYPE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone_type").unwrap());
+static ADDED_AT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("added_at").unwrap());
+static REMOVED_AT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("removed_at").unwrap());
+static EVENT_ID_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("event_id").unwrap());
+
+static COLUMNS: Lazy<[TableColumn; 6]> = Lazy::new(|| {
+    [
+        PRIMARY_KEY.clone(),
+        ZONE_TYPE_COL.clone(),
+        ADDED_AT_COL.clone(),
+        REMOVED_AT_COL.clone(),
+        EVENT_ID_COL.clone(),
+        LAST_UPDATED_AT_COL.clone(),
+    ]
+});
+static COLUMNS_REP: Lazy<String> = Lazy::new(|| COLUMNS.join(", "));
+static VALUES_REP: Lazy<String> = Lazy::new(|| {
+    let values = (1..=COLUMNS.len()).map(|i| format!("${i}")).collect::<Vec<_>>().join(", ");
 
-//todo: move projection into database!
+    format!("( {values} )")
+});
 
 #[derive(Debug, Clone)]
-pub struct MonitoredLocationZones {
-    zones: Arc<Mutex<HashSet<LocationZoneCode>>>,
-    query: StreamQuery<RegistrarEvent>,
+pub struct MonitoredZonesRepository {
+    pool: PgPool,
 }
 
-impl Default for MonitoredLocationZones {
-    fn default() -> Self {
-        Self {
-            zones: Default::default(),
-            query: query!(RegistrarEvent),
-        }
+impl MonitoredZonesRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Zones currently monitored; i.e., zones added and not since removed.
+    #[instrument(level = "trace", skip(self), err)]
+    pub async fn monitored(&self) -> Result<MonitoredLocationZonesView, RegistrarError> {
+        static MONITORED_ZONES_SQL: OnceCell<String> = OnceCell::new();
+        let sql = MONITORED_ZONES_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(PRIMARY_KEY.as_str())
+                .select(ZONE_TYPE_COL.as_str())
+                .from(&MONITORED_ZONES_TABLE)
+                .where_clause(format!("{} IS NULL", REMOVED_AT_COL.as_str()).as_str())
+                .to_string()
+        });
+
+        let zones: Vec<(LocationZoneCode, LocationZoneType)> =
+            sqlx::query_as(sql).fetch_all(&self.pool).await?;
+        Ok(zones
+            .into_iter()
+            .map(|(code, zone_type)| LocationZone { code, zone_type })
+            .collect())
     }
 }
 
-impl MonitoredLocationZones {
-    pub fn monitored(&self) -> MonitoredLocationZonesView {
-        MonitoredLocationZonesView(self.zones.lock().unwrap().clone())
+/// Postgres projection of the registrar's monitored zones. Removed zones are retained with their
+/// `removed_at` timestamp, and each row records the id of the registrar event that last changed it.
+#[derive(Debug)]
+pub struct MonitoredZonesProjection {
+    query: StreamQuery<RegistrarEvent>,
+    pool: PgPool,
+}
+
+impl MonitoredZonesProjection {
+    pub async fn new(pool: PgPool) -> Result<Self, sqlx::Error> {
+        static CREATE_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {primary_key} TEXT PRIMARY KEY,
+                    {zone_type} TEXT NOT NULL DEFAULT 'forecast',
+                    {added_at} TIMESTAMPTZ NOT NULL,
+                    {removed_at} TIMESTAMPTZ NULL,
+                    {event_id} BIGINT NOT NULL,
+                    {last_updated_at} TIMESTAMPTZ NULL DEFAULT clock_timestamp()
+                )"#,
+                table = MONITORED_ZONES_TABLE.as_str(),
+                primary_key = PRIMARY_KEY.as_str(),
+                zone_type = ZONE_TYPE_COL.as_str(),
+                added_at = ADDED_AT_COL.as_str(),
+                removed_at = REMOVED_AT_COL.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                last_updated_at = LAST_UPDATED_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&pool).await?;
+
+        static ADD_ZONE_TYPE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = ADD_ZONE_TYPE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                ALTER TABLE {table}
+                ADD COLUMN IF NOT EXISTS {zone_type} TEXT NOT NULL DEFAULT 'forecast'
+                "#,
+                table = MONITORED_ZONES_TABLE.as_str(),
+                zone_type = ZONE_TYPE_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&pool).await?;
+        Ok(Self { query: query!(RegistrarEvent), pool })
     }
 }
 
 #[async_trait]
-impl EventListener<RegistrarEvent> for MonitoredLocationZones {
+impl EventListener<RegistrarEvent> for MonitoredZonesProjection {
     type Error = RegistrarError;
 
     fn id(&self) -> &'static str {
-        "monitored_location_zones"
+        &MONITORED_ZONES_TABLE
     }
 
     fn query(&self) -> &StreamQuery<RegistrarEvent> {
         &self.query
     }
 
+    #[allow(clippy::blocks_in_conditions)]
+    #[instrument(level = "debug", skip(self), err)]
     async fn handle(
         &self, persisted_event: PersistedEvent<RegistrarEvent>,
     ) -> Result<(), Self::Error> {
         use RegistrarEvent as E;
 
-        let mut my_zones = self.zones.lock().unwrap();
-        match persisted_event.into_inner() {
+        let event_id = persisted_event.id();
+        let now = Utc::now();
+
+        let mut tx = sqlx::Acquire::begin(&self.pool).await?;
+        let result: PgQueryResult = match persisted_event.into_inner() {
+            E::ZoneAdded { zone, zone_type } => {
+                let zone = LocationZone { code: zone, zone_type };
+                Self::zone_added(zone, event_id, now, &mut tx).await?
+            },
             E::ForecastZoneAdded { zone } => {
-                my_zones.insert(zone);
+                let zone = LocationZone::forecast(zone);
+                Self::zone_added(zone, event_id, now, &mut tx).await?
             },
-            E::ForecastZoneRemoved { zone } => {
-                my_zones.remove(&zone);
+            E::ZoneRemoved { zone } | E::ForecastZoneRemoved { zone } => {
+                Self::zones_removed(Some(zone), event_id, now, &mut tx).await?
             },
-            E::AllForecastZonesRemoved => {
-                my_zones.clear();
+            E::AllZonesRemoved | E::AllForecastZonesRemoved => {
+                Self::zones_removed(None, event_id, now, &mut tx).await?
             },
+        };
+
+        let outcome = tx.commit().await;
+        if let Err(ref error) = outcome {
+            error!("postgres projection failed to commit registrar event transaction: {error:?}");
         }
 
-        Ok(())
+        debug!("monitored zones projection postgres query result: {result:?}");
+        outcome.map_err(|err| err.into())
+    }
+}
+
+impl MonitoredZonesProjection {
+    #[instrument(level = "debug", skip(tx), ret, err)]
+    async fn zone_added(
+        zone: LocationZone, event_id: i64, at: DateTime<Utc>, tx: &mut PgConnection,
+    ) -> Result<PgQueryResult, RegistrarError> {
+        static ZONE_ADDED_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ZONE_ADDED_SQL.get_or_init(|| {
+            let update_clause = COLUMNS
+                .iter()
+                .filter(|col| *col != &*PRIMARY_KEY)
+                .fold(sql::Update::new(), |clause, col| {
+                    clause.set(format!("{col} = EXCLUDED.{col}").as_str())
+                });
+
+            let conflict_clause = format!(
+                "( {key} ) DO UPDATE {update_clause}",
+                key = PRIMARY_KEY.as_str()
+            );
+
+            sql::Insert::new()
+                .insert_into(
+                    format!(
+                        "{table} ( {columns} )",
+                        table = MONITORED_ZONES_TABLE.as_str(),
+                        columns = COLUMNS_REP.as_str()
+                    )
+                    .as_str(),
+                )
+                .values(&VALUES_REP)
+                .on_conflict(conflict_clause.as_str())
+                .to_string()
+        });
+
+        sqlx::query(sql)
+            .bind(zone.code) // zone
+            .bind(zone.zone_type) // zone_type
+            .bind(at) // added_at
+            .bind(None::<DateTime<Utc>>) // removed_at
+            .bind(event_id) // event_id
+            .bind(at) // last_updated_at
+            .execute(tx)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Marks the zone, or all monitored zones if `None`, as removed.
+    #[instrument(level = "debug", skip(tx), ret, err)]
+    async fn zones_removed(
+        zone: Option<LocationZoneCode>, event_id: i64, at: DateTime<Utc>, tx: &mut PgConnection,
+    ) -> Result<PgQueryResult, RegistrarError> {
+        static ZONES_REMOVED_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ZONES_REMOVED_SQL.get_or_init(|| {
+            sql::Update::new()
+                .update(&MONITORED_ZONES_TABLE)
+                .set(
+                    format!(
+                        "{removed_at} = $2, {event_id} = $3, {last_updated_at} = $2",
+                        removed_at = REMOVED_AT_COL.as_str(),
+                        event_id = EVENT_ID_COL.as_str(),
+                        last_updated_at = LAST_UPDATED_AT_COL.as_str(),
+                    )
+                    .as_str(),
+                )
+                .where_clause(format!("{} IS NULL", REMOVED_AT_COL.as_str()).as_str())
+                .where_and(
+                    format!("( $1::TEXT IS NULL OR {} = $1 )", PRIMARY_KEY.as_str()).as_str(),
+                )
+                .to_string()
+        });
+
+        sqlx::query(sql)
+            .bind(zone)
+            .bind(at)
+            .bind(event_id)
+            .execute(tx)
+            .await
+            .map_err(|err| err.into())
     }
 }
@@ -0,0 +1,451 @@
+use super::{MonitoredZonesRepository, RegistrarError};
+use crate::model::weather::update::{self, UpdateWeatherId, UpdateWeatherServicesRef};
+use crate::model::weather::zone::WeatherRepository;
+use crate::model::weather::WeatherDecisionMakerRef;
+use crate::model::{LocationZone, LocationZoneCode};
+use crate::settings::{CadenceSettings, UpdateScheduleSettings, UpdateWeatherSettings};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio_util::task::TaskTracker;
+
+#[derive(Clone)]
+pub enum UpdateCadence {
+    Interval(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl fmt::Debug for UpdateCadence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interval(interval) => write!(f, "Interval({interval:?})"),
+            Self::Cron(schedule) => write!(f, "Cron({schedule})"),
+        }
+    }
+}
+
+impl UpdateCadence {
+    pub fn from_settings(settings: &CadenceSettings) -> Result<Option<Self>, RegistrarError> {
+        if let Some(expression) = settings.cron.as_deref() {
+            let schedule = cron::Schedule::from_str(expression).map_err(|err| {
+                let message = format!("bad cron expression {expression:?}: {err}");
+                RegistrarError::InvalidSchedule(message)
+            })?;
+            return Ok(Some(Self::Cron(Box::new(schedule))));
+        }
+
+        match settings.interval {
+            Some(interval) if interval.is_zero() => Err(RegistrarError::InvalidSchedule(
+                "update interval must be greater than zero".to_string(),
+            )),
+            Some(interval) => Ok(Some(Self::Interval(interval))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Interval(interval) => chrono::Duration::from_std(*interval)
+                .ok()
+                .and_then(|interval| after.checked_add_signed(interval)),
+            Self::Cron(schedule) => schedule.after(&after).next(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ScheduleKey {
+    Global,
+    Zone(LocationZoneCode),
+}
+
+/// Periodically runs the update weather saga for the monitored location zones. Each cadence runs
+/// as its own task tracked under the application `TaskTracker`, and the scheduler stops on
+/// `crate::shutdown()`.
+#[derive(Clone)]
+pub struct UpdateScheduler {
+    global: Option<UpdateCadence>,
+    zones: HashMap<LocationZoneCode, UpdateCadence>,
+    jitter: Duration,
+    monitored: MonitoredZonesRepository,
+    weather_dm: WeatherDecisionMakerRef,
+    services: UpdateWeatherServicesRef,
+}
+
+impl fmt::Debug for UpdateScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateScheduler")
+            .field("global", &self.global)
+            .field("zones", &self.zones)
+            .field("jitter", &self.jitter)
+            .finish()
+    }
+}
+
+impl UpdateScheduler {
+    #[instrument(level = "debug", skip(monitored, weather_dm, services), err)]
+    pub fn from_settings(
+        settings: &UpdateScheduleSettings, monitored: MonitoredZonesRepository,
+        weather_dm: WeatherDecisionMakerRef, services: UpdateWeatherServicesRef,
+    ) -> Result<Self, RegistrarError> {
+        let global = UpdateCadence::from_settings(&settings.cadence)?;
+
+        let mut zones = HashMap::with_capacity(settings.zones.len());
+        for (zone, cadence) in settings.zones.iter() {
+            match UpdateCadence::from_settings(cadence)? {
+                Some(cadence) => {
+                    zones.insert(zone.clone(), cadence);
+                },
+                None => warn!("no update cadence specified for zone {zone} - ignored"),
+            }
+        }
+
+        Ok(Self {
+            global,
+            zones,
+            jitter: settings.jitter,
+            monitored,
+            weather_dm,
+            services,
+        })
+    }
+
+    pub fn spawn(self, task_tracker: &TaskTracker) {
+        if self.global.is_none() && self.zones.is_empty() {
+            warn!("update weather schedule enabled without any cadence - scheduler not started");
+            return;
+        }
+
+        let global = self.global.clone().map(|cadence| (ScheduleKey::Global, cadence));
+        let zones = self
+            .zones
+            .iter()
+            .map(|(zone, cadence)| (ScheduleKey::Zone(zone.clone()), cadence.clone()));
+
+        for (key, cadence) in global.into_iter().chain(zones) {
+            let scheduler = self.clone();
+            let tracker = task_tracker.clone();
+            task_tracker.spawn(async move { scheduler.run(key, cadence, tracker).await });
+        }
+    }
+
+    /// Runs one schedule until shutdown. Interval cadences tick on a fixed period from the start,
+    /// so neither the jitter nor the time to start an update shifts later runs.
+    #[instrument(level = "debug", skip(self, task_tracker))]
+    async fn run(self, key: ScheduleKey, cadence: UpdateCadence, task_tracker: TaskTracker) {
+        info!(?key, ?cadence, "starting update weather schedule");
+
+        let shutdown = crate::shutdown();
+        tokio::pin!(shutdown);
+
+        let mut ticks = match &cadence {
+            UpdateCadence::Interval(period) => {
+                let start = tokio::time::Instant::now() + *period;
+                let mut ticks = tokio::time::interval_at(start, *period);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                Some(ticks)
+            },
+            UpdateCadence::Cron(_) => None,
+        };
+
+        loop {
+            let jitter = self.jitter_delay();
+            let scheduled = async {
+                let scheduled = match ticks.as_mut() {
+                    Some(ticks) => {
+                        ticks.tick().await;
+                        true
+                    },
+                    None => match cadence.next_after(Utc::now()) {
+                        Some(next_at) => {
+                            tokio::time::sleep((next_at - Utc::now()).to_std().unwrap_or_default())
+                                .await;
+                            true
+                        },
+                        None => false,
+                    },
+                };
+
+                if scheduled {
+                    tokio::time::sleep(jitter).await;
+                }
+                scheduled
+            };
+
+            tokio::select! {
+                _ = &mut shutdown => {
+                    info!(?key, "update weather schedule shutting down");
+                    break;
+                },
+                scheduled = scheduled => {
+                    if !scheduled {
+                        info!(?key, "no further scheduled weather updates - stopping schedule");
+                        break;
+                    }
+                },
+            }
+
+            self.start_update(&key, &task_tracker).await;
+        }
+    }
+
+    async fn start_update(&self, key: &ScheduleKey, task_tracker: &TaskTracker) {
+        let monitored: Vec<_> = match self.monitored.monitored().await {
+            Ok(view) => view.iter().cloned().collect(),
+            Err(error) => {
+                error!(
+                    ?error,
+                    ?key,
+                    "failed to load monitored zones - skipping scheduled update"
+                );
+                return;
+            },
+        };
+
+        let zones = zones_due(std::slice::from_ref(key), &monitored, &self.zones);
+        if zones.is_empty() {
+            debug!(?key, "no monitored zones due for scheduled update");
+            return;
+        }
+
+        let weather_dm = self.weather_dm.clone();
+        let services = self.services.clone();
+        task_tracker.spawn(async move {
+            match update::update_weather(&zones, weather_dm, services).await {
+                Ok(update_id) => info!(?update_id, ?zones, "scheduled weather update started"),
+                Err(error) => error!(?error, ?zones, "scheduled weather update failed"),
+            }
+        });
+    }
+
+    fn jitter_delay(&self) -> Duration {
+        let jitter_millis = self.jitter.as_millis() as u64;
+        if jitter_millis == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_millis))
+        }
+    }
+}
+
+/// Updates the monitored zones holding alerts past their expiry or end, so those alerts are noted
+/// as expired without waiting for the zones' next scheduled update. The scheduler is tracked under
+/// the application `TaskTracker` and stops on `crate::shutdown()`.
+#[derive(Clone)]
+pub struct AlertExpiryScheduler {
+    interval: Duration,
+    weather: WeatherRepository,
+    monitored: MonitoredZonesRepository,
+    weather_dm: WeatherDecisionMakerRef,
+    services: UpdateWeatherServicesRef,
+}
+
+impl fmt::Debug for AlertExpiryScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlertExpiryScheduler")
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl AlertExpiryScheduler {
+    pub fn from_settings(
+        settings: &UpdateWeatherSettings, weather: WeatherRepository,
+        monitored: MonitoredZonesRepository, weather_dm: WeatherDecisionMakerRef,
+        services: UpdateWeatherServicesRef,
+    ) -> Self {
+        Self {
+            interval: settings.alert_expiry_interval,
+            weather,
+            monitored,
+            weather_dm,
+            services,
+        }
+    }
+
+    pub fn spawn(self, task_tracker: &TaskTracker) {
+        if self.interval.is_zero() {
+            warn!("alert expiry interval is zero - alert expiry scheduler not started");
+            return;
+        }
+
+        task_tracker.spawn(async move { self.run().await });
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn run(self) {
+        info!(scheduler=?self, "starting alert expiry scheduler");
+
+        let shutdown = crate::shutdown();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    info!("alert expiry scheduler shutting down");
+                    break;
+                },
+                _ = interval.tick() => {},
+            }
+
+            match self.update_lapsed_zones().await {
+                Ok(None) => {},
+                Ok(Some(update_id)) => info!(%update_id, "update started to expire lapsed alerts"),
+                Err(error) => error!(?error, "alert expiry scheduler failed to check alerts"),
+            }
+        }
+    }
+
+    /// Starts an update of the monitored zones holding alerts no longer in force, returning the
+    /// update started, if any zone has lapsed alerts.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn update_lapsed_zones(&self) -> Result<Option<UpdateWeatherId>, RegistrarError> {
+        let lapsed: HashSet<_> = self
+            .weather
+            .zones_with_lapsed_alerts(Utc::now())
+            .await?
+            .into_iter()
+            .collect();
+        if lapsed.is_empty() {
+            return Ok(None);
+        }
+
+        let zones: Vec<_> = self
+            .monitored
+            .monitored()
+            .await?
+            .iter()
+            .filter(|zone| lapsed.contains(&zone.code))
+            .cloned()
+            .collect();
+
+        let update_id =
+            update::update_weather(&zones, self.weather_dm.clone(), self.services.clone()).await?;
+        Ok(update_id)
+    }
+}
+
+/// Identifies the monitored zones to update for the due schedules. The global schedule covers
+/// every monitored zone without its own cadence; zone schedules only apply while the zone is
+/// monitored.
+fn zones_due(
+    due: &[ScheduleKey], monitored: &[LocationZone],
+    overridden: &HashMap<LocationZoneCode, UpdateCadence>,
+) -> Vec<LocationZone> {
+    let mut zones = BTreeSet::new();
+
+    for key in due {
+        match key {
+            ScheduleKey::Global => {
+                zones.extend(
+                    monitored.iter().filter(|z| !overridden.contains_key(&z.code)).cloned(),
+                );
+            },
+            ScheduleKey::Zone(code) => match monitored.iter().find(|z| &z.code == code) {
+                Some(zone) => {
+                    zones.insert(zone.clone());
+                },
+                None => debug!("scheduled zone {code} is not monitored - skipping update"),
+            },
+        }
+    }
+
+    zones.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LocationZoneType;
+    use chrono::TimeZone;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_interval_cadence_next_after() {
+        let cadence = assert_some!(assert_ok!(UpdateCadence::from_settings(&CadenceSettings {
+            interval: Some(Duration::from_secs(15 * 60)),
+            cron: None,
+        })));
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 7, 0, 10, 0).unwrap();
+        assert_eq!(
+            cadence.next_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 3, 7, 0, 25, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_cron_cadence_next_after() {
+        let cadence = assert_some!(assert_ok!(UpdateCadence::from_settings(&CadenceSettings {
+            interval: Some(Duration::from_secs(60)),
+            cron: Some("0 */15 * * * *".to_string()),
+        })));
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 7, 0, 10, 0).unwrap();
+        assert_eq!(
+            cadence.next_after(now),
+            Some(Utc.with_ymd_and_hms(2024, 3, 7, 0, 15, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_cadence() {
+        assert_err!(UpdateCadence::from_settings(&CadenceSettings {
+            interval: None,
+            cron: Some("every so often".to_string()),
+        }));
+
+        assert_err!(UpdateCadence::from_settings(&CadenceSettings {
+            interval: Some(Duration::ZERO),
+            cron: None,
+        }));
+
+        assert_none!(assert_ok!(UpdateCadence::from_settings(
+            &CadenceSettings::default()
+        )));
+    }
+
+    #[test]
+    fn test_zones_due() {
+        let otis = LocationZone::forecast("otis");
+        let stella = LocationZone::new("stella", LocationZoneType::County);
+        let neo = LocationZoneCode::new("neo");
+        let monitored = vec![stella.clone(), otis.clone()];
+        let overridden = maplit::hashmap! {
+            stella.code.clone() => UpdateCadence::Interval(Duration::from_secs(60)),
+            neo.clone() => UpdateCadence::Interval(Duration::from_secs(60)),
+        };
+
+        assert_eq!(
+            zones_due(&[ScheduleKey::Global], &monitored, &overridden),
+            vec![otis.clone()]
+        );
+        assert_eq!(
+            zones_due(
+                &[ScheduleKey::Zone(stella.code.clone())],
+                &monitored,
+                &overridden
+            ),
+            vec![stella.clone()]
+        );
+        assert_eq!(
+            zones_due(&[ScheduleKey::Zone(neo.clone())], &monitored, &overridden),
+            Vec::<LocationZone>::new()
+        );
+        assert_eq!(
+            zones_due(
+                &[ScheduleKey::Global, ScheduleKey::Zone(stella.code.clone())],
+                &monitored,
+                &overridden
+            ),
+            vec![otis, stella]
+        );
+    }
+}
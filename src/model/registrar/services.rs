@@ -1,7 +1,7 @@
 use crate::model::registrar::errors::RegistrarError;
 use crate::model::weather::update::{self, UpdateWeatherId, UpdateWeatherServicesRef};
 use crate::model::weather::WeatherDecisionMakerRef;
-use crate::model::LocationZoneCode;
+use crate::model::LocationZone;
 use std::sync::Arc;
 
 pub trait RegistrarApi: Sync + Send {
@@ -10,7 +10,7 @@ pub trait RegistrarApi: Sync + Send {
     // ) -> Result<(), RegistrarError>;
 
     async fn update_weather(
-        &self, zones: &[LocationZoneCode], update_weather_dm: WeatherDecisionMakerRef,
+        &self, zones: &[LocationZone], update_weather_dm: WeatherDecisionMakerRef,
     ) -> Result<Option<UpdateWeatherId>, RegistrarError>;
 }
 
@@ -47,7 +47,7 @@ impl RegistrarApi for RegistrarServices {
 
     #[instrument(level = "debug", skip(self, dm), ret, err)]
     async fn update_weather(
-        &self, zones: &[LocationZoneCode], dm: WeatherDecisionMakerRef,
+        &self, zones: &[LocationZone], dm: WeatherDecisionMakerRef,
     ) -> Result<Option<UpdateWeatherId>, RegistrarError> {
         match self {
             Self::Full(svc) => svc.update_weather(zones, dm).await,
@@ -91,7 +91,7 @@ impl RegistrarApi for FullRegistrarServices {
 
     #[instrument(level = "debug", skip(self, dm), ret, err)]
     async fn update_weather(
-        &self, zones: &[LocationZoneCode], dm: WeatherDecisionMakerRef,
+        &self, zones: &[LocationZone], dm: WeatherDecisionMakerRef,
     ) -> Result<Option<UpdateWeatherId>, RegistrarError> {
         if zones.is_empty() {
             return Ok(None);
@@ -116,7 +116,7 @@ impl RegistrarApi for HappyPathServices {
 
     #[instrument(level = "debug", skip(self, _dm), ret, err)]
     async fn update_weather(
-        &self, _zones: &[LocationZoneCode], _dm: WeatherDecisionMakerRef,
+        &self, _zones: &[LocationZone], _dm: WeatherDecisionMakerRef,
     ) -> Result<Option<UpdateWeatherId>, RegistrarError> {
         Ok(None)
     }
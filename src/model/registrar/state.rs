@@ -1,8 +1,8 @@
 use crate::model::registrar::protocol::RegistrarEvent;
-use crate::model::LocationZoneCode;
+use crate::model::{LocationZoneCode, LocationZoneType};
 use disintegrate::{StateMutate, StateQuery};
 use smol_str::SmolStr;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use tagid::{Entity, IdGenerator, Label};
 
 // #[cfg(test)]
@@ -22,21 +22,26 @@ use tagid::{Entity, IdGenerator, Label};
 #[derive(Debug, Clone, Default, StateQuery, Label, Serialize, Deserialize)]
 #[state_query(RegistrarEvent)]
 pub struct Registrar {
-    pub location_codes: HashSet<LocationZoneCode>,
+    pub zones: HashMap<LocationZoneCode, LocationZoneType>,
     // services: RegistrarServicesRef,
 }
 
 impl StateMutate for Registrar {
     fn mutate(&mut self, event: Self::Event) {
+        use RegistrarEvent as E;
+
         match event {
-            RegistrarEvent::ForecastZoneAdded { zone } => {
-                self.location_codes.insert(zone);
+            E::ZoneAdded { zone, zone_type } => {
+                self.zones.insert(zone, zone_type);
+            },
+            E::ForecastZoneAdded { zone } => {
+                self.zones.insert(zone, LocationZoneType::Forecast);
             },
-            RegistrarEvent::ForecastZoneRemoved { zone } => {
-                self.location_codes.remove(&zone);
+            E::ZoneRemoved { zone } | E::ForecastZoneRemoved { zone } => {
+                self.zones.remove(&zone);
             },
-            RegistrarEvent::AllForecastZonesRemoved => {
-                self.location_codes.clear();
+            E::AllZonesRemoved | E::AllForecastZonesRemoved => {
+                self.zones.clear();
             },
         }
     }
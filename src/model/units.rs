@@ -0,0 +1,442 @@
+use super::{QuantitativeProperty, QuantitativeValue, WeatherFrame};
+use crate::errors::WeatherError;
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde_with::DeserializeFromStr;
+use std::borrow::Cow;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString, IntoStaticStr, VariantNames};
+
+/// Number of decimal places converted values are rounded to.
+const CONVERTED_DP: u32 = 4;
+
+/// Physical dimension a unit of measure quantifies; only units of the same dimension convert.
+#[derive(Debug, Display, Copy, Clone, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum Dimension {
+    Temperature,
+    Speed,
+    Pressure,
+    Length,
+    Angle,
+    Ratio,
+}
+
+/// Units of measure used by NOAA quantitative values, identified by their WMO unit codes. Codes
+/// are parsed with or without the `wmoUnit:` prefix, and the legacy `DegreesF` and `DegreesC`
+/// codes are accepted.
+#[derive(Debug, Display, Copy, Clone, PartialEq, Eq, Hash, IntoStaticStr, EnumString)]
+pub enum Unit {
+    #[strum(to_string = "wmoUnit:degC", serialize = "degC", serialize = "DegreesC")]
+    DegreesCelsius,
+
+    #[strum(to_string = "wmoUnit:degF", serialize = "degF", serialize = "DegreesF")]
+    DegreesFahrenheit,
+
+    #[strum(to_string = "wmoUnit:K", serialize = "K")]
+    Kelvin,
+
+    #[strum(to_string = "wmoUnit:m_s-1", serialize = "m_s-1")]
+    MetersPerSecond,
+
+    #[strum(to_string = "wmoUnit:km_h-1", serialize = "km_h-1")]
+    KilometersPerHour,
+
+    #[strum(to_string = "wmoUnit:mi_h-1", serialize = "mi_h-1")]
+    MilesPerHour,
+
+    #[strum(to_string = "wmoUnit:kt", serialize = "kt")]
+    Knots,
+
+    #[strum(to_string = "wmoUnit:Pa", serialize = "Pa")]
+    Pascals,
+
+    #[strum(to_string = "wmoUnit:hPa", serialize = "hPa")]
+    Hectopascals,
+
+    #[strum(to_string = "wmoUnit:inHg", serialize = "inHg")]
+    InchesOfMercury,
+
+    #[strum(to_string = "wmoUnit:m", serialize = "m")]
+    Meters,
+
+    #[strum(to_string = "wmoUnit:km", serialize = "km")]
+    Kilometers,
+
+    #[strum(to_string = "wmoUnit:mm", serialize = "mm")]
+    Millimeters,
+
+    #[strum(to_string = "wmoUnit:mi", serialize = "mi")]
+    Miles,
+
+    #[strum(to_string = "wmoUnit:in", serialize = "in")]
+    Inches,
+
+    #[strum(to_string = "wmoUnit:degree_(angle)", serialize = "degree_(angle)")]
+    DegreesAngle,
+
+    #[strum(to_string = "wmoUnit:percent", serialize = "percent")]
+    Percent,
+}
+
+impl Unit {
+    pub fn parse(code: &str) -> Result<Self, WeatherError> {
+        Self::from_str(code).map_err(|_| WeatherError::UnrecognizedUnit(code.to_string()))
+    }
+
+    /// WMO unit code of the unit, as NOAA reports it.
+    pub fn code(&self) -> &'static str {
+        self.into()
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Self::DegreesCelsius | Self::DegreesFahrenheit | Self::Kelvin => Dimension::Temperature,
+            Self::MetersPerSecond | Self::KilometersPerHour | Self::MilesPerHour | Self::Knots => {
+                Dimension::Speed
+            },
+            Self::Pascals | Self::Hectopascals | Self::InchesOfMercury => Dimension::Pressure,
+            Self::Meters | Self::Kilometers | Self::Millimeters | Self::Miles | Self::Inches => {
+                Dimension::Length
+            },
+            Self::DegreesAngle => Dimension::Angle,
+            Self::Percent => Dimension::Ratio,
+        }
+    }
+
+    /// Converts a value in this unit to the other unit of the same dimension.
+    pub fn convert(&self, value: Decimal, to: Self) -> Result<Decimal, WeatherError> {
+        if *self == to {
+            return Ok(value);
+        }
+
+        if self.dimension() != to.dimension() {
+            return Err(WeatherError::IncompatibleUnits { from: *self, to });
+        }
+
+        let converted = to.of_base(self.to_base(value));
+        Ok(converted.round_dp(CONVERTED_DP).normalize())
+    }
+
+    /// Expresses the value in the SI unit of the dimension: kelvin, meters per second, pascals,
+    /// meters, or the value itself for angles and ratios.
+    fn to_base(self, value: Decimal) -> Decimal {
+        match self {
+            Self::DegreesCelsius => value + dec!(273.15),
+            Self::DegreesFahrenheit => (value - dec!(32)) * dec!(5) / dec!(9) + dec!(273.15),
+            Self::Kelvin => value,
+            Self::KilometersPerHour => value / dec!(3.6),
+            Self::MilesPerHour => value * dec!(0.44704),
+            Self::Knots => value * dec!(1852) / dec!(3600),
+            Self::Hectopascals => value * dec!(100),
+            Self::InchesOfMercury => value * dec!(3386.389),
+            Self::Kilometers => value * dec!(1000),
+            Self::Millimeters => value / dec!(1000),
+            Self::Miles => value * dec!(1609.344),
+            Self::Inches => value * dec!(0.0254),
+            Self::MetersPerSecond
+            | Self::Pascals
+            | Self::Meters
+            | Self::DegreesAngle
+            | Self::Percent => value,
+        }
+    }
+
+    fn of_base(self, value: Decimal) -> Decimal {
+        match self {
+            Self::DegreesCelsius => value - dec!(273.15),
+            Self::DegreesFahrenheit => (value - dec!(273.15)) * dec!(9) / dec!(5) + dec!(32),
+            Self::Kelvin => value,
+            Self::KilometersPerHour => value * dec!(3.6),
+            Self::MilesPerHour => value / dec!(0.44704),
+            Self::Knots => value * dec!(3600) / dec!(1852),
+            Self::Hectopascals => value / dec!(100),
+            Self::InchesOfMercury => value / dec!(3386.389),
+            Self::Kilometers => value / dec!(1000),
+            Self::Millimeters => value * dec!(1000),
+            Self::Miles => value / dec!(1609.344),
+            Self::Inches => value / dec!(0.0254),
+            Self::MetersPerSecond
+            | Self::Pascals
+            | Self::Meters
+            | Self::DegreesAngle
+            | Self::Percent => value,
+        }
+    }
+}
+
+/// System of units quantitative values are reported in.
+#[derive(
+    Debug,
+    Display,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumString,
+    VariantNames,
+    ToSchema,
+    Serialize,
+    DeserializeFromStr,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    /// Celsius, km/h, hectopascals, kilometers and millimeters.
+    Metric,
+
+    /// Fahrenheit, mph, inches of mercury, miles and inches.
+    Imperial,
+
+    /// Kelvin, m/s, pascals and meters.
+    Si,
+}
+
+impl UnitSystem {
+    /// Unit the property is reported in under the system.
+    pub fn unit_for(&self, property: QuantitativeProperty) -> Unit {
+        use QuantitativeProperty as P;
+
+        match property {
+            P::Temperature
+            | P::Dewpoint
+            | P::MaxTemperatureLast24Hours
+            | P::MinTemperatureLast24Hours
+            | P::WindChill
+            | P::HeatIndex => match self {
+                Self::Metric => Unit::DegreesCelsius,
+                Self::Imperial => Unit::DegreesFahrenheit,
+                Self::Si => Unit::Kelvin,
+            },
+            P::WindSpeed | P::WindGust => match self {
+                Self::Metric => Unit::KilometersPerHour,
+                Self::Imperial => Unit::MilesPerHour,
+                Self::Si => Unit::MetersPerSecond,
+            },
+            P::BarometricPressure | P::SeaLevelPressure => match self {
+                Self::Metric => Unit::Hectopascals,
+                Self::Imperial => Unit::InchesOfMercury,
+                Self::Si => Unit::Pascals,
+            },
+            P::Visibility => match self {
+                Self::Metric => Unit::Kilometers,
+                Self::Imperial => Unit::Miles,
+                Self::Si => Unit::Meters,
+            },
+            P::PrecipitationLastHour | P::PrecipitationLast3Hours | P::PrecipitationLast6Hours => {
+                match self {
+                    Self::Metric => Unit::Millimeters,
+                    Self::Imperial => Unit::Inches,
+                    Self::Si => Unit::Meters,
+                }
+            },
+            P::WindDirection => Unit::DegreesAngle,
+            P::RelativeHumidity => Unit::Percent,
+        }
+    }
+}
+
+impl QuantitativeValue {
+    /// Parsed unit of the value's unit code.
+    pub fn unit(&self) -> Result<Unit, WeatherError> {
+        Unit::parse(self.unit_code())
+    }
+
+    /// Converts the value, and its min and max, to the unit.
+    pub fn convert_to(&self, unit: Unit) -> Result<Self, WeatherError> {
+        let from = self.unit()?;
+        Ok(Self {
+            value: from.convert(self.value, unit)?,
+            max_value: from.convert(self.max_value, unit)?,
+            min_value: from.convert(self.min_value, unit)?,
+            unit_code: Cow::Borrowed(unit.code()),
+            quality_control: self.quality_control,
+        })
+    }
+}
+
+impl WeatherFrame {
+    /// Converts the frame's quantitative values to the units of the system. A value whose unit
+    /// is not recognized, or does not convert to the system's unit, is kept as reported.
+    pub fn to_units(&self, system: UnitSystem) -> Self {
+        let convert = |property: QuantitativeProperty| {
+            self.property(property).map(|value| {
+                value.convert_to(system.unit_for(property)).unwrap_or_else(|error| {
+                    warn!(?error, %property, "keeping quantitative value in reported unit");
+                    value.clone()
+                })
+            })
+        };
+
+        Self {
+            timestamp: self.timestamp,
+            temperature: convert(QuantitativeProperty::Temperature),
+            dewpoint: convert(QuantitativeProperty::Dewpoint),
+            wind_direction: convert(QuantitativeProperty::WindDirection),
+            wind_direction_variable: self.wind_direction_variable,
+            wind_speed: convert(QuantitativeProperty::WindSpeed),
+            wind_gust: convert(QuantitativeProperty::WindGust),
+            barometric_pressure: convert(QuantitativeProperty::BarometricPressure),
+            sea_level_pressure: convert(QuantitativeProperty::SeaLevelPressure),
+            visibility: convert(QuantitativeProperty::Visibility),
+            max_temperature_last_24_hours: convert(QuantitativeProperty::MaxTemperatureLast24Hours),
+            min_temperature_last_24_hours: convert(QuantitativeProperty::MinTemperatureLast24Hours),
+            precipitation_last_hour: convert(QuantitativeProperty::PrecipitationLastHour),
+            precipitation_last_3_hours: convert(QuantitativeProperty::PrecipitationLast3Hours),
+            precipitation_last_6_hours: convert(QuantitativeProperty::PrecipitationLast6Hours),
+            relative_humidity: convert(QuantitativeProperty::RelativeHumidity),
+            wind_chill: convert(QuantitativeProperty::WindChill),
+            heat_index: convert(QuantitativeProperty::HeatIndex),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::QualityControl;
+    use claims::{assert_err, assert_ok};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_unit_system_deserializes_any_case() {
+        for rep in ["\"metric\"", "\"Metric\"", "\"METRIC\""] {
+            let system: UnitSystem = assert_ok!(serde_json::from_str(rep));
+            assert_eq!(system, UnitSystem::Metric);
+        }
+        assert_eq!(
+            assert_ok!(serde_json::from_str::<UnitSystem>("\"SI\"")),
+            UnitSystem::Si
+        );
+        assert_err!(serde_json::from_str::<UnitSystem>("\"furlongs\""));
+        assert_eq!(
+            assert_ok!(serde_json::to_string(&UnitSystem::Imperial)),
+            "\"imperial\""
+        );
+    }
+
+    #[test]
+    fn test_parse_unit_codes() {
+        assert_eq!(
+            assert_ok!(Unit::parse("wmoUnit:degC")),
+            Unit::DegreesCelsius
+        );
+        assert_eq!(assert_ok!(Unit::parse("km_h-1")), Unit::KilometersPerHour);
+        assert_eq!(assert_ok!(Unit::parse("DegreesF")), Unit::DegreesFahrenheit);
+        assert_eq!(
+            assert_ok!(Unit::parse("wmoUnit:degree_(angle)")),
+            Unit::DegreesAngle
+        );
+        assert_err!(Unit::parse("wmoUnit:furlong_fortnight-1"));
+        assert_eq!(Unit::Pascals.code(), "wmoUnit:Pa");
+    }
+
+    #[test]
+    fn test_convert_units() {
+        let c = Unit::DegreesCelsius;
+        assert_eq!(
+            assert_ok!(c.convert(dec!(100), Unit::DegreesFahrenheit)),
+            dec!(212)
+        );
+        assert_eq!(
+            assert_ok!(c.convert(dec!(-40), Unit::DegreesFahrenheit)),
+            dec!(-40)
+        );
+        assert_eq!(assert_ok!(c.convert(dec!(20), Unit::Kelvin)), dec!(293.15));
+
+        let kmh = Unit::KilometersPerHour;
+        assert_eq!(
+            assert_ok!(kmh.convert(dec!(36), Unit::MetersPerSecond)),
+            dec!(10)
+        );
+        assert_eq!(
+            assert_ok!(kmh.convert(dec!(16.0934), Unit::MilesPerHour)),
+            dec!(10)
+        );
+
+        let pa = Unit::Pascals;
+        assert_eq!(
+            assert_ok!(pa.convert(dec!(101325), Unit::Hectopascals)),
+            dec!(1013.25)
+        );
+        assert_eq!(
+            assert_ok!(pa.convert(dec!(101325), Unit::InchesOfMercury)),
+            dec!(29.9213)
+        );
+
+        assert_eq!(
+            assert_ok!(Unit::Millimeters.convert(dec!(25.4), Unit::Inches)),
+            dec!(1)
+        );
+        assert_err!(c.convert(dec!(1), Unit::Meters));
+    }
+
+    #[test]
+    fn test_frame_to_units() {
+        let frame = WeatherFrame {
+            timestamp: iso8601_timestamp::Timestamp::UNIX_EPOCH,
+            temperature: Some(QuantitativeValue::new(
+                10.0,
+                5.0,
+                15.0,
+                "wmoUnit:degC",
+                QualityControl::V,
+            )),
+            wind_speed: Some(QuantitativeValue::new(
+                18.0,
+                0.0,
+                36.0,
+                "wmoUnit:km_h-1",
+                QualityControl::V,
+            )),
+            relative_humidity: Some(QuantitativeValue::new(
+                55.0,
+                55.0,
+                55.0,
+                "wmoUnit:percent",
+                QualityControl::V,
+            )),
+            visibility: Some(QuantitativeValue::new(
+                1.0,
+                1.0,
+                1.0,
+                "nwsUnit:unknown",
+                QualityControl::Z,
+            )),
+            dewpoint: None,
+            wind_direction: None,
+            wind_direction_variable: false,
+            wind_gust: None,
+            barometric_pressure: None,
+            sea_level_pressure: None,
+            max_temperature_last_24_hours: None,
+            min_temperature_last_24_hours: None,
+            precipitation_last_hour: None,
+            precipitation_last_3_hours: None,
+            precipitation_last_6_hours: None,
+            wind_chill: None,
+            heat_index: None,
+        };
+
+        let actual = frame.to_units(UnitSystem::Si);
+        let temperature = actual.temperature.unwrap();
+        assert_eq!(temperature.unit_code(), "wmoUnit:K");
+        assert_eq!(
+            (
+                temperature.value,
+                temperature.min_value,
+                temperature.max_value
+            ),
+            (dec!(283.15), dec!(278.15), dec!(288.15))
+        );
+        let wind_speed = actual.wind_speed.unwrap();
+        assert_eq!(wind_speed.unit_code(), "wmoUnit:m_s-1");
+        assert_eq!(
+            (wind_speed.value, wind_speed.min_value, wind_speed.max_value),
+            (dec!(5), dec!(0), dec!(10))
+        );
+        assert_eq!(actual.relative_humidity, frame.relative_humidity);
+        assert_eq!(actual.visibility, frame.visibility);
+    }
+}
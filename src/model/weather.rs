@@ -4,8 +4,12 @@ pub mod zone;
 pub use errors::WeatherError;
 pub use support::WeatherSupport;
 
-use self::update::UpdateWeatherId;
-use crate::model::{LocationZoneCode, WeatherAlert, WeatherFrame, ZoneForecast};
+use self::update::{UpdateWeatherId, UpdateWeatherSummary};
+use crate::model::{
+    HourlyForecast, LocationZone, LocationZoneCode, StationObservation, WeatherAlert, WeatherFrame,
+    ZoneForecast,
+};
+use chrono::{DateTime, Utc};
 use disintegrate::serde::json::Json;
 use disintegrate::Event;
 use disintegrate_postgres::{PgDecisionMaker, PgEventStore, WithPgSnapshot};
@@ -17,7 +21,7 @@ pub type WeatherDecisionMaker = PgDecisionMaker<WeatherEvent, WeatherEventSerde,
 pub type WeatherDecisionMakerRef = Arc<WeatherDecisionMaker>;
 
 #[derive(Debug, Clone, PartialEq, Eq, Event, Serialize, Deserialize)]
-#[group(LocationZoneEvent, [ObservationUpdated, ForecastUpdated, AlertActivated, AlertDeactivated])]
+#[group(LocationZoneEvent, [ObservationUpdated, ForecastUpdated, HourlyForecastUpdated, AlertActivated, AlertUpdated, AlertExpired, AlertDeactivated])]
 #[group(UpdateWeatherEvent, [UpdateStarted, AlertsReviewed, UpdateLocationFailed, UpdateCompleted, UpdateFailed])]
 pub enum WeatherEvent {
     ObservationUpdated {
@@ -26,6 +30,8 @@ pub enum WeatherEvent {
         #[id]
         update_id: UpdateWeatherId,
         weather: Arc<WeatherFrame>,
+        #[serde(default)]
+        stations: Arc<Vec<StationObservation>>,
     },
     ForecastUpdated {
         #[id]
@@ -34,23 +40,58 @@ pub enum WeatherEvent {
         update_id: UpdateWeatherId,
         forecast: Arc<ZoneForecast>,
     },
+    /// Hourly forecast of the zone's representative gridpoint.
+    HourlyForecastUpdated {
+        #[id]
+        zone: LocationZoneCode,
+        #[id]
+        update_id: UpdateWeatherId,
+        forecast: Arc<HourlyForecast>,
+    },
     AlertActivated {
         #[id]
         zone: LocationZoneCode,
         #[id]
         update_id: UpdateWeatherId,
+        #[serde(default)]
+        alert_id: String,
+        alert: Arc<WeatherAlert>,
+    },
+    AlertUpdated {
+        #[id]
+        zone: LocationZoneCode,
+        #[id]
+        update_id: UpdateWeatherId,
+        alert_id: String,
         alert: Arc<WeatherAlert>,
     },
+    AlertExpired {
+        #[id]
+        zone: LocationZoneCode,
+        #[id]
+        update_id: UpdateWeatherId,
+        alert_id: String,
+    },
+    /// An alert no longer reported active by NOAA before it expired. Events recorded before alerts
+    /// were tracked by id do not carry an `alert_id` and deactivate all of the zone's alerts.
     AlertDeactivated {
         #[id]
         zone: LocationZoneCode,
         #[id]
         update_id: UpdateWeatherId,
+        #[serde(default)]
+        alert_id: Option<String>,
     },
+    /// Events recorded before updates were timed do not carry a `started_at`. An update retrying
+    /// the failed zones of a previous update links back to it with `retry_of`.
     UpdateStarted {
         #[id]
         update_id: UpdateWeatherId,
-        zones: Vec<LocationZoneCode>,
+        zones: Vec<LocationZone>,
+        #[serde(default)]
+        started_at: Option<DateTime<Utc>>,
+        #[serde(default)]
+        retry_of: Option<UpdateWeatherId>,
     },
     AlertsReviewed {
         #[id]
@@ -63,14 +104,18 @@ pub enum WeatherEvent {
         zone: LocationZoneCode,
         cause: String,
     },
-    // UpdateCompleted {
-    //     #[id]
-    //     update_id: UpdateWeatherId,
-    // },
-    // UpdateFailed {
-    //     #[id]
-    //     update_id: UpdateWeatherId,
-    // },
+    /// The update finished with at least one zone updated.
+    UpdateCompleted {
+        #[id]
+        update_id: UpdateWeatherId,
+        summary: UpdateWeatherSummary,
+    },
+    /// The update finished without updating any zone.
+    UpdateFailed {
+        #[id]
+        update_id: UpdateWeatherId,
+        summary: UpdateWeatherSummary,
+    },
 }
 
 impl WeatherEvent {
@@ -78,12 +123,17 @@ impl WeatherEvent {
     pub fn update_id(&self) -> &UpdateWeatherId {
         match self {
             Self::AlertActivated { update_id, .. } => update_id,
+            Self::AlertUpdated { update_id, .. } => update_id,
+            Self::AlertExpired { update_id, .. } => update_id,
             Self::AlertDeactivated { update_id, .. } => update_id,
             Self::AlertsReviewed { update_id, .. } => update_id,
             Self::ForecastUpdated { update_id, .. } => update_id,
+            Self::HourlyForecastUpdated { update_id, .. } => update_id,
             Self::UpdateLocationFailed { update_id, .. } => update_id,
             Self::ObservationUpdated { update_id, .. } => update_id,
             Self::UpdateStarted { update_id, .. } => update_id,
+            Self::UpdateCompleted { update_id, .. } => update_id,
+            Self::UpdateFailed { update_id, .. } => update_id,
         }
     }
 
@@ -91,12 +141,18 @@ impl WeatherEvent {
     pub fn zones(&self) -> Vec<LocationZoneCode> {
         match self {
             Self::AlertActivated { zone, .. } => vec![zone.clone()],
+            Self::AlertUpdated { zone, .. } => vec![zone.clone()],
+            Self::AlertExpired { zone, .. } => vec![zone.clone()],
             Self::AlertDeactivated { zone, .. } => vec![zone.clone()],
             Self::AlertsReviewed { .. } => vec![],
             Self::ForecastUpdated { zone, .. } => vec![zone.clone()],
+            Self::HourlyForecastUpdated { zone, .. } => vec![zone.clone()],
             Self::UpdateLocationFailed { zone, .. } => vec![zone.clone()],
             Self::ObservationUpdated { zone, .. } => vec![zone.clone()],
-            Self::UpdateStarted { zones, .. } => zones.clone(),
+            Self::UpdateStarted { zones, .. } => zones.iter().map(|z| z.code.clone()).collect(),
+            Self::UpdateCompleted { summary, .. } | Self::UpdateFailed { summary, .. } => {
+                summary.zones().cloned().collect()
+            },
         }
     }
 }
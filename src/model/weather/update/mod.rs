@@ -1,21 +1,28 @@
 mod location_status;
+mod progress;
 mod protocol;
 mod read_model;
 mod services;
 mod state;
 mod status;
+mod watchdog;
 
 pub use errors::UpdateWeatherError;
-pub use read_model::{UpdateWeatherRepository, UpdateWeatherStatusView};
+pub use progress::{progress_of, UpdateProgress, UpdateProgressChange, UpdateProgressSender};
+pub use read_model::{
+    SortOrder, UpdateHistory, UpdateHistoryCursor, UpdateHistoryQuery, UpdateWeatherRepository,
+    UpdateWeatherStatusView, MAX_UPDATE_HISTORY_LIMIT,
+};
 pub use services::{UpdateWeatherServices, UpdateWeatherServicesRef};
-pub use state::UpdateWeatherId;
+pub use state::{UpdateWeatherId, UpdateWeatherStateDiscriminants, UpdateWeatherSummary};
 pub use support::UpdateWeatherSupport;
+pub use watchdog::UpdateWeatherWatchdog;
 
 use crate::model::weather::update::protocol::{
-    NoteAlertsReviewed, NoteLocationUpdateFailure, StartUpdate,
+    CompleteUpdate, ExpireUpdate, NoteAlertsReviewed, NoteLocationUpdateFailure, StartUpdate,
 };
 use crate::model::weather::{WeatherDecisionMakerRef, WeatherEvent};
-use crate::model::LocationZoneCode;
+use crate::model::{LocationZone, LocationZoneCode};
 use tagid::Entity;
 
 pub fn next_id() -> UpdateWeatherId {
@@ -24,19 +31,43 @@ pub fn next_id() -> UpdateWeatherId {
 
 #[instrument(level = "debug", skip(weather_dm), ret, err)]
 pub async fn update_weather(
-    zones: &[LocationZoneCode], weather_dm: WeatherDecisionMakerRef,
-    services: UpdateWeatherServicesRef,
+    zones: &[LocationZone], weather_dm: WeatherDecisionMakerRef, services: UpdateWeatherServicesRef,
+) -> Result<Option<UpdateWeatherId>, UpdateWeatherError> {
+    if zones.is_empty() {
+        return Ok(None);
+    }
+
+    let start = StartUpdate::for_zones(zones.to_vec(), weather_dm.clone(), services)?;
+    do_start_update(start, weather_dm).await
+}
+
+/// Starts a new update of the failed zones of a finished update, linked back to it. Failed zones
+/// that are no longer monitored are not retried.
+#[instrument(level = "debug", skip(weather_dm), ret, err)]
+pub async fn retry_failed_zones(
+    original: &UpdateWeatherStatusView, monitored: &[LocationZone],
+    weather_dm: WeatherDecisionMakerRef, services: UpdateWeatherServicesRef,
 ) -> Result<Option<UpdateWeatherId>, UpdateWeatherError> {
+    if original.state != UpdateWeatherStateDiscriminants::Finished {
+        return Err(UpdateWeatherError::NotFinished(original.update_id.clone()));
+    }
+
+    let failed = original.update_statuses.failed_zones();
+    let zones: Vec<_> = monitored.iter().filter(|z| failed.contains(&z.code)).cloned().collect();
     if zones.is_empty() {
         return Ok(None);
     }
 
+    let start = StartUpdate::for_zones(zones, weather_dm.clone(), services)?
+        .retrying(original.update_id.clone());
+    do_start_update(start, weather_dm).await
+}
+
+async fn do_start_update(
+    start: StartUpdate, weather_dm: WeatherDecisionMakerRef,
+) -> Result<Option<UpdateWeatherId>, UpdateWeatherError> {
     let events = weather_dm
-        .make(StartUpdate::for_zones(
-            zones.to_vec(),
-            weather_dm.clone(),
-            services.clone(),
-        )?)
+        .make(start)
         .await
         .map_err(|err| UpdateWeatherError::Decision(Box::new(err)))?;
 
@@ -73,6 +104,34 @@ pub async fn note_zone_update_failure(
     Ok(())
 }
 
+/// Notes the update's completion, or failure, once its work is finished. Nothing is noted while
+/// zone updates remain in progress or after the update has finished.
+#[instrument(level = "debug", skip(weather_dm), err)]
+pub async fn note_update_completion(
+    update_id: UpdateWeatherId, weather_dm: WeatherDecisionMakerRef,
+) -> Result<(), UpdateWeatherError> {
+    weather_dm
+        .make(CompleteUpdate::new(update_id))
+        .await
+        .map_err(|err| UpdateWeatherError::Decision(Box::new(err)))?;
+
+    Ok(())
+}
+
+/// Expires the update if it is still active, noting its zones still in progress as failed and
+/// finishing the update.
+#[instrument(level = "debug", skip(weather_dm), err)]
+pub async fn expire_update(
+    update_id: UpdateWeatherId, weather_dm: WeatherDecisionMakerRef,
+) -> Result<(), UpdateWeatherError> {
+    weather_dm
+        .make(ExpireUpdate::new(update_id))
+        .await
+        .map_err(|err| UpdateWeatherError::Decision(Box::new(err)))?;
+
+    Ok(())
+}
+
 mod errors {
     use crate::errors::BoxDynError;
     use crate::model::weather::update::UpdateWeatherId;
@@ -100,6 +159,12 @@ mod errors {
         #[error("finished update weather process [{0}] cannot process command: {1}")]
         Finished(UpdateWeatherId, String),
 
+        #[error("update weather process [{0}] is not finished")]
+        NotFinished(UpdateWeatherId),
+
+        #[error("invalid update history cursor: {0:?}")]
+        BadCursor(String),
+
         #[error("failed to execution update weather decision: {0}")]
         LocationZone(#[from] LocationZoneError),
 
@@ -142,12 +207,14 @@ mod errors {
 }
 
 mod support {
+    use crate::model::weather::update::progress::UPDATE_PROGRESS_CAPACITY;
     use crate::model::weather::update::read_model::UpdateWeatherRepository;
     use crate::model::weather::update::{
-        UpdateWeatherError, UpdateWeatherServices, UpdateWeatherServicesRef,
+        UpdateProgressSender, UpdateWeatherError, UpdateWeatherServices, UpdateWeatherServicesRef,
     };
     use crate::model::weather::WeatherEventStore;
     use crate::services::noaa::NoaaWeatherServices;
+    use crate::settings::UpdateWeatherSettings;
     use anyhow::anyhow;
     use disintegrate_postgres::{PgEventListener, PgEventListenerConfig};
     use sqlx::PgPool;
@@ -159,17 +226,24 @@ mod support {
     pub struct UpdateWeatherSupport {
         pub history_repository: UpdateWeatherRepository,
         pub services: UpdateWeatherServicesRef,
+        pub settings: UpdateWeatherSettings,
+        pub progress: UpdateProgressSender,
     }
 
     impl UpdateWeatherSupport {
         pub async fn from_noaa(
             pool: PgPool, es: WeatherEventStore, noaa: NoaaWeatherServices,
-            task_tracker: &TaskTracker,
+            settings: &UpdateWeatherSettings, task_tracker: &TaskTracker,
         ) -> Result<Self, UpdateWeatherError> {
             Self::new(
                 pool,
                 es,
-                Arc::new(UpdateWeatherServices::new(noaa)),
+                Arc::new(
+                    UpdateWeatherServices::new(noaa)
+                        .with_zone_retry(settings.zone_retry.clone())
+                        .with_hourly_forecast_timeout(settings.hourly_forecast_timeout),
+                ),
+                settings,
                 task_tracker,
             )
             .await
@@ -178,13 +252,19 @@ mod support {
         #[instrument(level = "debug", name = "UpdateWeatherSupport::new", skip(es), err)]
         pub async fn new(
             pool: PgPool, es: WeatherEventStore, services: UpdateWeatherServicesRef,
-            task_tracker: &TaskTracker,
+            settings: &UpdateWeatherSettings, task_tracker: &TaskTracker,
         ) -> Result<Self, UpdateWeatherError> {
             let history_repository = UpdateWeatherRepository::new(pool.clone());
+            let (progress, _) = tokio::sync::broadcast::channel(UPDATE_PROGRESS_CAPACITY);
 
+            let projection_progress = progress.clone();
             task_tracker.spawn(async move {
                 let update_history_projection =
-                    super::read_model::UpdateWeatherHistoryProjection::new(pool).await?;
+                    super::read_model::UpdateWeatherHistoryProjection::new(
+                        pool,
+                        projection_progress,
+                    )
+                    .await?;
                 let listener_config = PgEventListenerConfig::poller(Duration::from_millis(50));
 
                 PgEventListener::builder(es)
@@ -197,7 +277,12 @@ mod support {
                 Ok::<(), anyhow::Error>(())
             });
 
-            Ok(Self { history_repository, services })
+            Ok(Self {
+                history_repository,
+                services,
+                settings: settings.clone(),
+                progress,
+            })
         }
     }
 }
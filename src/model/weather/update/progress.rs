@@ -0,0 +1,234 @@
+use super::state::{UpdateWeatherId, UpdateWeatherSummary};
+use super::status::UpdateStep;
+use crate::model::weather::WeatherEvent;
+use crate::model::LocationZoneCode;
+use futures::Stream;
+use std::future::Future;
+use strum_macros::IntoStaticStr;
+use tokio::sync::broadcast;
+
+pub const UPDATE_PROGRESS_CAPACITY: usize = 1_024;
+
+pub type UpdateProgressSender = broadcast::Sender<UpdateProgress>;
+
+/// Progress of an update weather process, published as the update history projection applies it
+/// to the update's status.
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProgress {
+    pub update_id: UpdateWeatherId,
+
+    #[serde(flatten)]
+    pub change: UpdateProgressChange,
+}
+
+#[derive(Debug, Clone, PartialEq, IntoStaticStr, ToSchema, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum UpdateProgressChange {
+    Started {
+        zones: Vec<LocationZoneCode>,
+    },
+    StepAdvanced {
+        zone: LocationZoneCode,
+        step: UpdateStep,
+    },
+    ZoneFailed {
+        zone: LocationZoneCode,
+        cause: String,
+    },
+    AlertsReviewed,
+    Completed {
+        summary: UpdateWeatherSummary,
+    },
+    Failed {
+        summary: UpdateWeatherSummary,
+    },
+}
+
+impl UpdateProgressChange {
+    /// The update finishes with its completion or failure.
+    pub const fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed { .. } | Self::Failed { .. })
+    }
+}
+
+impl From<&WeatherEvent> for UpdateProgress {
+    fn from(event: &WeatherEvent) -> Self {
+        use UpdateProgressChange as C;
+        use WeatherEvent as E;
+
+        let change = match event {
+            E::UpdateStarted { zones, .. } => C::Started {
+                zones: zones.iter().map(|z| z.code.clone()).collect(),
+            },
+            E::ObservationUpdated { zone, .. } => {
+                C::StepAdvanced { zone: zone.clone(), step: UpdateStep::Observation }
+            },
+            E::ForecastUpdated { zone, .. } => {
+                C::StepAdvanced { zone: zone.clone(), step: UpdateStep::Forecast }
+            },
+            E::HourlyForecastUpdated { zone, .. } => C::StepAdvanced {
+                zone: zone.clone(),
+                step: UpdateStep::HourlyForecast,
+            },
+            E::AlertActivated { zone, .. }
+            | E::AlertUpdated { zone, .. }
+            | E::AlertExpired { zone, .. }
+            | E::AlertDeactivated { zone, .. } => {
+                C::StepAdvanced { zone: zone.clone(), step: UpdateStep::Alert }
+            },
+            E::UpdateLocationFailed { zone, cause, .. } => {
+                C::ZoneFailed { zone: zone.clone(), cause: cause.clone() }
+            },
+            E::AlertsReviewed { .. } => C::AlertsReviewed,
+            E::UpdateCompleted { summary, .. } => C::Completed { summary: summary.clone() },
+            E::UpdateFailed { summary, .. } => C::Failed { summary: summary.clone() },
+        };
+
+        Self { update_id: event.update_id().clone(), change }
+    }
+}
+
+/// Streams the progress of the update from the subscription until the update finishes. Progress
+/// missed because the subscriber lagged behind is skipped; since that may include the update's
+/// finish, the stream also ends if `is_finished` then reports the update finished.
+pub fn progress_of<F, Fut>(
+    update_id: UpdateWeatherId, progress_rx: broadcast::Receiver<UpdateProgress>, is_finished: F,
+) -> impl Stream<Item = UpdateProgress>
+where
+    F: Fn(UpdateWeatherId) -> Fut + Clone,
+    Fut: Future<Output = bool>,
+{
+    futures::stream::unfold(Some(progress_rx), move |progress_rx| {
+        let update_id = update_id.clone();
+        let is_finished = is_finished.clone();
+        async move {
+            let mut progress_rx = progress_rx?;
+            loop {
+                match progress_rx.recv().await {
+                    Ok(progress) if progress.update_id == update_id => {
+                        let next =
+                            if progress.change.is_finished() { None } else { Some(progress_rx) };
+                        return Some((progress, next));
+                    },
+                    Ok(_) => {},
+                    Err(broadcast::error::RecvError::Lagged(nr_skipped)) => {
+                        warn!(
+                            %update_id, %nr_skipped,
+                            "update progress subscriber lagged - progress skipped"
+                        );
+                        if is_finished(update_id.clone()).await {
+                            debug!(%update_id, "update finished while subscriber lagged");
+                            return None;
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LocationZone;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_progress_of_update_until_finished() {
+        let update_id = UpdateWeatherId::for_labeled("update-1");
+        let other_id = UpdateWeatherId::for_labeled("update-2");
+        let zone = LocationZoneCode::new("WAZ558");
+
+        let (progress_tx, progress_rx) = broadcast::channel(16);
+        let events = [
+            WeatherEvent::UpdateStarted {
+                update_id: update_id.clone(),
+                zones: vec![LocationZone::forecast(zone.clone())],
+                started_at: None,
+                retry_of: None,
+            },
+            WeatherEvent::AlertsReviewed { update_id: other_id.clone() },
+            WeatherEvent::UpdateLocationFailed {
+                update_id: update_id.clone(),
+                zone: zone.clone(),
+                cause: "NOAA unavailable".to_string(),
+            },
+            WeatherEvent::AlertsReviewed { update_id: update_id.clone() },
+            WeatherEvent::UpdateFailed {
+                update_id: update_id.clone(),
+                summary: UpdateWeatherSummary::default(),
+            },
+            WeatherEvent::AlertsReviewed { update_id: update_id.clone() },
+        ];
+        for event in events.iter() {
+            progress_tx.send(UpdateProgress::from(event)).unwrap();
+        }
+
+        let changes: Vec<_> = tokio_test::block_on(
+            progress_of(update_id, progress_rx, |_| async { false })
+                .map(|progress| progress.change)
+                .collect(),
+        );
+        assert_eq!(
+            changes,
+            vec![
+                UpdateProgressChange::Started { zones: vec![zone.clone()] },
+                UpdateProgressChange::ZoneFailed { zone, cause: "NOAA unavailable".to_string() },
+                UpdateProgressChange::AlertsReviewed,
+                UpdateProgressChange::Failed { summary: UpdateWeatherSummary::default() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_of_lagged_update_ends_once_finished() {
+        let update_id = UpdateWeatherId::for_labeled("update-1");
+        let zone = LocationZoneCode::new("WAZ558");
+        let events = [
+            WeatherEvent::UpdateStarted {
+                update_id: update_id.clone(),
+                zones: vec![LocationZone::forecast(zone.clone())],
+                started_at: None,
+                retry_of: None,
+            },
+            WeatherEvent::AlertsReviewed { update_id: update_id.clone() },
+            WeatherEvent::UpdateLocationFailed {
+                update_id: update_id.clone(),
+                zone: zone.clone(),
+                cause: "NOAA unavailable".to_string(),
+            },
+        ];
+
+        let progress = |finished: bool| {
+            let (progress_tx, progress_rx) = broadcast::channel(2);
+            for event in events.iter() {
+                progress_tx.send(UpdateProgress::from(event)).unwrap();
+            }
+            drop(progress_tx);
+
+            tokio_test::block_on(
+                progress_of(
+                    update_id.clone(),
+                    progress_rx,
+                    move |_| async move { finished },
+                )
+                .map(|progress| progress.change)
+                .collect::<Vec<_>>(),
+            )
+        };
+
+        // -- the finish itself may be among the skipped progress
+        assert_eq!(progress(true), vec![]);
+        assert_eq!(
+            progress(false),
+            vec![
+                UpdateProgressChange::AlertsReviewed,
+                UpdateProgressChange::ZoneFailed { zone, cause: "NOAA unavailable".to_string() },
+            ]
+        );
+    }
+}
@@ -1,12 +1,15 @@
-use super::state::{UpdateWeather, UpdateWeatherId, UpdateWeatherState};
+use super::state::{UpdateWeather, UpdateWeatherId, UpdateWeatherState, UpdateWeatherSummary};
 use super::{UpdateWeatherError, UpdateWeatherServicesRef};
 use crate::model::weather::zone::LocationZoneError;
 use crate::model::weather::{zone, WeatherDecisionMakerRef, WeatherEvent};
-use crate::model::{LocationZoneCode, WeatherAlert};
+use crate::model::{LocationZone, LocationZoneCode, WeatherAlert};
 use crate::services::noaa::AlertApi;
+use crate::settings::ZoneRetrySettings;
+use chrono::{DateTime, Utc};
 use disintegrate::Decision;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::future::Future;
 use tagid::Entity;
 use tracing_futures::Instrument;
 
@@ -88,9 +91,129 @@ impl Decision for NoteLocationUpdateFailure {
     }
 }
 
+/// Notes the update completed, or failed if no zone was updated, once its work is finished.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CompleteUpdate {
+    update_id: UpdateWeatherId,
+    as_of: DateTime<Utc>,
+}
+
+impl CompleteUpdate {
+    pub fn new(update_id: UpdateWeatherId) -> Self {
+        Self::as_of(update_id, Utc::now())
+    }
+
+    pub fn as_of(update_id: UpdateWeatherId, as_of: DateTime<Utc>) -> Self {
+        Self { update_id, as_of }
+    }
+}
+
+impl Decision for CompleteUpdate {
+    type Event = WeatherEvent;
+    type StateQuery = UpdateWeather;
+    type Error = UpdateWeatherError;
+
+    fn state_query(&self) -> Self::StateQuery {
+        UpdateWeather::new(self.update_id.clone())
+    }
+
+    #[instrument(level = "debug", name = "CompleteUpdate::process", ret, err)]
+    fn process(&self, state: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
+        use UpdateWeatherState as S;
+
+        match &state.state {
+            S::Active(status) if status.is_finished() => Ok(vec![finish_update(
+                self.update_id.clone(),
+                status.summarize(self.as_of),
+            )]),
+            S::Active(_) | S::Finished(_) => Ok(vec![]),
+            S::Quiescent(_) => Err(UpdateWeatherError::NotStarted(
+                self.update_id.clone(),
+                tynm::type_name::<Self>(),
+            )),
+        }
+    }
+}
+
+pub const UPDATE_DEADLINE_EXCEEDED: &str = "update deadline exceeded";
+
+/// Expires an update still active past its deadline, e.g., when a zone update task panicked or
+/// hangs. Zones still in progress are noted as failed and the update is finished.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExpireUpdate {
+    update_id: UpdateWeatherId,
+    as_of: DateTime<Utc>,
+}
+
+impl ExpireUpdate {
+    pub fn new(update_id: UpdateWeatherId) -> Self {
+        Self::as_of(update_id, Utc::now())
+    }
+
+    pub fn as_of(update_id: UpdateWeatherId, as_of: DateTime<Utc>) -> Self {
+        Self { update_id, as_of }
+    }
+}
+
+impl Decision for ExpireUpdate {
+    type Event = WeatherEvent;
+    type StateQuery = UpdateWeather;
+    type Error = UpdateWeatherError;
+
+    fn state_query(&self) -> Self::StateQuery {
+        UpdateWeather::new(self.update_id.clone())
+    }
+
+    #[instrument(level = "debug", name = "ExpireUpdate::process", ret, err)]
+    fn process(&self, state: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
+        use UpdateWeatherState as S;
+
+        match &state.state {
+            S::Active(status) => {
+                let mut zones: Vec<_> = status.active_zones().into_iter().collect();
+                zones.sort();
+
+                let mut expired = status.clone();
+                let mut events = Vec::with_capacity(zones.len() + 1);
+                for zone in zones {
+                    let event = WeatherEvent::UpdateLocationFailed {
+                        update_id: self.update_id.clone(),
+                        zone,
+                        cause: UPDATE_DEADLINE_EXCEEDED.to_string(),
+                    };
+                    expired.mutate(event.clone());
+                    events.push(event);
+                }
+
+                events.push(finish_update(
+                    self.update_id.clone(),
+                    expired.summarize(self.as_of),
+                ));
+                Ok(events)
+            },
+            S::Finished(_) => Ok(vec![]),
+            S::Quiescent(_) => Err(UpdateWeatherError::NotStarted(
+                self.update_id.clone(),
+                tynm::type_name::<Self>(),
+            )),
+        }
+    }
+}
+
+/// The update completes if any zone was updated; otherwise it fails.
+fn finish_update(update_id: UpdateWeatherId, summary: UpdateWeatherSummary) -> WeatherEvent {
+    if summary.is_failure() {
+        WeatherEvent::UpdateFailed { update_id, summary }
+    } else {
+        WeatherEvent::UpdateCompleted { update_id, summary }
+    }
+}
+
 pub struct StartUpdate {
     update_id: UpdateWeatherId,
-    zones: Vec<LocationZoneCode>,
+    zones: Vec<LocationZone>,
+    started_at: DateTime<Utc>,
+    retry_of: Option<UpdateWeatherId>,
     weather_dm: WeatherDecisionMakerRef,
     services: UpdateWeatherServicesRef,
 }
@@ -100,6 +223,8 @@ impl fmt::Debug for StartUpdate {
         f.debug_struct("StartUpdate")
             .field("update_id", &self.update_id)
             .field("zones", &self.zones)
+            .field("started_at", &self.started_at)
+            .field("retry_of", &self.retry_of)
             .field("services", &self.services)
             .finish()
     }
@@ -115,21 +240,33 @@ impl Eq for StartUpdate {}
 
 impl StartUpdate {
     pub fn for_zones(
-        zones: Vec<LocationZoneCode>, weather_dm: WeatherDecisionMakerRef,
+        zones: Vec<LocationZone>, weather_dm: WeatherDecisionMakerRef,
         services: UpdateWeatherServicesRef,
     ) -> Result<Self, UpdateWeatherError> {
         Self::new(UpdateWeather::next_id(), zones, weather_dm, services)
     }
 
     pub fn new(
-        update_id: UpdateWeatherId, zones: Vec<LocationZoneCode>,
-        weather_dm: WeatherDecisionMakerRef, services: UpdateWeatherServicesRef,
+        update_id: UpdateWeatherId, zones: Vec<LocationZone>, weather_dm: WeatherDecisionMakerRef,
+        services: UpdateWeatherServicesRef,
     ) -> Result<Self, UpdateWeatherError> {
         if zones.is_empty() {
             return Err(UpdateWeatherError::NoLocations);
         }
 
-        Ok(Self { update_id, zones, weather_dm, services })
+        Ok(Self {
+            update_id,
+            zones,
+            started_at: Utc::now(),
+            retry_of: None,
+            weather_dm,
+            services,
+        })
+    }
+
+    /// Links the update back to the update whose failed zones it retries.
+    pub fn retrying(self, original: UpdateWeatherId) -> Self {
+        Self { retry_of: Some(original), ..self }
     }
 }
 
@@ -150,11 +287,13 @@ impl Decision for StartUpdate {
                 Ok(vec![WeatherEvent::UpdateStarted {
                     update_id: self.update_id.clone(),
                     zones: self.zones.clone(),
+                    started_at: Some(self.started_at),
+                    retry_of: self.retry_of.clone(),
                 }])
             },
             _ => Err(UpdateWeatherError::AlreadyStarted(
                 self.update_id.clone(),
-                self.zones.clone(),
+                self.zones.iter().map(|z| z.code.clone()).collect(),
             )),
         }
     }
@@ -174,26 +313,72 @@ impl StartUpdate {
             let update_id_f_i = self.update_id.clone();
             let weather_dm_o = self.weather_dm.clone();
             let weather_dm_f = self.weather_dm.clone();
+            let services_o = self.services.clone();
+            let services_f = self.services.clone();
             let zone_o = z.clone();
-            let zone_o_i = z.clone();
+            let zone_o_i = z.code.clone();
             let zone_f = z.clone();
-            let zone_f_i = z.clone();
+            let zone_f_i = z.code.clone();
+            let update_id_h = self.update_id.clone();
+            let update_id_h_i = self.update_id.clone();
+            let weather_dm_h = self.weather_dm.clone();
+            let services_h = self.services.clone();
+            let zone_h = z.clone();
+            let zone_h_i = z.code.clone();
             tokio::spawn(
-                async move { zone::observe(update_id_o, zone_o, weather_dm_o).await }.instrument(
-                    debug_span!(
-                        "observe location zone weather",
-                        update_id=%update_id_o_i,zone=%zone_o_i
-                    ),
-                ),
+                async move {
+                    let zone_code = zone_o.code.clone();
+                    let outcome = do_with_zone_retry(&zone_code, services_o.zone_retry(), || {
+                        zone::observe(update_id_o.clone(), zone_o.clone(), weather_dm_o.clone())
+                    })
+                    .await;
+                    do_settle_zone_update(update_id_o, zone_code, outcome, weather_dm_o).await;
+                }
+                .instrument(debug_span!(
+                    "observe location zone weather",
+                    update_id=%update_id_o_i,zone=%zone_o_i
+                )),
             );
 
             tokio::spawn(
-                async move { zone::forecast(update_id_f, zone_f, weather_dm_f).await }.instrument(
-                    debug_span!(
-                        "forecast location zone weather",
-                        update_id=%update_id_f_i, zone=%zone_f_i
-                    ),
-                ),
+                async move {
+                    let hourly_timeout = services_h.hourly_forecast_timeout();
+                    let hourly = tokio::time::timeout(
+                        hourly_timeout,
+                        zone::hourly_forecast(update_id_h, zone_h.clone(), weather_dm_h),
+                    )
+                    .await;
+                    match hourly {
+                        Ok(Ok(())) => {},
+                        Ok(Err(error)) => warn!(
+                            ?error, zone=%zone_h.code,
+                            "failed to update hourly forecast -- ignoring"
+                        ),
+                        Err(_) => warn!(
+                            zone=%zone_h.code, timeout=?hourly_timeout,
+                            "hourly forecast timed out -- ignoring"
+                        ),
+                    }
+                }
+                .instrument(debug_span!(
+                    "hourly forecast location zone weather",
+                    update_id=%update_id_h_i, zone=%zone_h_i
+                )),
+            );
+
+            tokio::spawn(
+                async move {
+                    let zone_code = zone_f.code.clone();
+                    let outcome = do_with_zone_retry(&zone_code, services_f.zone_retry(), || {
+                        zone::forecast(update_id_f.clone(), zone_f.clone(), weather_dm_f.clone())
+                    })
+                    .await;
+                    do_settle_zone_update(update_id_f, zone_code, outcome, weather_dm_f).await;
+                }
+                .instrument(debug_span!(
+                    "forecast location zone weather",
+                    update_id=%update_id_f_i, zone=%zone_f_i
+                )),
             );
         });
     }
@@ -201,21 +386,24 @@ impl StartUpdate {
     fn do_spawn_alerts(&self) {
         let update_id = self.update_id.clone();
         let update_id_i = self.update_id.clone();
-        let zones = self.zones.clone();
-        let zones_i = self.zones.clone();
+        let zones: Vec<_> = self.zones.iter().map(|z| z.code.clone()).collect();
+        let zones_i = zones.clone();
         let weather_dm = self.weather_dm.clone();
         let services = self.services.clone();
 
         tokio::spawn(
             async move {
                 if let Err(error) =
-                    do_update_zone_alerts(update_id, zones, weather_dm, services).await
+                    do_update_zone_alerts(update_id.clone(), zones, weather_dm.clone(), services)
+                        .await
                 {
                     warn!(
                         ?error,
                         "failed to update location weather alerts -- ignoring"
                     );
                 }
+
+                do_complete_update(update_id, weather_dm).await;
             }
             .instrument(debug_span!(
                 "update location zone weather alerts",
@@ -230,37 +418,44 @@ async fn do_update_zone_alerts(
     update_id: UpdateWeatherId, zones: Vec<LocationZoneCode>, weather_dm: WeatherDecisionMakerRef,
     services: UpdateWeatherServicesRef,
 ) -> Result<(), UpdateWeatherError> {
-    let update_scope: HashSet<_> = zones.into_iter().collect();
-    let mut alerted_zones = HashSet::with_capacity(update_scope.len());
-
-    // -- zones with alerts
-    let alerts = services.active_alerts().await?;
-    let nr_alerts = alerts.len();
-    let mut update_failures = HashMap::new();
-
-    for alert in alerts {
-        let affected: Vec<_> = alert
-            .affected_zones
-            .clone()
-            .into_iter()
-            .filter(|z| update_scope.contains(z))
-            .collect();
-
-        let (alerted, failures) =
-            do_alert_affected_zones(update_id.clone(), affected, alert, weather_dm.clone()).await;
-        alerted_zones.extend(alerted);
-        update_failures.extend(failures);
-    }
-
-    // -- unaffected zones
-    let unaffected_zones: Vec<_> = update_scope.difference(&alerted_zones).cloned().collect();
-    info!(?alerted_zones, ?unaffected_zones, %nr_alerts, "DMR: finish alerting with affected notes...");
-    let unaffected_failures =
-        do_update_unaffected_zones(update_id.clone(), unaffected_zones, weather_dm.clone()).await;
-    update_failures.extend(unaffected_failures);
-
-    // -- note update failures
-    do_note_alert_update_failures(update_id.clone(), update_failures, weather_dm.clone()).await?;
+    // -- group active alerts by zone in update scope; zones without alerts are noted with none
+    let mut zone_alerts: HashMap<_, Vec<WeatherAlert>> =
+        zones.into_iter().map(|zone| (zone, vec![])).collect();
+
+    // -- without active alerts, zone alerts are left as is rather than noted with none so the
+    // -- update can still finish
+    match services.active_alerts().await {
+        Ok(alerts) => {
+            let nr_alerts = alerts.len();
+            for alert in alerts {
+                for zone in alert.affected_zones.iter() {
+                    if let Some(alerts) = zone_alerts.get_mut(zone) {
+                        alerts.push(alert.clone());
+                    }
+                }
+            }
+
+            let alerted_zones: HashSet<_> = zone_alerts
+                .iter()
+                .filter(|(_, alerts)| !alerts.is_empty())
+                .map(|(zone, _)| zone.clone())
+                .collect();
+            info!(?alerted_zones, %nr_alerts, "noting active alerts for location zones...");
+
+            let update_failures =
+                do_note_zone_alerts(update_id.clone(), zone_alerts, weather_dm.clone()).await;
+
+            // -- note update failures
+            do_note_alert_update_failures(update_id.clone(), update_failures, weather_dm.clone())
+                .await?;
+        },
+        Err(error) => {
+            warn!(
+                ?error,
+                "failed to fetch active weather alerts -- zone alerts not updated"
+            );
+        },
+    }
 
     // -- note alerts updated as far as they will be
     super::note_alerts_updated(update_id, weather_dm.clone()).await?;
@@ -268,43 +463,79 @@ async fn do_update_zone_alerts(
     Ok(())
 }
 
-type ZoneUpdateFailures = HashMap<LocationZoneCode, LocationZoneError>;
+/// Runs the zone update, retrying failed attempts with exponential backoff per the retry policy.
+async fn do_with_zone_retry<F, Fut>(
+    zone: &LocationZoneCode, retry: &ZoneRetrySettings, mut update: F,
+) -> Result<(), LocationZoneError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), LocationZoneError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match update().await {
+            Err(error) if attempt < retry.max_attempts => {
+                let backoff = retry.backoff_for(attempt);
+                warn!(
+                    ?error, %attempt, ?backoff,
+                    "location zone {zone} update attempt failed -- retrying"
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            },
+            outcome => return outcome,
+        }
+    }
+}
 
-#[instrument(level = "trace", skip(weather_dm), ret)]
-async fn do_alert_affected_zones(
-    update_id: UpdateWeatherId, affected: Vec<LocationZoneCode>, alert: WeatherAlert,
+/// Notes the zone's update failure, if it failed, and then the update's completion if its work
+/// is finished.
+#[instrument(level = "debug", skip(weather_dm))]
+async fn do_settle_zone_update(
+    update_id: UpdateWeatherId, zone: LocationZoneCode, outcome: Result<(), LocationZoneError>,
     weather_dm: WeatherDecisionMakerRef,
-) -> (Vec<LocationZoneCode>, ZoneUpdateFailures) {
-    let mut alerted = vec![];
-    let mut failures = ZoneUpdateFailures::new();
-
-    for zone in affected {
-        alerted.push(zone.clone());
-        let alert_outcome = zone::alert(
+) {
+    if let Err(failure) = outcome {
+        let noted = super::note_zone_update_failure(
             update_id.clone(),
-            zone.clone(),
-            Some(alert.clone()),
+            zone,
+            failure.into(),
             weather_dm.clone(),
         )
         .await;
-        if let Err(error) = alert_outcome {
-            failures.insert(zone, error);
+
+        if let Err(error) = noted {
+            warn!(
+                ?error,
+                "failed to note location zone update failure -- ignoring"
+            );
         }
     }
 
-    (alerted, failures)
+    do_complete_update(update_id, weather_dm).await;
+}
+
+async fn do_complete_update(update_id: UpdateWeatherId, weather_dm: WeatherDecisionMakerRef) {
+    if let Err(error) = super::note_update_completion(update_id.clone(), weather_dm).await {
+        warn!(
+            ?error,
+            "failed to note completion of update weather saga({update_id}) -- ignoring"
+        );
+    }
 }
 
+type ZoneUpdateFailures = HashMap<LocationZoneCode, LocationZoneError>;
+
 #[instrument(level = "trace", skip(weather_dm), ret)]
-async fn do_update_unaffected_zones(
-    update_id: UpdateWeatherId, unaffected: Vec<LocationZoneCode>,
+async fn do_note_zone_alerts(
+    update_id: UpdateWeatherId, zone_alerts: HashMap<LocationZoneCode, Vec<WeatherAlert>>,
     weather_dm: WeatherDecisionMakerRef,
 ) -> ZoneUpdateFailures {
     let mut failures = ZoneUpdateFailures::new();
 
-    for zone in unaffected {
+    for (zone, alerts) in zone_alerts {
         let alert_outcome =
-            zone::alert(update_id.clone(), zone.clone(), None, weather_dm.clone()).await;
+            zone::alerts(update_id.clone(), zone.clone(), alerts, weather_dm.clone()).await;
         if let Err(error) = alert_outcome {
             failures.insert(zone, error);
         }
@@ -343,3 +574,225 @@ async fn do_note_alert_update_failures(
         Err(errors.pop().unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{LocationZoneType, ZoneForecast};
+    use crate::services::noaa::{HappyPathWeatherServices, ZoneWeatherApi};
+    use crate::testing;
+    use chrono::TimeZone;
+    use claims::{assert_err, assert_ok};
+    use once_cell::sync::Lazy;
+    use std::sync::Arc;
+    use WeatherEvent as E;
+
+    static UPDATE_ID: Lazy<UpdateWeatherId> =
+        Lazy::new(|| UpdateWeatherId::for_labeled("update-1"));
+    static STARTED_AT: Lazy<DateTime<Utc>> =
+        Lazy::new(|| Utc.with_ymd_and_hms(2024, 3, 7, 12, 0, 0).unwrap());
+    static FINISHED_AT: Lazy<DateTime<Utc>> =
+        Lazy::new(|| *STARTED_AT + chrono::Duration::seconds(42));
+    static OTIS: Lazy<LocationZoneCode> = Lazy::new(|| LocationZoneCode::new("WAZ558"));
+    static STELLA: Lazy<LocationZoneCode> = Lazy::new(|| LocationZoneCode::new("ORZ006"));
+
+    fn started() -> WeatherEvent {
+        E::UpdateStarted {
+            update_id: UPDATE_ID.clone(),
+            zones: vec![
+                LocationZone::forecast(OTIS.clone()),
+                LocationZone::forecast(STELLA.clone()),
+            ],
+            started_at: Some(*STARTED_AT),
+            retry_of: None,
+        }
+    }
+
+    fn updated(zone: &LocationZoneCode) -> Vec<WeatherEvent> {
+        let observation = assert_ok!(tokio_test::block_on(
+            HappyPathWeatherServices.zone_observation(LocationZoneType::Forecast, zone)
+        ));
+        let forecast = ZoneForecast {
+            zone_code: zone.to_string(),
+            updated: *STARTED_AT,
+            periods: vec![],
+        };
+
+        vec![
+            E::ObservationUpdated {
+                zone: zone.clone(),
+                update_id: UPDATE_ID.clone(),
+                weather: Arc::new(observation.weather),
+                stations: Arc::new(observation.stations),
+            },
+            E::ForecastUpdated {
+                zone: zone.clone(),
+                update_id: UPDATE_ID.clone(),
+                forecast: Arc::new(forecast),
+            },
+        ]
+    }
+
+    fn failed(zone: &LocationZoneCode) -> WeatherEvent {
+        E::UpdateLocationFailed {
+            update_id: UPDATE_ID.clone(),
+            zone: zone.clone(),
+            cause: "NOAA unavailable".to_string(),
+        }
+    }
+
+    fn reviewed() -> WeatherEvent {
+        E::AlertsReviewed { update_id: UPDATE_ID.clone() }
+    }
+
+    fn complete() -> CompleteUpdate {
+        CompleteUpdate::as_of(UPDATE_ID.clone(), *FINISHED_AT)
+    }
+
+    #[test]
+    fn it_completes_finished_update_with_summary() {
+        let mut history = vec![started()];
+        history.extend(updated(&OTIS));
+        history.push(failed(&STELLA));
+        history.push(reviewed());
+
+        testing::TestHarness::given(history)
+            .when(complete())
+            .then([E::UpdateCompleted {
+                update_id: UPDATE_ID.clone(),
+                summary: UpdateWeatherSummary {
+                    succeeded: vec![OTIS.clone()],
+                    failed: vec![STELLA.clone()],
+                    started_at: Some(*STARTED_AT),
+                    finished_at: *FINISHED_AT,
+                    duration_ms: Some(42_000),
+                },
+            }]);
+    }
+
+    #[test]
+    fn it_fails_finished_update_without_updated_zones() {
+        testing::TestHarness::given([started(), failed(&OTIS), failed(&STELLA), reviewed()])
+            .when(complete())
+            .then([E::UpdateFailed {
+                update_id: UPDATE_ID.clone(),
+                summary: UpdateWeatherSummary {
+                    succeeded: vec![],
+                    failed: vec![STELLA.clone(), OTIS.clone()],
+                    started_at: Some(*STARTED_AT),
+                    finished_at: *FINISHED_AT,
+                    duration_ms: Some(42_000),
+                },
+            }]);
+    }
+
+    #[test]
+    fn it_does_not_complete_update_until_finished() {
+        let mut history = vec![started()];
+        history.extend(updated(&OTIS));
+        history.push(failed(&STELLA));
+        testing::TestHarness::given(history.clone()).when(complete()).then([]);
+
+        history.push(reviewed());
+        history.push(E::UpdateCompleted {
+            update_id: UPDATE_ID.clone(),
+            summary: UpdateWeatherSummary::default(),
+        });
+        testing::TestHarness::given(history).when(complete()).then([]);
+    }
+
+    fn expire() -> ExpireUpdate {
+        ExpireUpdate::as_of(UPDATE_ID.clone(), *FINISHED_AT)
+    }
+
+    fn expired(zone: &LocationZoneCode) -> WeatherEvent {
+        E::UpdateLocationFailed {
+            update_id: UPDATE_ID.clone(),
+            zone: zone.clone(),
+            cause: UPDATE_DEADLINE_EXCEEDED.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_expires_update_with_zones_in_progress() {
+        let mut history = vec![started()];
+        history.extend(updated(&OTIS));
+
+        testing::TestHarness::given(history).when(expire()).then([
+            expired(&STELLA),
+            E::UpdateCompleted {
+                update_id: UPDATE_ID.clone(),
+                summary: UpdateWeatherSummary {
+                    succeeded: vec![OTIS.clone()],
+                    failed: vec![STELLA.clone()],
+                    started_at: Some(*STARTED_AT),
+                    finished_at: *FINISHED_AT,
+                    duration_ms: Some(42_000),
+                },
+            },
+        ]);
+
+        testing::TestHarness::given([started(), reviewed()]).when(expire()).then([
+            expired(&STELLA),
+            expired(&OTIS),
+            E::UpdateFailed {
+                update_id: UPDATE_ID.clone(),
+                summary: UpdateWeatherSummary {
+                    succeeded: vec![],
+                    failed: vec![STELLA.clone(), OTIS.clone()],
+                    started_at: Some(*STARTED_AT),
+                    finished_at: *FINISHED_AT,
+                    duration_ms: Some(42_000),
+                },
+            },
+        ]);
+    }
+
+    #[test]
+    fn it_does_not_expire_finished_update() {
+        testing::TestHarness::given([
+            started(),
+            failed(&OTIS),
+            failed(&STELLA),
+            reviewed(),
+            E::UpdateFailed {
+                update_id: UPDATE_ID.clone(),
+                summary: UpdateWeatherSummary::default(),
+            },
+        ])
+        .when(expire())
+        .then([]);
+    }
+
+    #[test]
+    fn it_retries_zone_update_per_retry_policy() {
+        let retry = ZoneRetrySettings {
+            max_attempts: 3,
+            backoff: std::time::Duration::ZERO,
+        };
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+
+        let outcome = tokio_test::block_on(do_with_zone_retry(&OTIS, &retry, || {
+            let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            async move {
+                if attempt < 2 {
+                    Err(LocationZoneError::Decision(
+                        format!("attempt {attempt}").into(),
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+        }));
+        assert_ok!(outcome);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        attempts.store(0, std::sync::atomic::Ordering::SeqCst);
+        let outcome = tokio_test::block_on(do_with_zone_retry(&OTIS, &retry, || {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async { Err(LocationZoneError::Decision("always fails".into())) }
+        }));
+        assert_err!(outcome);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+}
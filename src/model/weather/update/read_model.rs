@@ -1,7 +1,8 @@
+use crate::model::weather::update::progress::{UpdateProgress, UpdateProgressSender};
 use crate::model::weather::update::state::{UpdateWeatherStateDiscriminants, WeatherUpdateStatus};
 use crate::model::weather::update::{UpdateWeatherError, UpdateWeatherId};
 use crate::model::weather::WeatherEvent;
-use crate::model::LocationZoneCode;
+use crate::model::{LocationZone, LocationZoneCode};
 use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
@@ -11,7 +12,9 @@ use sql_query_builder as sql;
 use sqlx::postgres::PgQueryResult;
 use sqlx::{ColumnIndex, FromRow, PgConnection, PgPool};
 use std::clone::Clone;
+use std::fmt;
 use std::str::FromStr;
+use strum_macros::{Display, EnumString};
 
 pub const UPDATE_WEATHER_HISTORY: &str = "update_weather_history";
 
@@ -55,6 +58,96 @@ where
     }
 }
 
+pub const DEFAULT_UPDATE_HISTORY_LIMIT: usize = 50;
+pub const MAX_UPDATE_HISTORY_LIMIT: usize = 500;
+
+#[derive(Debug, Default, Display, EnumString, Copy, Clone, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Selects a page of the update history, ordered by when updates were last updated. Updates may
+/// be filtered by state, by a range of last updated times and by a zone included in the update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateHistoryQuery {
+    pub state: Option<UpdateWeatherStateDiscriminants>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub zone: Option<LocationZoneCode>,
+    pub order: SortOrder,
+    pub limit: usize,
+    pub cursor: Option<UpdateHistoryCursor>,
+}
+
+impl Default for UpdateHistoryQuery {
+    fn default() -> Self {
+        Self {
+            state: None,
+            from: None,
+            to: None,
+            zone: None,
+            order: SortOrder::default(),
+            limit: DEFAULT_UPDATE_HISTORY_LIMIT,
+            cursor: None,
+        }
+    }
+}
+
+/// Position in the update history after which the next page continues. Updates last updated at
+/// the same time are ordered by their id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateHistoryCursor {
+    pub last_updated_at: DateTime<Utc>,
+    pub update_id: UpdateWeatherId,
+}
+
+impl fmt::Display for UpdateHistoryCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}_{}",
+            self.last_updated_at.timestamp_micros(),
+            self.update_id.id
+        )
+    }
+}
+
+impl FromStr for UpdateHistoryCursor {
+    type Err = UpdateWeatherError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let bad_cursor = || UpdateWeatherError::BadCursor(rep.to_string());
+
+        let (micros, update_id) = rep.split_once('_').ok_or_else(bad_cursor)?;
+        let micros: i64 = micros.parse().map_err(|_| bad_cursor())?;
+        let nanos =
+            u32::try_from(micros.rem_euclid(1_000_000) * 1_000).map_err(|_| bad_cursor())?;
+        let last_updated_at =
+            DateTime::from_timestamp(micros.div_euclid(1_000_000), nanos).ok_or_else(bad_cursor)?;
+        if update_id.is_empty() {
+            return Err(bad_cursor());
+        }
+
+        Ok(Self {
+            last_updated_at,
+            update_id: UpdateWeatherId::for_labeled(update_id),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateHistory {
+    pub updates: Vec<UpdateWeatherStatusView>,
+
+    /// Pass as the `cursor` of the following request to continue with the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct UpdateWeatherRepository {
     pool: PgPool,
@@ -113,6 +206,89 @@ impl UpdateWeatherRepository {
         //
         // sqlx::query_as(sql).bind(update_id).fetch_optional(&self.pool).await
     }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn update_history(
+        &self, query: &UpdateHistoryQuery,
+    ) -> Result<UpdateHistory, UpdateWeatherError> {
+        let last_updated_at = LAST_UPDATED_AT_COL.as_str();
+        let primary_key = PRIMARY_KEY.as_str();
+        let (cursor_op, order) = match query.order {
+            SortOrder::Asc => (">", "ASC"),
+            SortOrder::Desc => ("<", "DESC"),
+        };
+
+        let select = sql::Select::new()
+            .select(COLUMNS_REP.as_str())
+            .from(&UPDATE_WEATHER_HISTORY_TABLE)
+            .where_clause(format!("( $1::TEXT IS NULL OR {} = $1 )", STATE_COL.as_str()).as_str())
+            .where_and(format!("( $2::TIMESTAMPTZ IS NULL OR {last_updated_at} >= $2 )").as_str())
+            .where_and(format!("( $3::TIMESTAMPTZ IS NULL OR {last_updated_at} < $3 )").as_str())
+            .where_and(
+                format!(
+                    "( $4::TEXT IS NULL OR {}->'location_statuses' ? $4 )",
+                    UPDATE_STATUSES_COL.as_str()
+                )
+                .as_str(),
+            )
+            .where_and(
+                format!(
+                    "( $5::TIMESTAMPTZ IS NULL \
+                    OR ( {last_updated_at}, {primary_key} ) {cursor_op} ( $5, $6 ) )"
+                )
+                .as_str(),
+            )
+            .order_by(format!("{last_updated_at} {order}, {primary_key} {order}").as_str());
+        let sql = format!("{select} LIMIT $7");
+
+        let mut updates: Vec<UpdateWeatherStatusView> = sqlx::query_as(&sql)
+            .bind(query.state)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(query.zone.as_ref())
+            .bind(query.cursor.as_ref().map(|c| c.last_updated_at))
+            .bind(query.cursor.as_ref().map(|c| c.update_id.clone()))
+            .bind((query.limit + 1) as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let next_cursor = if query.limit < updates.len() {
+            updates.truncate(query.limit);
+            updates.last().map(|view| {
+                UpdateHistoryCursor {
+                    last_updated_at: view.last_updated_at,
+                    update_id: view.update_id.clone(),
+                }
+                .to_string()
+            })
+        } else {
+            None
+        };
+
+        Ok(UpdateHistory { updates, next_cursor })
+    }
+
+    /// Fetches the updates still active that started before the given time, oldest first.
+    /// Updates recorded before updates were timed are considered started as of their last update.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn fetch_stuck_updates(
+        &self, started_before: DateTime<Utc>,
+    ) -> Result<Vec<UpdateWeatherStatusView>, UpdateWeatherError> {
+        sqlx::query_as(
+            r#"
+            SELECT update_id, state, update_statuses, last_updated_at
+            FROM update_weather_history
+            WHERE state = $1
+              AND COALESCE((update_statuses->>'started_at')::timestamptz, last_updated_at) < $2
+            ORDER BY COALESCE((update_statuses->>'started_at')::timestamptz, last_updated_at)
+            "#,
+        )
+        .bind(UpdateWeatherStateDiscriminants::Active)
+        .bind(started_before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|err| err.into())
+    }
 }
 
 // async fn do_fetch_optional_view<'q, 'e, 'c, DB, E>(
@@ -197,10 +373,11 @@ impl UpdateWeatherRepository {
 pub struct UpdateWeatherHistoryProjection {
     query: StreamQuery<WeatherEvent>,
     pool: PgPool,
+    progress: UpdateProgressSender,
 }
 
 impl UpdateWeatherHistoryProjection {
-    pub async fn new(pool: PgPool) -> Result<Self, sqlx::Error> {
+    pub async fn new(pool: PgPool, progress: UpdateProgressSender) -> Result<Self, sqlx::Error> {
         static CREATE_TABLE_DDL: OnceCell<String> = OnceCell::new();
         let sql = CREATE_TABLE_DDL.get_or_init(|| {
             format!(
@@ -210,7 +387,7 @@ impl UpdateWeatherHistoryProjection {
                     {state} TEXT,
                     {update_statuses} JSONB,
                     {last_updated_at} TIMESTAMPTZ NULL DEFAULT clock_timestamp()
-                "#,
+                )"#,
                 table = UPDATE_WEATHER_HISTORY_TABLE.as_str(),
                 primary_key = PRIMARY_KEY.as_str(),
                 state = STATE_COL.as_str(),
@@ -220,7 +397,7 @@ impl UpdateWeatherHistoryProjection {
         });
 
         sqlx::query(sql).execute(&pool).await?;
-        Ok(Self { query: query(None), pool })
+        Ok(Self { query: query(None), pool, progress })
     }
 }
 
@@ -246,15 +423,15 @@ impl EventListener<WeatherEvent> for UpdateWeatherHistoryProjection {
         let view = self.fetch_optional_view(event.update_id()).await?;
 
         let result: Result<PgQueryResult, UpdateWeatherError> = match (event, view) {
-            (E::UpdateStarted { update_id, zones }, None) => {
-                Self::started(update_id, zones, &mut tx).await
+            (E::UpdateStarted { update_id, zones, started_at, retry_of }, None) => {
+                Self::started(update_id, zones, started_at, retry_of, &mut tx).await
             },
-            (E::UpdateStarted { update_id, zones }, Some(view)) => {
+            (E::UpdateStarted { update_id, zones, started_at, retry_of }, Some(view)) => {
                 warn!(
                     restart_update=%update_id, restart_zones=?zones, previous_update=?view,
                     "unexpected update weather RESTART"
                 );
-                Self::started(update_id, zones, &mut tx).await
+                Self::started(update_id, zones, started_at, retry_of, &mut tx).await
             },
             (event, Some(mut view)) => {
                 let new_state = view.update_statuses.mutate(event);
@@ -308,11 +485,15 @@ impl EventListener<WeatherEvent> for UpdateWeatherHistoryProjection {
         // };
 
         let outcome = tx.commit().await;
-        if let Err(ref error) = outcome {
-            error!(
+        match &outcome {
+            Ok(()) => {
+                // -- no subscribers is not an error
+                let _ = self.progress.send(UpdateProgress::from(&event_t));
+            },
+            Err(error) => error!(
                 event=?event_t,
                 "postgres projection failed to commit update weather event transaction: {error:?}"
-            );
+            ),
         }
 
         debug!(
@@ -340,12 +521,18 @@ const SELECT_UPDATE_VIEW_SQL: &str = r#"
 
 impl UpdateWeatherHistoryProjection {
     async fn started(
-        update_id: UpdateWeatherId, zones: Vec<LocationZoneCode>, tx: &mut PgConnection,
+        update_id: UpdateWeatherId, zones: Vec<LocationZone>, started_at: Option<DateTime<Utc>>,
+        retry_of: Option<UpdateWeatherId>, tx: &mut PgConnection,
     ) -> Result<PgQueryResult, UpdateWeatherError> {
+        let zones = zones.into_iter().map(|z| z.code).collect();
         Self::update_or_insert(
             update_id,
             Some(UpdateWeatherStateDiscriminants::Active),
-            Some(WeatherUpdateStatus::new(zones)),
+            Some(WeatherUpdateStatus {
+                started_at,
+                retry_of,
+                ..WeatherUpdateStatus::new(zones)
+            }),
             tx,
         )
         .await
@@ -470,3 +657,35 @@ impl UpdateWeatherHistoryProjection {
             .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_update_history_cursor_roundtrip() {
+        let cursor = UpdateHistoryCursor {
+            last_updated_at: Utc.with_ymd_and_hms(2024, 3, 7, 12, 0, 0).unwrap()
+                + chrono::Duration::microseconds(123_456),
+            update_id: UpdateWeatherId::for_labeled("update-1"),
+        };
+
+        let rep = cursor.to_string();
+        assert_eq!(rep, "1709812800123456_update-1");
+        assert_eq!(assert_ok!(rep.parse::<UpdateHistoryCursor>()), cursor);
+
+        assert_err!("1709812800123456".parse::<UpdateHistoryCursor>());
+        assert_err!("yesterday_update-1".parse::<UpdateHistoryCursor>());
+        assert_err!("1709812800123456_".parse::<UpdateHistoryCursor>());
+    }
+
+    #[test]
+    fn test_parse_sort_order() {
+        assert_eq!(assert_ok!(SortOrder::from_str("asc")), SortOrder::Asc);
+        assert_eq!(assert_ok!(SortOrder::from_str("DESC")), SortOrder::Desc);
+        assert_err!(SortOrder::from_str("sideways"));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::model::WeatherAlert;
 use crate::services::noaa::{AlertApi, NoaaWeatherError, NoaaWeatherServices};
+use crate::settings::{UpdateWeatherSettings, ZoneRetrySettings};
 use std::sync::Arc;
+use std::time::Duration;
 
 pub type UpdateWeatherServicesRef = Arc<UpdateWeatherServices>;
 
@@ -21,11 +23,33 @@ pub type UpdateWeatherServicesRef = Arc<UpdateWeatherServices>;
 #[derive(Debug, Clone)]
 pub struct UpdateWeatherServices {
     noaa: NoaaWeatherServices,
+    zone_retry: ZoneRetrySettings,
+    hourly_forecast_timeout: Duration,
 }
 
 impl UpdateWeatherServices {
     pub fn new(noaa: NoaaWeatherServices) -> Self {
-        Self { noaa }
+        Self {
+            noaa,
+            zone_retry: ZoneRetrySettings::default(),
+            hourly_forecast_timeout: UpdateWeatherSettings::default_hourly_forecast_timeout(),
+        }
+    }
+
+    pub fn with_zone_retry(self, zone_retry: ZoneRetrySettings) -> Self {
+        Self { zone_retry, ..self }
+    }
+
+    pub fn with_hourly_forecast_timeout(self, hourly_forecast_timeout: Duration) -> Self {
+        Self { hourly_forecast_timeout, ..self }
+    }
+
+    pub fn zone_retry(&self) -> &ZoneRetrySettings {
+        &self.zone_retry
+    }
+
+    pub fn hourly_forecast_timeout(&self) -> Duration {
+        self.hourly_forecast_timeout
     }
 }
 
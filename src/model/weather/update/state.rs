@@ -3,6 +3,7 @@ use super::status::UpdateStep;
 use crate::model::weather::update::status::LocationUpdateStatus;
 use crate::model::weather::WeatherEvent;
 use crate::model::LocationZoneCode;
+use chrono::{DateTime, Utc};
 use disintegrate::{StateMutate, StateQuery};
 use std::collections::HashSet;
 use std::str::FromStr;
@@ -58,9 +59,9 @@ impl UpdateWeatherState {
             Self::Quiescent(q) => q.mutate(event),
             Self::Active(a) => match a.mutate(event) {
                 UpdateWeatherStateDiscriminants::Active => None,
-                UpdateWeatherStateDiscriminants::Finished => {
-                    Some(UpdateWeatherState::Finished(FinishedWeatherUpdate))
-                },
+                UpdateWeatherStateDiscriminants::Finished => Some(UpdateWeatherState::Finished(
+                    FinishedWeatherUpdate(a.summary.clone()),
+                )),
                 UpdateWeatherStateDiscriminants::Quiescent => {
                     error!("quiescent state mutation not possible by event ");
                     None
@@ -113,8 +114,14 @@ pub struct QuiescentWeatherUpdate;
 impl QuiescentWeatherUpdate {
     fn mutate(&mut self, event: WeatherEvent) -> Option<UpdateWeatherState> {
         match event {
-            WeatherEvent::UpdateStarted { zones, .. } => {
-                Some(UpdateWeatherState::Active(WeatherUpdateStatus::new(zones)))
+            WeatherEvent::UpdateStarted { zones, started_at, retry_of, .. } => {
+                let zones = zones.into_iter().map(|z| z.code).collect();
+                let status = WeatherUpdateStatus {
+                    started_at,
+                    retry_of,
+                    ..WeatherUpdateStatus::new(zones)
+                };
+                Some(UpdateWeatherState::Active(status))
             },
             event => {
                 warn!(
@@ -131,6 +138,16 @@ impl QuiescentWeatherUpdate {
 pub struct WeatherUpdateStatus {
     location_statuses: MultiIndexLocationStatusMap,
     pub alerts_reviewed: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<UpdateWeatherSummary>,
+
+    /// The update whose failed zones this update retries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<UpdateWeatherId>,
 }
 
 impl WeatherUpdateStatus {
@@ -143,7 +160,13 @@ impl WeatherUpdateStatus {
         //     location_statuses.insert(LocationStatus::new(zone));
         // }
 
-        Self { location_statuses, alerts_reviewed: false }
+        Self {
+            location_statuses,
+            alerts_reviewed: false,
+            started_at: None,
+            summary: None,
+            retry_of: None,
+        }
     }
 
     #[inline]
@@ -184,44 +207,92 @@ impl WeatherUpdateStatus {
         result
     }
 
+    /// The update's work is finished once alerts are reviewed and no zone update remains in
+    /// progress. A finished update remains active until its completion is noted.
+    pub fn is_finished(&self) -> bool {
+        self.current_state() == UpdateWeatherStateDiscriminants::Finished
+    }
+
+    /// Summarizes the outcome of the update's zones as of when the update finished.
+    pub fn summarize(&self, finished_at: DateTime<Utc>) -> UpdateWeatherSummary {
+        let mut succeeded: Vec<_> = self.succeeded_zones().into_iter().collect();
+        succeeded.sort();
+        let mut failed: Vec<_> = self.failed_zones().into_iter().collect();
+        failed.sort();
+
+        UpdateWeatherSummary {
+            succeeded,
+            failed,
+            started_at: self.started_at,
+            finished_at,
+            duration_ms: self
+                .started_at
+                .map(|started_at| (finished_at - started_at).num_milliseconds()),
+        }
+    }
+
+    /// Applies the event to the update's status. The update is `Finished` by its noted completion
+    /// or failure and remains so for events noted later, such as a late hourly forecast. Updates
+    /// started before their finish was noted are `Finished` once their work is.
     #[instrument(level = "debug", ret)]
     pub fn mutate(&mut self, event: WeatherEvent) -> UpdateWeatherStateDiscriminants {
         use WeatherEvent as E;
 
-        match event {
+        let work = match event {
             E::ObservationUpdated { zone, .. } => {
                 self.advance_zone_step(&zone, UpdateStep::Observation)
             },
 
             E::ForecastUpdated { zone, .. } => self.advance_zone_step(&zone, UpdateStep::Forecast),
 
-            E::AlertActivated { zone, .. } | E::AlertDeactivated { zone, .. } => {
-                self.advance_zone_step(&zone, UpdateStep::Alert)
+            E::HourlyForecastUpdated { zone, .. } => match self.status_for(&zone) {
+                Some(status) if status.is_active() => {
+                    self.advance_zone_step(&zone, UpdateStep::HourlyForecast)
+                },
+                _ => self.current_state(),
             },
 
+            E::AlertActivated { zone, .. }
+            | E::AlertUpdated { zone, .. }
+            | E::AlertExpired { zone, .. }
+            | E::AlertDeactivated { zone, .. } => self.advance_zone_step(&zone, UpdateStep::Alert),
+
             E::AlertsReviewed { .. } => {
                 self.alerts_reviewed = true;
-                if self.active_zones().is_empty() {
-                    UpdateWeatherStateDiscriminants::Finished
-                } else {
-                    UpdateWeatherStateDiscriminants::Active
-                }
+                self.current_state()
             },
 
             E::UpdateLocationFailed { zone, cause, .. } => {
                 self.update_zone_failure_for(zone, cause)
             },
 
-            // E::UpdateCompleted { .. } | E::UpdateFailed { .. } => {
-            //     Some(UpdateWeatherState::Finished(FinishedWeatherUpdate))
-            // },
+            E::UpdateCompleted { summary, .. } | E::UpdateFailed { summary, .. } => {
+                self.summary = Some(summary);
+                return UpdateWeatherStateDiscriminants::Finished;
+            },
+
             event => {
                 warn!(?event, "active update weather process cannot handle event");
                 UpdateWeatherStateDiscriminants::Active
             },
+        };
+
+        debug!(?work, "update weather work status");
+        if self.summary.is_some() {
+            UpdateWeatherStateDiscriminants::Finished
+        } else if self.predates_noted_finish() {
+            work
+        } else {
+            UpdateWeatherStateDiscriminants::Active
         }
     }
 
+    /// Updates were timed from their start when their completion and failure began to be noted,
+    /// so an update without a start time finished without either being noted.
+    fn predates_noted_finish(&self) -> bool {
+        self.started_at.is_none()
+    }
+
     #[instrument(level = "trace", ret)]
     pub fn is_only_active_zone(&self, zone: &LocationZoneCode) -> bool {
         let active_zone = self
@@ -243,6 +314,14 @@ impl WeatherUpdateStatus {
         })
     }
 
+    fn current_state(&self) -> UpdateWeatherStateDiscriminants {
+        if self.alerts_reviewed && self.active_zones().is_empty() {
+            UpdateWeatherStateDiscriminants::Finished
+        } else {
+            UpdateWeatherStateDiscriminants::Active
+        }
+    }
+
     fn prep_zone(&mut self, zone: &LocationZoneCode) -> Option<UpdateWeatherStateDiscriminants> {
         match self.status_for(zone) {
             None => {
@@ -253,18 +332,19 @@ impl WeatherUpdateStatus {
 
             Some(status) if status.is_completed() => {
                 warn!("{zone} zone was marked for update after completion - ignored");
-                Some(UpdateWeatherStateDiscriminants::Finished)
+                Some(self.current_state())
             },
 
             _ => None,
         }
     }
 
+    /// Advances the zone's update step, returning `Finished` once the update's work is finished.
     pub fn advance_zone_step(
         &mut self, zone: &LocationZoneCode, step: UpdateStep,
     ) -> UpdateWeatherStateDiscriminants {
-        if let Some(finished) = self.prep_zone(zone) {
-            return finished;
+        if let Some(current) = self.prep_zone(zone) {
+            return current;
         }
 
         let is_only_active_zone = self.is_only_active_zone(zone);
@@ -291,8 +371,8 @@ impl WeatherUpdateStatus {
     pub fn update_zone_failure_for(
         &mut self, zone: LocationZoneCode, cause: String,
     ) -> UpdateWeatherStateDiscriminants {
-        if let Some(finished) = self.prep_zone(&zone) {
-            return finished;
+        if let Some(current) = self.prep_zone(&zone) {
+            return current;
         }
 
         let is_only_active_zone = self.is_only_active_zone(&zone);
@@ -321,8 +401,33 @@ impl WeatherUpdateStatus {
     }
 }
 
+/// A finished weather update, with the summary noted on its completion or failure. Updates
+/// finished before summaries were noted do not have one.
 #[derive(Debug, Default, Clone, PartialEq, ToSchema, Serialize, Deserialize)]
-pub struct FinishedWeatherUpdate;
+pub struct FinishedWeatherUpdate(pub Option<UpdateWeatherSummary>);
+
+/// Outcome of a finished weather update.
+#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+pub struct UpdateWeatherSummary {
+    pub succeeded: Vec<LocationZoneCode>,
+    pub failed: Vec<LocationZoneCode>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: DateTime<Utc>,
+
+    /// Milliseconds from the start of the update until it finished, if its start is known.
+    pub duration_ms: Option<i64>,
+}
+
+impl UpdateWeatherSummary {
+    pub fn zones(&self) -> impl Iterator<Item = &LocationZoneCode> {
+        self.succeeded.iter().chain(self.failed.iter())
+    }
+
+    /// An update fails when none of its zones were updated.
+    pub fn is_failure(&self) -> bool {
+        self.succeeded.is_empty()
+    }
+}
 
 impl FinishedWeatherUpdate {
     fn mutate(&mut self, event: WeatherEvent) -> Option<UpdateWeatherState> {
@@ -338,8 +443,10 @@ impl FinishedWeatherUpdate {
 mod tests {
     use super::*;
     use crate::model::weather::update::status::UpdateSteps;
+    use crate::model::{Gridpoint, HourlyForecast};
     use claims::*;
     use pretty_assertions::assert_eq;
+    use std::sync::Arc;
 
     #[test]
     fn test_is_only_active_zone() {
@@ -354,7 +461,13 @@ mod tests {
             status: LocationUpdateStatus::Succeeded,
         });
 
-        let status = WeatherUpdateStatus { location_statuses, alerts_reviewed: false };
+        let status = WeatherUpdateStatus {
+            location_statuses,
+            alerts_reviewed: false,
+            started_at: None,
+            summary: None,
+            retry_of: None,
+        };
 
         assert!(status.is_only_active_zone(&LocationZoneCode::new("foo")));
         assert!(!status.is_only_active_zone(&LocationZoneCode::new("bar")));
@@ -442,4 +555,103 @@ mod tests {
             maplit::hashset! { otis.clone(), stella.clone(), neo.clone()}
         )
     }
+
+    #[test]
+    fn test_weather_update_status_further_alert_for_completed_zone() {
+        let otis = LocationZoneCode::new("otis");
+        let stella = LocationZoneCode::new("stella");
+
+        let mut status = WeatherUpdateStatus::new(vec![otis.clone(), stella.clone()]);
+        status.alerts_reviewed = true;
+        status.advance_zone_step(&otis, UpdateStep::Alert);
+        status.advance_zone_step(&otis, UpdateStep::Observation);
+        status.advance_zone_step(&otis, UpdateStep::Forecast);
+        assert_eq!(status.succeeded_zones(), maplit::hashset! { otis.clone() });
+
+        // -- a zone reporting several alerts notes a step for each
+        let state = status.advance_zone_step(&otis, UpdateStep::Alert);
+        assert_eq!(state, UpdateWeatherStateDiscriminants::Active);
+        assert_eq!(status.active_zones(), maplit::hashset! { stella.clone() });
+
+        status.advance_zone_step(&stella, UpdateStep::Alert);
+        status.advance_zone_step(&stella, UpdateStep::Observation);
+        let state = status.advance_zone_step(&stella, UpdateStep::Forecast);
+        assert_eq!(state, UpdateWeatherStateDiscriminants::Finished);
+
+        let state = status.advance_zone_step(&stella, UpdateStep::Alert);
+        assert_eq!(state, UpdateWeatherStateDiscriminants::Finished);
+    }
+
+    #[test]
+    fn test_weather_update_status_hourly_forecast() {
+        let update_id = UpdateWeatherId::for_labeled("update-1");
+        let zone = LocationZoneCode::new("otis");
+        let hourly = |zone: &LocationZoneCode| WeatherEvent::HourlyForecastUpdated {
+            zone: zone.clone(),
+            update_id: update_id.clone(),
+            forecast: Arc::new(HourlyForecast {
+                gridpoint: Gridpoint { office: "TOP".to_string(), x: 31, y: 80 },
+                updated: Utc::now(),
+                periods: vec![],
+            }),
+        };
+
+        let mut status = WeatherUpdateStatus::new(vec![zone.clone()]);
+        status.started_at = Some(Utc::now());
+        let state = status.mutate(hourly(&zone));
+        assert_eq!(state, UpdateWeatherStateDiscriminants::Active);
+        assert_eq!(
+            assert_some!(status.status_for(&zone)),
+            LocationUpdateStatus::InProgress(UpdateStep::HourlyForecast.into())
+        );
+
+        status.advance_zone_step(&zone, UpdateStep::Observation);
+        status.advance_zone_step(&zone, UpdateStep::Forecast);
+        let summary = status.summarize(Utc::now());
+        status.mutate(WeatherEvent::UpdateCompleted { update_id: update_id.clone(), summary });
+
+        let state = status.mutate(hourly(&zone));
+        assert_eq!(
+            state,
+            UpdateWeatherStateDiscriminants::Finished,
+            "late hourly forecast leaves the finished update finished"
+        );
+        assert_eq!(
+            assert_some!(status.status_for(&zone)),
+            LocationUpdateStatus::succeeded()
+        );
+    }
+
+    #[test]
+    fn test_weather_update_status_finish() {
+        let update_id = UpdateWeatherId::for_labeled("update-1");
+        let zone = LocationZoneCode::new("otis");
+        let work = |status: &mut WeatherUpdateStatus| {
+            status.mutate(WeatherEvent::AlertsReviewed { update_id: update_id.clone() });
+            status.mutate(WeatherEvent::UpdateLocationFailed {
+                update_id: update_id.clone(),
+                zone: zone.clone(),
+                cause: "NOAA unavailable".to_string(),
+            })
+        };
+
+        let mut status = WeatherUpdateStatus::new(vec![zone.clone()]);
+        status.started_at = Some(Utc::now());
+        assert_eq!(
+            work(&mut status),
+            UpdateWeatherStateDiscriminants::Active,
+            "update finishes when its finish is noted"
+        );
+        let summary = status.summarize(Utc::now());
+        let state =
+            status.mutate(WeatherEvent::UpdateFailed { update_id: update_id.clone(), summary });
+        assert_eq!(state, UpdateWeatherStateDiscriminants::Finished);
+
+        let mut legacy = WeatherUpdateStatus::new(vec![zone.clone()]);
+        assert_eq!(
+            work(&mut legacy),
+            UpdateWeatherStateDiscriminants::Finished,
+            "update started before finishes were noted finishes with its work"
+        );
+    }
 }
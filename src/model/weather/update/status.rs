@@ -16,6 +16,11 @@ pub enum UpdateStep {
     Observation = 0b0001,
     Forecast = 0b0010,
     Alert = 0b0100,
+
+    /// Hourly forecasts are supplemental: they are fetched alongside the zone's observation and
+    /// forecast and noted as a step while the zone's update is in progress, but the zone succeeds
+    /// without them and a failure to fetch them does not fail the update.
+    HourlyForecast = 0b1000,
 }
 
 pub type UpdateSteps = BitFlags<UpdateStep>;
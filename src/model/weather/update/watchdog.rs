@@ -0,0 +1,105 @@
+use super::{UpdateWeatherError, UpdateWeatherRepository};
+use crate::model::weather::WeatherDecisionMakerRef;
+use crate::settings::UpdateWeatherSettings;
+use chrono::Utc;
+use std::fmt;
+use std::time::Duration;
+use tokio_util::task::TaskTracker;
+
+/// Enforces the update deadline: updates still active past the deadline, e.g., because a zone
+/// update task panicked or hangs, are expired. The watchdog is tracked under the application
+/// `TaskTracker` and stops on `crate::shutdown()`.
+#[derive(Clone)]
+pub struct UpdateWeatherWatchdog {
+    deadline: Duration,
+    interval: Duration,
+    repository: UpdateWeatherRepository,
+    weather_dm: WeatherDecisionMakerRef,
+}
+
+impl fmt::Debug for UpdateWeatherWatchdog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateWeatherWatchdog")
+            .field("deadline", &self.deadline)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl UpdateWeatherWatchdog {
+    pub fn from_settings(
+        settings: &UpdateWeatherSettings, repository: UpdateWeatherRepository,
+        weather_dm: WeatherDecisionMakerRef,
+    ) -> Self {
+        Self {
+            deadline: settings.deadline,
+            interval: settings.watchdog_interval,
+            repository,
+            weather_dm,
+        }
+    }
+
+    pub fn spawn(self, task_tracker: &TaskTracker) {
+        if self.deadline.is_zero() || self.interval.is_zero() {
+            warn!(watchdog=?self, "update weather deadline not enforced - watchdog not started");
+            return;
+        }
+
+        task_tracker.spawn(async move { self.run().await });
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn run(self) {
+        info!(watchdog=?self, "starting update weather watchdog");
+
+        let shutdown = crate::shutdown();
+        tokio::pin!(shutdown);
+
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    info!("update weather watchdog shutting down");
+                    break;
+                },
+                _ = interval.tick() => {},
+            }
+
+            match self.expire_overdue_updates().await {
+                Ok(0) => {},
+                Ok(nr_expired) => {
+                    warn!(%nr_expired, "expired update weather processes past deadline")
+                },
+                Err(error) => error!(?error, "update weather watchdog failed to check updates"),
+            }
+        }
+    }
+
+    /// Expires the active updates started before the deadline, returning how many were expired.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn expire_overdue_updates(&self) -> Result<usize, UpdateWeatherError> {
+        let started_before = chrono::Duration::from_std(self.deadline)
+            .ok()
+            .and_then(|deadline| Utc::now().checked_sub_signed(deadline));
+        let Some(started_before) = started_before else {
+            return Ok(0);
+        };
+
+        let mut nr_expired = 0;
+        for view in self.repository.fetch_stuck_updates(started_before).await? {
+            let update_id = view.update_id;
+            let stuck_zones = view.update_statuses.active_zones();
+            match super::expire_update(update_id.clone(), self.weather_dm.clone()).await {
+                Ok(()) => {
+                    warn!(%update_id, ?stuck_zones, "update weather process expired past deadline");
+                    nr_expired += 1;
+                },
+                Err(error) => error!(?error, %update_id, "failed to expire update weather process"),
+            }
+        }
+
+        Ok(nr_expired)
+    }
+}
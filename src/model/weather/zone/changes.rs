@@ -0,0 +1,358 @@
+use super::LocationZoneError;
+use crate::model::weather::update::UpdateWeatherId;
+use crate::model::weather::{LocationZoneEvent, WeatherEventStore};
+use crate::model::{HourlyForecast, LocationZoneCode, WeatherAlert, WeatherFrame, ZoneForecast};
+use disintegrate::{query, EventStore, PersistedEvent};
+use futures::{Stream, StreamExt, TryStreamExt};
+use std::collections::HashSet;
+use std::sync::Arc;
+use strum_macros::IntoStaticStr;
+use tokio::sync::broadcast;
+
+pub const ZONE_WEATHER_CHANGES_CAPACITY: usize = 1_024;
+
+/// The most recorded changes a subscription replays before its live changes.
+pub const MAX_REPLAYED_ZONE_CHANGES: usize = 1_024;
+
+pub type ZoneWeatherChangeSender = broadcast::Sender<ZoneWeatherChange>;
+
+/// A change to a zone's weather, published as the zone weather projection writes it. The id of the
+/// change's event orders changes and is the point from which a subscription resumes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneWeatherChange {
+    pub event_id: i64,
+    pub zone: LocationZoneCode,
+    pub update_id: UpdateWeatherId,
+    pub change: ZoneChange,
+}
+
+#[derive(Debug, Clone, PartialEq, IntoStaticStr, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum ZoneChange {
+    ObservationUpdated {
+        weather: Arc<WeatherFrame>,
+    },
+    ForecastUpdated {
+        forecast: Arc<ZoneForecast>,
+    },
+    HourlyForecastUpdated {
+        forecast: Arc<HourlyForecast>,
+    },
+    AlertActivated {
+        alert_id: String,
+        alert: Arc<WeatherAlert>,
+    },
+    AlertUpdated {
+        alert_id: String,
+        alert: Arc<WeatherAlert>,
+    },
+    AlertExpired {
+        alert_id: String,
+    },
+    AlertDeactivated {
+        alert_id: Option<String>,
+    },
+}
+
+impl From<&PersistedEvent<LocationZoneEvent>> for ZoneWeatherChange {
+    fn from(event: &PersistedEvent<LocationZoneEvent>) -> Self {
+        use LocationZoneEvent as E;
+        use ZoneChange as C;
+
+        let (zone, update_id, change) = match &**event {
+            E::ObservationUpdated { zone, update_id, weather, .. } => (
+                zone,
+                update_id,
+                C::ObservationUpdated { weather: weather.clone() },
+            ),
+            E::ForecastUpdated { zone, update_id, forecast } => (
+                zone,
+                update_id,
+                C::ForecastUpdated { forecast: forecast.clone() },
+            ),
+            E::HourlyForecastUpdated { zone, update_id, forecast } => (
+                zone,
+                update_id,
+                C::HourlyForecastUpdated { forecast: forecast.clone() },
+            ),
+            E::AlertActivated { zone, update_id, alert_id, alert } => {
+                let alert_id = if alert_id.is_empty() { &alert.id } else { alert_id };
+                let change = C::AlertActivated { alert_id: alert_id.clone(), alert: alert.clone() };
+                (zone, update_id, change)
+            },
+            E::AlertUpdated { zone, update_id, alert_id, alert } => {
+                let change = C::AlertUpdated { alert_id: alert_id.clone(), alert: alert.clone() };
+                (zone, update_id, change)
+            },
+            E::AlertExpired { zone, update_id, alert_id } => (
+                zone,
+                update_id,
+                C::AlertExpired { alert_id: alert_id.clone() },
+            ),
+            E::AlertDeactivated { zone, update_id, alert_id } => (
+                zone,
+                update_id,
+                C::AlertDeactivated { alert_id: alert_id.clone() },
+            ),
+        };
+
+        Self {
+            event_id: event.id(),
+            zone: zone.clone(),
+            update_id: update_id.clone(),
+            change,
+        }
+    }
+}
+
+/// Message sent to a zone weather subscriber.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum ZoneWeatherMessage {
+    Change(ZoneWeatherChange),
+
+    /// The subscriber fell further behind the live changes than the change channel holds, and
+    /// the subscription ends. Subscribing again from `resume_from` replays the skipped changes;
+    /// `resume_from` is absent if no change was received before falling behind.
+    Lagged {
+        nr_skipped: u64,
+        resume_from: Option<i64>,
+    },
+
+    /// More changes were recorded after the subscription's resume point than a subscription
+    /// replays, and the subscription ends after the replayed ones. Subscribing again from
+    /// `resume_from` continues the replay.
+    ReplayTruncated {
+        resume_from: i64,
+    },
+}
+
+/// Streams the weather changes of the subscribed zones. A subscription resuming from an event id
+/// first replays the zones' changes recorded after it, up to `MAX_REPLAYED_ZONE_CHANGES`, then
+/// continues with live changes received from the subscription to the change channel.
+#[instrument(level = "debug", skip(es, changes_rx), err)]
+pub async fn zone_weather_changes(
+    zones: HashSet<LocationZoneCode>, resume_from: Option<i64>, es: &WeatherEventStore,
+    changes_rx: broadcast::Receiver<ZoneWeatherChange>,
+) -> Result<impl Stream<Item = ZoneWeatherMessage> + Send, LocationZoneError> {
+    // -- load one past the limit to tell whether the replay is truncated
+    let replayed = match resume_from {
+        Some(event_id) => {
+            replay_changes(&zones, event_id, MAX_REPLAYED_ZONE_CHANGES + 1, es).await?
+        },
+        None => vec![],
+    };
+
+    Ok(replayed_then_live_changes(
+        Arc::new(zones),
+        replayed,
+        MAX_REPLAYED_ZONE_CHANGES,
+        resume_from,
+        changes_rx,
+    ))
+}
+
+/// Streams the replayed changes followed by the live changes. If more changes were replayed than
+/// the limit, the stream instead ends after the limit with a `ReplayTruncated` message.
+fn replayed_then_live_changes(
+    zones: Arc<HashSet<LocationZoneCode>>, mut replayed: Vec<ZoneWeatherChange>,
+    max_replayed: usize, resume_from: Option<i64>,
+    changes_rx: broadcast::Receiver<ZoneWeatherChange>,
+) -> impl Stream<Item = ZoneWeatherMessage> + Send {
+    let truncated = if max_replayed < replayed.len() {
+        replayed.truncate(max_replayed);
+        replayed
+            .last()
+            .map(|change| ZoneWeatherMessage::ReplayTruncated { resume_from: change.event_id })
+    } else {
+        None
+    };
+
+    let last_event_id = replayed.last().map(|change| change.event_id).or(resume_from);
+    let live = truncated.is_none().then(|| live_changes(zones, last_event_id, changes_rx));
+
+    futures::stream::iter(replayed.into_iter().map(ZoneWeatherMessage::Change))
+        .chain(futures::stream::iter(truncated))
+        .chain(futures::stream::iter(live).flatten())
+}
+
+/// Loads at most `limit` of the zones' changes recorded after the event id from the event store.
+async fn replay_changes(
+    zones: &HashSet<LocationZoneCode>, after_event_id: i64, limit: usize, es: &WeatherEventStore,
+) -> Result<Vec<ZoneWeatherChange>, LocationZoneError> {
+    let query = zones
+        .iter()
+        .map(|zone| {
+            let zone = zone.clone();
+            query!(LocationZoneEvent, zone == zone)
+        })
+        .reduce(|acc, zone_query| acc.union::<LocationZoneEvent, _>(&zone_query));
+    let Some(query) = query.map(|q| q.change_origin(after_event_id)) else {
+        return Ok(vec![]);
+    };
+
+    let changes = es
+        .stream(&query)
+        .map_ok(|event| ZoneWeatherChange::from(&event))
+        .take(limit)
+        .try_collect()
+        .await?;
+    Ok(changes)
+}
+
+/// Streams the live changes of the zones after the last event id. The change channel is bounded,
+/// so a subscriber consuming slower than changes are published falls behind, is sent a `Lagged`
+/// message carrying the id of the last change it received and the stream ends.
+fn live_changes(
+    zones: Arc<HashSet<LocationZoneCode>>, last_event_id: Option<i64>,
+    changes_rx: broadcast::Receiver<ZoneWeatherChange>,
+) -> impl Stream<Item = ZoneWeatherMessage> + Send {
+    futures::stream::unfold(Some((changes_rx, last_event_id)), move |state| {
+        let zones = zones.clone();
+        async move {
+            let (mut changes_rx, mut last_event_id) = state?;
+            loop {
+                match changes_rx.recv().await {
+                    // -- changes of other zones are received in order, so also mark resume point
+                    Ok(change) if Some(change.event_id) > last_event_id => {
+                        last_event_id = Some(change.event_id);
+                        if zones.contains(&change.zone) {
+                            let message = ZoneWeatherMessage::Change(change);
+                            return Some((message, Some((changes_rx, last_event_id))));
+                        }
+                    },
+                    Ok(_) => {},
+                    Err(broadcast::error::RecvError::Lagged(nr_skipped)) => {
+                        warn!(
+                            ?last_event_id, %nr_skipped,
+                            "zone weather subscriber lagged - ending subscription"
+                        );
+                        let message =
+                            ZoneWeatherMessage::Lagged { nr_skipped, resume_from: last_event_id };
+                        return Some((message, None));
+                    },
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::weather::WeatherEvent;
+    use pretty_assertions::assert_eq;
+
+    fn expired(event_id: i64, zone: &LocationZoneCode) -> ZoneWeatherChange {
+        let event = WeatherEvent::AlertExpired {
+            zone: zone.clone(),
+            update_id: UpdateWeatherId::for_labeled("update-1"),
+            alert_id: format!("alert-{event_id}"),
+        };
+        let event = LocationZoneEvent::try_from(event).unwrap();
+        ZoneWeatherChange::from(&PersistedEvent::new(event_id, event))
+    }
+
+    #[test]
+    fn test_live_changes_of_subscribed_zones_after_resume_point() {
+        let subscribed = LocationZoneCode::new("WAZ558");
+        let other = LocationZoneCode::new("WAZ315");
+        let zones = Arc::new(maplit::hashset! { subscribed.clone() });
+
+        let (changes_tx, changes_rx) = broadcast::channel(16);
+        for (event_id, zone) in [
+            (3, &subscribed),
+            (4, &subscribed),
+            (5, &other),
+            (6, &subscribed),
+        ] {
+            changes_tx.send(expired(event_id, zone)).unwrap();
+        }
+        drop(changes_tx);
+
+        let event_ids: Vec<_> = tokio_test::block_on(
+            live_changes(zones, Some(3), changes_rx)
+                .map(|message| match message {
+                    ZoneWeatherMessage::Change(change) => Some(change.event_id),
+                    ZoneWeatherMessage::Lagged { .. }
+                    | ZoneWeatherMessage::ReplayTruncated { .. } => None,
+                })
+                .collect(),
+        );
+        assert_eq!(event_ids, vec![Some(4), Some(6)]);
+    }
+
+    #[test]
+    fn test_lagged_subscriber_told_to_resume_from_last_change() {
+        let zone = LocationZoneCode::new("WAZ558");
+        let zones = Arc::new(maplit::hashset! { zone.clone() });
+
+        let (changes_tx, changes_rx) = broadcast::channel(2);
+        for event_id in 1..=5 {
+            changes_tx.send(expired(event_id, &zone)).unwrap();
+        }
+
+        let messages: Vec<_> =
+            tokio_test::block_on(live_changes(zones, Some(1), changes_rx).collect());
+        assert_eq!(
+            messages,
+            vec![ZoneWeatherMessage::Lagged { nr_skipped: 3, resume_from: Some(1) }]
+        );
+    }
+
+    #[test]
+    fn test_replay_then_live_changes() {
+        let zone = LocationZoneCode::new("WAZ558");
+        let zones = Arc::new(maplit::hashset! { zone.clone() });
+
+        let (changes_tx, changes_rx) = broadcast::channel(16);
+        changes_tx.send(expired(2, &zone)).unwrap();
+        changes_tx.send(expired(3, &zone)).unwrap();
+        drop(changes_tx);
+
+        let replayed = vec![expired(2, &zone)];
+        let messages: Vec<_> = tokio_test::block_on(
+            replayed_then_live_changes(zones, replayed, 2, Some(1), changes_rx).collect(),
+        );
+        assert_eq!(
+            messages,
+            vec![
+                ZoneWeatherMessage::Change(expired(2, &zone)),
+                ZoneWeatherMessage::Change(expired(3, &zone)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncated_replay_told_to_resume_from_last_replayed() {
+        let zone = LocationZoneCode::new("WAZ558");
+        let zones = Arc::new(maplit::hashset! { zone.clone() });
+
+        let (changes_tx, changes_rx) = broadcast::channel(16);
+        changes_tx.send(expired(5, &zone)).unwrap();
+
+        let replayed = (2..=4).map(|event_id| expired(event_id, &zone)).collect();
+        let messages: Vec<_> = tokio_test::block_on(
+            replayed_then_live_changes(zones, replayed, 2, Some(1), changes_rx).collect(),
+        );
+        assert_eq!(
+            messages,
+            vec![
+                ZoneWeatherMessage::Change(expired(2, &zone)),
+                ZoneWeatherMessage::Change(expired(3, &zone)),
+                ZoneWeatherMessage::ReplayTruncated { resume_from: 3 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::model::weather::zone::LocationZoneError;
+use crate::model::weather::LocationZoneEvent;
+use crate::model::{Gridpoint, HourlyForecast, HourlyForecastPeriod, LocationZoneCode};
+use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use disintegrate::{query, EventListener, PersistedEvent, StreamQuery};
+use once_cell::sync::{Lazy, OnceCell};
+use sql_query_builder as sql;
+use sqlx::postgres::{PgQueryResult, PgRow};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, Row};
+use std::str::FromStr;
+
+pub const HOURLY_FORECAST_VIEW: &str = "zone_hourly_forecast";
+pub static HOURLY_FORECAST_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(HOURLY_FORECAST_VIEW).unwrap());
+static ZONE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone").unwrap());
+static OFFICE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("office").unwrap());
+static GRID_X_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("grid_x").unwrap());
+static GRID_Y_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("grid_y").unwrap());
+static UPDATED_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("updated").unwrap());
+static PERIODS_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("periods").unwrap());
+static EVENT_ID_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("event_id").unwrap());
+
+static COLUMNS: Lazy<[TableColumn; 8]> = Lazy::new(|| {
+    [
+        ZONE_COL.clone(),
+        OFFICE_COL.clone(),
+        GRID_X_COL.clone(),
+        GRID_Y_COL.clone(),
+        UPDATED_COL.clone(),
+        PERIODS_COL.clone(),
+        EVENT_ID_COL.clone(),
+        LAST_UPDATED_AT_COL.clone(),
+    ]
+});
+static COLUMNS_REP: Lazy<String> = Lazy::new(|| COLUMNS.join(", "));
+static VALUES_REP: Lazy<String> = Lazy::new(|| {
+    let values = (1..=COLUMNS.len()).map(|i| format!("${i}")).collect::<Vec<_>>().join(", ");
+
+    format!("( {values} )")
+});
+
+#[derive(Debug, Clone)]
+pub struct HourlyForecastRepository {
+    pool: PgPool,
+}
+
+impl HourlyForecastRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The zone's latest hourly forecast, keeping only the periods overlapping the time range, or
+    /// `None` if no hourly forecast has been noted for the zone.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn hourly_forecast(
+        &self, zone: &LocationZoneCode, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>,
+    ) -> Result<Option<HourlyForecast>, sqlx::Error> {
+        static SELECT_HOURLY_FORECAST_SQL: OnceCell<String> = OnceCell::new();
+        let sql = SELECT_HOURLY_FORECAST_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(OFFICE_COL.as_str())
+                .select(GRID_X_COL.as_str())
+                .select(GRID_Y_COL.as_str())
+                .select(UPDATED_COL.as_str())
+                .select(PERIODS_COL.as_str())
+                .from(&HOURLY_FORECAST_TABLE)
+                .where_clause(format!("{} = $1", ZONE_COL.as_str()).as_str())
+                .to_string()
+        });
+
+        let row = sqlx::query(sql).bind(zone).fetch_optional(&self.pool).await?;
+        let Some(forecast) = row.as_ref().map(Self::forecast_from_row).transpose()? else {
+            return Ok(None);
+        };
+
+        let periods = forecast.periods_between(from, to).cloned().collect();
+        Ok(Some(HourlyForecast { periods, ..forecast }))
+    }
+
+    fn forecast_from_row(row: &PgRow) -> Result<HourlyForecast, sqlx::Error> {
+        let grid_x: i32 = row.try_get(GRID_X_COL.clone())?;
+        let grid_y: i32 = row.try_get(GRID_Y_COL.clone())?;
+        let gridpoint = Gridpoint {
+            office: row.try_get(OFFICE_COL.clone())?,
+            x: u32::try_from(grid_x).map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+            y: u32::try_from(grid_y).map_err(|err| sqlx::Error::Decode(Box::new(err)))?,
+        };
+
+        let periods: Json<Vec<HourlyForecastPeriod>> = row.try_get(PERIODS_COL.clone())?;
+        Ok(HourlyForecast {
+            gridpoint,
+            updated: row.try_get(UPDATED_COL.clone())?,
+            periods: periods.0,
+        })
+    }
+}
+
+/// Postgres projection keeping each zone's latest hourly forecast, which supplements the zone's
+/// text forecast in `zone_weather`.
+#[derive(Debug)]
+pub struct HourlyForecastProjection {
+    query: StreamQuery<LocationZoneEvent>,
+    pool: PgPool,
+}
+
+impl HourlyForecastProjection {
+    pub async fn new(pool: PgPool) -> Result<Self, sqlx::Error> {
+        static CREATE_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {zone} TEXT NOT NULL,
+                    {office} TEXT NOT NULL,
+                    {grid_x} INTEGER NOT NULL,
+                    {grid_y} INTEGER NOT NULL,
+                    {updated} TIMESTAMPTZ NOT NULL,
+                    {periods} JSONB NOT NULL,
+                    {event_id} BIGINT NOT NULL,
+                    {last_updated_at} TIMESTAMPTZ NULL DEFAULT clock_timestamp(),
+                    PRIMARY KEY ( {zone} )
+                )"#,
+                table = HOURLY_FORECAST_TABLE.as_str(),
+                zone = ZONE_COL.as_str(),
+                office = OFFICE_COL.as_str(),
+                grid_x = GRID_X_COL.as_str(),
+                grid_y = GRID_Y_COL.as_str(),
+                updated = UPDATED_COL.as_str(),
+                periods = PERIODS_COL.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                last_updated_at = LAST_UPDATED_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&pool).await?;
+        Ok(Self { query: query(None), pool })
+    }
+}
+
+#[async_trait]
+impl EventListener<LocationZoneEvent> for HourlyForecastProjection {
+    type Error = LocationZoneError;
+
+    fn id(&self) -> &'static str {
+        &HOURLY_FORECAST_TABLE
+    }
+
+    fn query(&self) -> &StreamQuery<LocationZoneEvent> {
+        &self.query
+    }
+
+    #[allow(clippy::blocks_in_conditions)]
+    #[instrument(level = "debug", skip(self), err)]
+    async fn handle(&self, event: PersistedEvent<LocationZoneEvent>) -> Result<(), Self::Error> {
+        let event_id = event.id();
+        let LocationZoneEvent::HourlyForecastUpdated { zone, forecast, .. } = event.into_inner()
+        else {
+            return Ok(());
+        };
+
+        let mut tx = sqlx::Acquire::begin(&self.pool).await?;
+        let result = Self::update_or_insert_forecast(zone, &forecast, event_id, &mut tx).await?;
+
+        let outcome = tx.commit().await;
+        if let Err(ref error) = outcome {
+            error!("postgres projection failed to commit hourly forecast transaction: {error:?}");
+        }
+
+        debug!("hourly forecast projection postgres query result: {result:?}");
+        outcome.map_err(|err| err.into())
+    }
+}
+
+impl HourlyForecastProjection {
+    #[instrument(level = "debug", skip(forecast, tx), ret, err)]
+    async fn update_or_insert_forecast(
+        zone: LocationZoneCode, forecast: &HourlyForecast, event_id: i64, tx: &mut PgConnection,
+    ) -> Result<PgQueryResult, LocationZoneError> {
+        static UPDATE_OR_INSERT_FORECAST_SQL: OnceCell<String> = OnceCell::new();
+        let sql = UPDATE_OR_INSERT_FORECAST_SQL.get_or_init(|| {
+            let update_clause = COLUMNS
+                .iter()
+                .filter(|col| col.as_str() != ZONE_COL.as_str())
+                .fold(sql::Update::new(), |clause, col| {
+                    clause.set(format!("{col} = EXCLUDED.{col}").as_str())
+                });
+
+            let conflict_clause = format!("( {} ) DO UPDATE {update_clause}", ZONE_COL.as_str());
+
+            sql::Insert::new()
+                .insert_into(
+                    format!(
+                        "{table} ( {columns} )",
+                        table = HOURLY_FORECAST_TABLE.as_str(),
+                        columns = COLUMNS_REP.as_str()
+                    )
+                    .as_str(),
+                )
+                .values(&VALUES_REP)
+                .on_conflict(conflict_clause.as_str())
+                .to_string()
+        });
+
+        let gridpoint = &forecast.gridpoint;
+        sqlx::query(sql)
+            .bind(zone)
+            .bind(gridpoint.office.as_str())
+            .bind(gridpoint.x as i32)
+            .bind(gridpoint.y as i32)
+            .bind(forecast.updated)
+            .bind(Json(&forecast.periods))
+            .bind(event_id) // event_id
+            .bind(Utc::now()) // last_updated_at
+            .execute(tx)
+            .await
+            .map_err(|err| err.into())
+    }
+}
@@ -1,14 +1,26 @@
 use super::{LocationZoneCode, WeatherDecisionMakerRef};
-use crate::model::{LocationZoneType, WeatherAlert};
-use crate::services::noaa::ZoneWeatherApi;
+use crate::model::{LocationZone, WeatherAlert};
+use crate::services::noaa::{GridpointForecastApi, ZoneWeatherApi};
 
+pub mod changes;
+pub mod hourly_forecast;
+pub mod observation_history;
 pub mod protocol;
 pub mod read_model;
 mod services;
 mod state;
 
+pub use changes::{
+    zone_weather_changes, ZoneChange, ZoneWeatherChange, ZoneWeatherChangeSender,
+    ZoneWeatherMessage,
+};
 pub use errors::LocationZoneError;
-pub use read_model::WeatherRepository;
+pub use hourly_forecast::{HourlyForecastRepository, HOURLY_FORECAST_TABLE};
+pub use observation_history::{
+    ObservationHistory, ObservationHistoryQuery, ObservationHistoryRepository,
+    OBSERVATION_HISTORY_TABLE,
+};
+pub use read_model::{ForecastPeriods, WeatherRepository, ZoneWeather};
 pub use support::LocationZoneSupport;
 
 use crate::model::weather::update::UpdateWeatherId;
@@ -22,11 +34,15 @@ pub use read_model::ZONE_WEATHER_TABLE;
 
 #[instrument(level = "debug", skip(weather_dm), err)]
 pub async fn observe(
-    update_id: UpdateWeatherId, zone: LocationZoneCode, weather_dm: WeatherDecisionMakerRef,
+    update_id: UpdateWeatherId, zone: LocationZone, weather_dm: WeatherDecisionMakerRef,
 ) -> Result<(), LocationZoneError> {
-    let observation = services::services().zone_observation(&zone).await?;
+    let observation = services::services().zone_observation(zone.zone_type, &zone.code).await?;
     weather_dm
-        .make(protocol::NoteObservation::new(zone, update_id, observation))
+        .make(protocol::NoteObservation::new(
+            zone.code,
+            update_id,
+            observation,
+        ))
         .await
         .map_err(|err| LocationZoneError::Decision(Box::new(err)))?;
     Ok(())
@@ -34,25 +50,42 @@ pub async fn observe(
 
 #[instrument(level = "debug", skip(weather_dm), err)]
 pub async fn forecast(
-    update_id: UpdateWeatherId, zone: LocationZoneCode, weather_dm: WeatherDecisionMakerRef,
+    update_id: UpdateWeatherId, zone: LocationZone, weather_dm: WeatherDecisionMakerRef,
 ) -> Result<(), LocationZoneError> {
-    let forecast = services::services()
-        .zone_forecast(LocationZoneType::Forecast, &zone)
-        .await?;
+    let forecast = services::services().zone_forecast(zone.zone_type, &zone.code).await?;
     weather_dm
-        .make(protocol::NoteForecast::new(zone, update_id, forecast))
+        .make(protocol::NoteForecast::new(zone.code, update_id, forecast))
         .await
         .map_err(|err| LocationZoneError::Decision(Box::new(err)))?;
     Ok(())
 }
 
+/// Notes the hourly forecast of the zone's representative gridpoint.
 #[instrument(level = "debug", skip(weather_dm), err)]
-pub async fn alert(
-    update_id: UpdateWeatherId, zone: LocationZoneCode, alert: Option<WeatherAlert>,
+pub async fn hourly_forecast(
+    update_id: UpdateWeatherId, zone: LocationZone, weather_dm: WeatherDecisionMakerRef,
+) -> Result<(), LocationZoneError> {
+    let services = services::services();
+    let gridpoint = services.zone_gridpoint(zone.zone_type, &zone.code).await?;
+    let forecast = services.hourly_forecast(&gridpoint).await?;
+    weather_dm
+        .make(protocol::NoteHourlyForecast::new(
+            zone.code, update_id, forecast,
+        ))
+        .await
+        .map_err(|err| LocationZoneError::Decision(Box::new(err)))?;
+    Ok(())
+}
+
+/// Notes the complete set of alerts currently active for the zone. Alerts no longer reported are
+/// deactivated, or expired if no longer in force.
+#[instrument(level = "debug", skip(weather_dm), err)]
+pub async fn alerts(
+    update_id: UpdateWeatherId, zone: LocationZoneCode, alerts: Vec<WeatherAlert>,
     weather_dm: WeatherDecisionMakerRef,
 ) -> Result<(), LocationZoneError> {
     weather_dm
-        .make(protocol::NoteAlert::new(zone, update_id, alert))
+        .make(protocol::NoteAlerts::new(zone, update_id, alerts))
         .await
         .map_err(|err| LocationZoneError::Decision(Box::new(err)))?;
     Ok(())
@@ -106,10 +139,18 @@ mod errors {
 }
 
 mod support {
+    use super::changes::{ZoneWeatherChangeSender, ZONE_WEATHER_CHANGES_CAPACITY};
     use super::errors::LocationZoneError;
     use super::services::{self, LocationZoneServices, LocationZoneServicesRef};
+    use crate::model::weather::zone::hourly_forecast::{
+        HourlyForecastProjection, HourlyForecastRepository,
+    };
+    use crate::model::weather::zone::observation_history::{
+        ObservationHistoryProjection, ObservationHistoryRepository,
+    };
     use crate::model::weather::zone::read_model::WeatherRepository;
     use crate::model::weather::WeatherEventStore;
+    use crate::model::zone_metadata::ZoneMetadataRepository;
     use crate::services::noaa::NoaaWeatherServices;
     use anyhow::anyhow;
     use disintegrate_postgres::{PgEventListener, PgEventListenerConfig};
@@ -122,7 +163,10 @@ mod support {
     pub struct LocationZoneSupport {
         // pub decision_maker: LocationZoneDecisionMakerRef,
         pub weather_repository: WeatherRepository,
+        pub observation_repository: ObservationHistoryRepository,
+        pub hourly_forecast_repository: HourlyForecastRepository,
         pub services: LocationZoneServicesRef,
+        pub changes: ZoneWeatherChangeSender,
     }
 
     impl LocationZoneSupport {
@@ -138,21 +182,32 @@ mod support {
             //     5,
             // ));
 
-            if let Err(prior) =
-                services::initialize_services(Arc::new(LocationZoneServices::new(noaa)))
-            {
+            if let Err(prior) = services::initialize_services(Arc::new(LocationZoneServices::new(
+                noaa,
+                ZoneMetadataRepository::new(pool.clone()),
+            ))) {
                 warn!("location zone service instance overwritten: {prior:?}");
             }
 
             let weather_repository = WeatherRepository::new(pool.clone());
+            let observation_repository = ObservationHistoryRepository::new(pool.clone());
+            let hourly_forecast_repository = HourlyForecastRepository::new(pool.clone());
+            let (changes, _) = tokio::sync::broadcast::channel(ZONE_WEATHER_CHANGES_CAPACITY);
 
+            let projection_changes = changes.clone();
             task_tracker.spawn(async move {
                 let weather_projection =
-                    super::read_model::ZoneWeatherProjection::new(pool).await?;
+                    super::read_model::ZoneWeatherProjection::new(pool.clone(), projection_changes)
+                        .await?;
+                let observation_projection =
+                    ObservationHistoryProjection::new(pool.clone()).await?;
+                let hourly_forecast_projection = HourlyForecastProjection::new(pool).await?;
 
                 let listener_config = PgEventListenerConfig::poller(Duration::from_millis(50));
                 PgEventListener::builder(es)
-                    .register_listener(weather_projection, listener_config)
+                    .register_listener(weather_projection, listener_config.clone())
+                    .register_listener(observation_projection, listener_config.clone())
+                    .register_listener(hourly_forecast_projection, listener_config)
                     .start_with_shutdown(crate::shutdown())
                     .await
                     .map_err(|e| {
@@ -161,7 +216,13 @@ mod support {
                 Ok::<(), anyhow::Error>(())
             });
 
-            Ok(Self { weather_repository, services: services::services() })
+            Ok(Self {
+                weather_repository,
+                observation_repository,
+                hourly_forecast_repository,
+                services: services::services(),
+                changes,
+            })
         }
     }
 }
@@ -0,0 +1,567 @@
+use crate::model::weather::zone::LocationZoneError;
+use crate::model::weather::LocationZoneEvent;
+use crate::model::{LocationZoneCode, QualityControl, QuantitativeProperty, WeatherFrame};
+use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use disintegrate::{query, EventListener, PersistedEvent, StreamQuery};
+use once_cell::sync::{Lazy, OnceCell};
+use rust_decimal::Decimal;
+use sql_query_builder as sql;
+use sqlx::postgres::{PgQueryResult, PgRow};
+use sqlx::{PgConnection, PgPool, Row};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use strum::IntoEnumIterator;
+
+pub const OBSERVATION_HISTORY_VIEW: &str = "zone_observation_history";
+pub static OBSERVATION_HISTORY_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(OBSERVATION_HISTORY_VIEW).unwrap());
+static ZONE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone").unwrap());
+static OBSERVED_AT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("observed_at").unwrap());
+static EVENT_ID_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("event_id").unwrap());
+static BUCKET_START_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("bucket_start").unwrap());
+static NR_OBSERVATIONS_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("nr_observations").unwrap());
+
+/// Each quantitative property is recorded in its own value column alongside columns for the
+/// property's unit and quality control.
+#[derive(Debug, Clone)]
+struct PropertyColumns {
+    property: QuantitativeProperty,
+    value: TableColumn,
+    unit: TableColumn,
+    quality_control: TableColumn,
+}
+
+impl PropertyColumns {
+    fn new(property: QuantitativeProperty) -> Self {
+        let name = property.snake_case_name();
+        Self {
+            property,
+            value: TableColumn::new(name).unwrap(),
+            unit: TableColumn::new(format!("{name}_unit")).unwrap(),
+            quality_control: TableColumn::new(format!("{name}_qc")).unwrap(),
+        }
+    }
+
+    fn aggregate_column(&self, aggregate: &str) -> String {
+        format!("{}_{aggregate}", self.value.as_str())
+    }
+}
+
+static PROPERTY_COLUMNS: Lazy<Vec<PropertyColumns>> =
+    Lazy::new(|| QuantitativeProperty::iter().map(PropertyColumns::new).collect());
+
+fn property_columns(property: QuantitativeProperty) -> &'static PropertyColumns {
+    PROPERTY_COLUMNS.iter().find(|pc| pc.property == property).unwrap()
+}
+
+static COLUMNS: Lazy<Vec<TableColumn>> = Lazy::new(|| {
+    let mut columns = vec![ZONE_COL.clone(), OBSERVED_AT_COL.clone()];
+    for pc in PROPERTY_COLUMNS.iter() {
+        columns.push(pc.value.clone());
+        columns.push(pc.unit.clone());
+        columns.push(pc.quality_control.clone());
+    }
+    columns.push(EVENT_ID_COL.clone());
+    columns.push(LAST_UPDATED_AT_COL.clone());
+    columns
+});
+static COLUMNS_REP: Lazy<String> = Lazy::new(|| COLUMNS.join(", "));
+static VALUES_REP: Lazy<String> = Lazy::new(|| {
+    let values = (1..=COLUMNS.len()).map(|i| format!("${i}")).collect::<Vec<_>>().join(", ");
+
+    format!("( {values} )")
+});
+
+pub const DEFAULT_OBSERVATION_LIMIT: usize = 100;
+pub const MAX_OBSERVATION_LIMIT: usize = 1_000;
+
+/// Selects a page of a zone's observation history. Observations are returned in observation time
+/// order, either individually or downsampled into fixed `bucket` intervals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservationHistoryQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub properties: Vec<QuantitativeProperty>,
+    pub limit: usize,
+    pub cursor: Option<DateTime<Utc>>,
+    pub bucket: Option<Duration>,
+}
+
+impl Default for ObservationHistoryQuery {
+    fn default() -> Self {
+        Self {
+            from: None,
+            to: None,
+            properties: QuantitativeProperty::iter().collect(),
+            limit: DEFAULT_OBSERVATION_LIMIT,
+            cursor: None,
+            bucket: None,
+        }
+    }
+}
+
+impl ObservationHistoryQuery {
+    /// Parses a comma-separated list of property names, e.g., `temperature,windSpeed`.
+    pub fn parse_properties(rep: &str) -> Result<Vec<QuantitativeProperty>, strum::ParseError> {
+        let mut properties = vec![];
+        for name in rep.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            let property = QuantitativeProperty::from_str(name)?;
+            if !properties.contains(&property) {
+                properties.push(property);
+            }
+        }
+        Ok(properties)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservationHistory {
+    pub zone: LocationZoneCode,
+
+    #[serde(flatten)]
+    pub series: ObservationSeries,
+
+    /// Pass as the `cursor` of the following request to continue with the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ObservationSeries {
+    Observations(Vec<ZoneObservation>),
+    Buckets(Vec<ObservationBucket>),
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneObservation {
+    pub observed_at: DateTime<Utc>,
+    pub values: BTreeMap<String, ObservedValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservedValue {
+    pub value: Decimal,
+    pub unit_code: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality_control: Option<QualityControl>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservationBucket {
+    pub bucket_start: DateTime<Utc>,
+    pub nr_observations: i64,
+    pub values: BTreeMap<String, BucketStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketStatistics {
+    pub min: Decimal,
+    pub avg: Decimal,
+    pub max: Decimal,
+    pub unit_code: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ObservationHistoryRepository {
+    pool: PgPool,
+}
+
+impl ObservationHistoryRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn observation_history(
+        &self, zone: &LocationZoneCode, query: &ObservationHistoryQuery,
+    ) -> Result<ObservationHistory, sqlx::Error> {
+        match query.bucket {
+            None => self.observations(zone, query).await,
+            Some(bucket) => self.buckets(zone, bucket, query).await,
+        }
+    }
+
+    fn history_select(cursor_condition: &str) -> sql::Select {
+        let observed_at = OBSERVED_AT_COL.as_str();
+        sql::Select::new()
+            .from(&OBSERVATION_HISTORY_TABLE)
+            .where_clause(format!("{} = $1", ZONE_COL.as_str()).as_str())
+            .where_and(format!("( $2::TIMESTAMPTZ IS NULL OR {observed_at} >= $2 )").as_str())
+            .where_and(format!("( $3::TIMESTAMPTZ IS NULL OR {observed_at} < $3 )").as_str())
+            .where_and(format!("( $4::TIMESTAMPTZ IS NULL OR {cursor_condition} )").as_str())
+    }
+
+    async fn observations(
+        &self, zone: &LocationZoneCode, query: &ObservationHistoryQuery,
+    ) -> Result<ObservationHistory, sqlx::Error> {
+        let observed_at = OBSERVED_AT_COL.as_str();
+        let select = query
+            .properties
+            .iter()
+            .map(|p| property_columns(*p))
+            .fold(
+                Self::history_select(&format!("{observed_at} > $4")),
+                |select, pc| {
+                    select
+                        .select(pc.value.as_str())
+                        .select(pc.unit.as_str())
+                        .select(pc.quality_control.as_str())
+                },
+            )
+            .select(observed_at)
+            .order_by(observed_at);
+        let sql = format!("{select} LIMIT $5");
+
+        let rows = sqlx::query(&sql)
+            .bind(zone)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(query.cursor)
+            .bind((query.limit + 1) as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut observations = rows
+            .iter()
+            .map(|row| Self::observation_from_row(row, &query.properties))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let next_cursor = if query.limit < observations.len() {
+            observations.truncate(query.limit);
+            observations.last().map(|o| o.observed_at)
+        } else {
+            None
+        };
+
+        Ok(ObservationHistory {
+            zone: zone.clone(),
+            series: ObservationSeries::Observations(observations),
+            next_cursor,
+        })
+    }
+
+    fn observation_from_row(
+        row: &PgRow, properties: &[QuantitativeProperty],
+    ) -> Result<ZoneObservation, sqlx::Error> {
+        let observed_at = row.try_get(OBSERVED_AT_COL.clone())?;
+
+        let mut values = BTreeMap::new();
+        for property in properties {
+            let pc = property_columns(*property);
+            let Some(value) = row.try_get::<Option<Decimal>, _>(pc.value.clone())? else {
+                continue;
+            };
+
+            let unit_code: Option<String> = row.try_get(pc.unit.clone())?;
+            let quality_control: Option<String> = row.try_get(pc.quality_control.clone())?;
+            let quality_control = quality_control
+                .map(|qc| QualityControl::from_str(&qc))
+                .transpose()
+                .map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+
+            values.insert(
+                property.to_string(),
+                ObservedValue {
+                    value,
+                    unit_code: unit_code.unwrap_or_default(),
+                    quality_control,
+                },
+            );
+        }
+
+        Ok(ZoneObservation { observed_at, values })
+    }
+
+    async fn buckets(
+        &self, zone: &LocationZoneCode, bucket: Duration, query: &ObservationHistoryQuery,
+    ) -> Result<ObservationHistory, sqlx::Error> {
+        let observed_at = OBSERVED_AT_COL.as_str();
+        let bucket_start = BUCKET_START_COL.as_str();
+        let select = query
+            .properties
+            .iter()
+            .map(|p| property_columns(*p))
+            .fold(
+                Self::history_select(&format!("{observed_at} >= $4")),
+                |select, pc| {
+                    let value = pc.value.as_str();
+                    select
+                        .select(format!("min({value}) AS {}", pc.aggregate_column("min")).as_str())
+                        .select(format!("avg({value}) AS {}", pc.aggregate_column("avg")).as_str())
+                        .select(format!("max({value}) AS {}", pc.aggregate_column("max")).as_str())
+                        .select(format!("max({}) AS {}", pc.unit, pc.unit).as_str())
+                },
+            )
+            .select(
+                format!(
+                    "to_timestamp(floor(extract(epoch FROM {observed_at}) / $6) * $6) AS \
+                     {bucket_start}"
+                )
+                .as_str(),
+            )
+            .select(format!("count(*) AS {}", NR_OBSERVATIONS_COL.as_str()).as_str())
+            .group_by(bucket_start)
+            .order_by(bucket_start);
+        let sql = format!("{select} LIMIT $5");
+
+        let rows = sqlx::query(&sql)
+            .bind(zone)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(query.cursor)
+            .bind((query.limit + 1) as i64)
+            .bind(bucket.as_secs_f64())
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut buckets = rows
+            .iter()
+            .map(|row| Self::bucket_from_row(row, &query.properties))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // buckets are aligned to the epoch, so the next page resumes at the following bucket
+        let next_cursor = if query.limit < buckets.len() {
+            buckets.truncate(query.limit);
+            let bucket = chrono::Duration::from_std(bucket).ok();
+            buckets
+                .last()
+                .zip(bucket)
+                .and_then(|(b, width)| b.bucket_start.checked_add_signed(width))
+        } else {
+            None
+        };
+
+        Ok(ObservationHistory {
+            zone: zone.clone(),
+            series: ObservationSeries::Buckets(buckets),
+            next_cursor,
+        })
+    }
+
+    fn bucket_from_row(
+        row: &PgRow, properties: &[QuantitativeProperty],
+    ) -> Result<ObservationBucket, sqlx::Error> {
+        let bucket_start = row.try_get(BUCKET_START_COL.clone())?;
+        let nr_observations = row.try_get(NR_OBSERVATIONS_COL.clone())?;
+
+        let mut values = BTreeMap::new();
+        for property in properties {
+            let pc = property_columns(*property);
+            let min: Option<Decimal> = row.try_get(pc.aggregate_column("min").as_str())?;
+            let avg: Option<Decimal> = row.try_get(pc.aggregate_column("avg").as_str())?;
+            let max: Option<Decimal> = row.try_get(pc.aggregate_column("max").as_str())?;
+            let unit_code: Option<String> = row.try_get(pc.unit.clone())?;
+
+            if let (Some(min), Some(avg), Some(max)) = (min, avg, max) {
+                let unit_code = unit_code.unwrap_or_default();
+                values.insert(
+                    property.to_string(),
+                    BucketStatistics { min, avg, max, unit_code },
+                );
+            }
+        }
+
+        Ok(ObservationBucket { bucket_start, nr_observations, values })
+    }
+}
+
+/// Postgres projection appending each zone observation to the zone's observation history, so
+/// past weather remains available after `zone_weather` moves on to the latest observation.
+#[derive(Debug)]
+pub struct ObservationHistoryProjection {
+    query: StreamQuery<LocationZoneEvent>,
+    pool: PgPool,
+}
+
+impl ObservationHistoryProjection {
+    pub async fn new(pool: PgPool) -> Result<Self, sqlx::Error> {
+        static CREATE_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_TABLE_DDL.get_or_init(|| {
+            let property_columns = PROPERTY_COLUMNS
+                .iter()
+                .map(|pc| {
+                    format!(
+                        "{value} NUMERIC NULL, {unit} TEXT NULL, {qc} TEXT NULL,",
+                        value = pc.value,
+                        unit = pc.unit,
+                        qc = pc.quality_control,
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n                    ");
+
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {zone} TEXT NOT NULL,
+                    {observed_at} TIMESTAMPTZ NOT NULL,
+                    {property_columns}
+                    {event_id} BIGINT NOT NULL,
+                    {last_updated_at} TIMESTAMPTZ NULL DEFAULT clock_timestamp(),
+                    PRIMARY KEY ( {zone}, {observed_at} )
+                )"#,
+                table = OBSERVATION_HISTORY_TABLE.as_str(),
+                zone = ZONE_COL.as_str(),
+                observed_at = OBSERVED_AT_COL.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                last_updated_at = LAST_UPDATED_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&pool).await?;
+        Ok(Self { query: query(None), pool })
+    }
+}
+
+#[async_trait]
+impl EventListener<LocationZoneEvent> for ObservationHistoryProjection {
+    type Error = LocationZoneError;
+
+    fn id(&self) -> &'static str {
+        &OBSERVATION_HISTORY_TABLE
+    }
+
+    fn query(&self) -> &StreamQuery<LocationZoneEvent> {
+        &self.query
+    }
+
+    #[allow(clippy::blocks_in_conditions)]
+    #[instrument(level = "debug", skip(self), err)]
+    async fn handle(&self, event: PersistedEvent<LocationZoneEvent>) -> Result<(), Self::Error> {
+        let event_id = event.id();
+        let LocationZoneEvent::ObservationUpdated { zone, weather, .. } = event.into_inner() else {
+            return Ok(());
+        };
+
+        let mut tx = sqlx::Acquire::begin(&self.pool).await?;
+        let result = Self::record_observation(zone, &weather, event_id, &mut tx).await?;
+
+        let outcome = tx.commit().await;
+        if let Err(ref error) = outcome {
+            error!(
+                "postgres projection failed to commit observation history transaction: {error:?}"
+            );
+        }
+
+        debug!("observation history projection postgres query result: {result:?}");
+        outcome.map_err(|err| err.into())
+    }
+}
+
+impl ObservationHistoryProjection {
+    #[instrument(level = "debug", skip(weather, tx), ret, err)]
+    async fn record_observation(
+        zone: LocationZoneCode, weather: &WeatherFrame, event_id: i64, tx: &mut PgConnection,
+    ) -> Result<PgQueryResult, LocationZoneError> {
+        static RECORD_OBSERVATION_SQL: OnceCell<String> = OnceCell::new();
+        let sql = RECORD_OBSERVATION_SQL.get_or_init(|| {
+            let key = [ZONE_COL.as_str(), OBSERVED_AT_COL.as_str()];
+            let update_clause = COLUMNS
+                .iter()
+                .filter(|col| !key.contains(&col.as_str()))
+                .fold(sql::Update::new(), |clause, col| {
+                    clause.set(format!("{col} = EXCLUDED.{col}").as_str())
+                });
+
+            let conflict_clause = format!("( {} ) DO UPDATE {update_clause}", key.join(", "));
+
+            sql::Insert::new()
+                .insert_into(
+                    format!(
+                        "{table} ( {columns} )",
+                        table = OBSERVATION_HISTORY_TABLE.as_str(),
+                        columns = COLUMNS_REP.as_str()
+                    )
+                    .as_str(),
+                )
+                .values(&VALUES_REP)
+                .on_conflict(conflict_clause.as_str())
+                .to_string()
+        });
+
+        let observed_at: DateTime<Utc> = SystemTime::from(weather.timestamp).into();
+
+        let query = PROPERTY_COLUMNS.iter().fold(
+            sqlx::query(sql).bind(zone).bind(observed_at),
+            |query, pc| {
+                let value = weather.property(pc.property);
+                query
+                    .bind(value.map(|v| v.value))
+                    .bind(value.map(|v| v.unit_code().to_string()))
+                    .bind(value.map(|v| v.quality_control.to_string()))
+            },
+        );
+
+        query
+            .bind(event_id) // event_id
+            .bind(Utc::now()) // last_updated_at
+            .execute(tx)
+            .await
+            .map_err(|err| err.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_properties() {
+        assert_eq!(
+            assert_ok!(ObservationHistoryQuery::parse_properties(
+                "temperature, windSpeed,WINDSPEED,,heatIndex"
+            )),
+            vec![
+                QuantitativeProperty::Temperature,
+                QuantitativeProperty::WindSpeed,
+                QuantitativeProperty::HeatIndex,
+            ]
+        );
+
+        assert_eq!(
+            assert_ok!(ObservationHistoryQuery::parse_properties("")),
+            Vec::<QuantitativeProperty>::new()
+        );
+
+        assert_err!(ObservationHistoryQuery::parse_properties(
+            "temperature,humidex"
+        ));
+    }
+
+    #[test]
+    fn test_property_columns() {
+        assert_eq!(COLUMNS.len(), 2 + 3 * PROPERTY_COLUMNS.len() + 2);
+
+        let max_temperature = property_columns(QuantitativeProperty::MaxTemperatureLast24Hours);
+        assert_eq!(
+            max_temperature.value.as_str(),
+            "max_temperature_last_24_hours"
+        );
+        assert_eq!(
+            max_temperature.unit.as_str(),
+            "max_temperature_last_24_hours_unit"
+        );
+        assert_eq!(
+            max_temperature.quality_control.as_str(),
+            "max_temperature_last_24_hours_qc"
+        );
+        assert_eq!(
+            max_temperature.aggregate_column("avg"),
+            "max_temperature_last_24_hours_avg"
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use crate::model::weather::update::UpdateWeatherId;
 use crate::model::weather::zone::errors::LocationZoneError;
 use crate::model::weather::zone::state::{
-    LocationZoneAlert, LocationZoneForecast, LocationZoneWeather,
+    LocationZoneAlert, LocationZoneForecast, LocationZoneHourlyForecast, LocationZoneWeather,
 };
 use crate::model::weather::WeatherEvent;
-use crate::model::{LocationZoneCode, WeatherAlert, WeatherFrame, ZoneForecast};
+use crate::model::{
+    HourlyForecast, LocationZoneCode, ObservationReport, StationObservation, WeatherAlert,
+    WeatherFrame, ZoneForecast,
+};
+use chrono::{DateTime, Utc};
 use disintegrate::Decision;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 #[derive(Debug, PartialEq)]
@@ -13,11 +18,19 @@ pub struct NoteObservation {
     zone: LocationZoneCode,
     update_id: UpdateWeatherId,
     weather: Arc<WeatherFrame>,
+    stations: Arc<Vec<StationObservation>>,
 }
 
 impl NoteObservation {
-    pub fn new(zone: LocationZoneCode, update_id: UpdateWeatherId, weather: WeatherFrame) -> Self {
-        Self { zone, update_id, weather: Arc::new(weather) }
+    pub fn new(
+        zone: LocationZoneCode, update_id: UpdateWeatherId, observation: ObservationReport,
+    ) -> Self {
+        Self {
+            zone,
+            update_id,
+            weather: Arc::new(observation.weather),
+            stations: Arc::new(observation.stations),
+        }
     }
 }
 
@@ -36,6 +49,7 @@ impl Decision for NoteObservation {
             zone: self.zone.clone(),
             update_id: self.update_id.clone(),
             weather: self.weather.clone(),
+            stations: self.stations.clone(),
         }])
     }
 }
@@ -73,21 +87,71 @@ impl Decision for NoteForecast {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct NoteAlert {
+pub struct NoteHourlyForecast {
     zone: LocationZoneCode,
     update_id: UpdateWeatherId,
-    alert: Option<Arc<WeatherAlert>>,
+    forecast: Arc<HourlyForecast>,
 }
 
-impl NoteAlert {
+impl NoteHourlyForecast {
     pub fn new(
-        zone: LocationZoneCode, update_id: UpdateWeatherId, alert: Option<WeatherAlert>,
+        zone: LocationZoneCode, update_id: UpdateWeatherId, forecast: HourlyForecast,
+    ) -> Self {
+        Self { zone, update_id, forecast: Arc::new(forecast) }
+    }
+}
+
+impl Decision for NoteHourlyForecast {
+    type Event = WeatherEvent;
+    type StateQuery = LocationZoneHourlyForecast;
+    type Error = LocationZoneError;
+
+    fn state_query(&self) -> Self::StateQuery {
+        LocationZoneHourlyForecast::new(self.zone.clone())
+    }
+
+    #[instrument(level = "debug", name = "NoteHourlyForecast::process", ret, err)]
+    fn process(&self, _: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![WeatherEvent::HourlyForecastUpdated {
+            zone: self.zone.clone(),
+            update_id: self.update_id.clone(),
+            forecast: self.forecast.clone(),
+        }])
+    }
+}
+
+/// Reconciles the alerts NOAA currently reports for a zone against the zone's active alerts.
+/// Alerts are identified by their NOAA id, and an alert is expired once it is no longer in force
+/// as of the time the alerts were noted.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NoteAlerts {
+    zone: LocationZoneCode,
+    update_id: UpdateWeatherId,
+    alerts: Vec<Arc<WeatherAlert>>,
+    as_of: DateTime<Utc>,
+}
+
+impl NoteAlerts {
+    pub fn new(
+        zone: LocationZoneCode, update_id: UpdateWeatherId, alerts: Vec<WeatherAlert>,
+    ) -> Self {
+        Self::as_of(zone, update_id, alerts, Utc::now())
+    }
+
+    pub fn as_of(
+        zone: LocationZoneCode, update_id: UpdateWeatherId, alerts: Vec<WeatherAlert>,
+        as_of: DateTime<Utc>,
     ) -> Self {
-        Self { zone, update_id, alert: alert.map(Arc::new) }
+        Self {
+            zone,
+            update_id,
+            alerts: alerts.into_iter().map(Arc::new).collect(),
+            as_of,
+        }
     }
 }
 
-impl Decision for NoteAlert {
+impl Decision for NoteAlerts {
     type Event = WeatherEvent;
     type StateQuery = LocationZoneAlert;
     type Error = LocationZoneError;
@@ -96,22 +160,194 @@ impl Decision for NoteAlert {
         LocationZoneAlert::new(self.zone.clone())
     }
 
-    #[instrument(level = "debug", name = "NoteAlert::process", ret, err)]
+    #[instrument(level = "debug", name = "NoteAlerts::process", ret, err)]
     fn process(&self, state: &Self::StateQuery) -> Result<Vec<Self::Event>, Self::Error> {
-        let event = match (state.active_alert(), &self.alert) {
-            (false, Some(alert)) => Some(WeatherEvent::AlertActivated {
-                zone: self.zone.clone(),
-                update_id: self.update_id.clone(),
-                alert: alert.clone(),
-            }),
-            (true, None) => Some(WeatherEvent::AlertDeactivated {
-                zone: self.zone.clone(),
-                update_id: self.update_id.clone(),
-            }),
-            _ => None,
-        };
+        let zone = || self.zone.clone();
+        let update_id = || self.update_id.clone();
+
+        let mut events = vec![];
+        let mut reported = HashSet::with_capacity(self.alerts.len());
+
+        for alert in self.alerts.iter() {
+            if !reported.insert(alert.id.as_str()) {
+                continue;
+            }
+
+            let alert_id = alert.id.clone();
+            let event = match state.active_alert(&alert.id) {
+                Some(_) if alert.is_expired(self.as_of) => Some(WeatherEvent::AlertExpired {
+                    zone: zone(),
+                    update_id: update_id(),
+                    alert_id,
+                }),
+                None if alert.is_expired(self.as_of) => None,
+                None => Some(WeatherEvent::AlertActivated {
+                    zone: zone(),
+                    update_id: update_id(),
+                    alert_id,
+                    alert: alert.clone(),
+                }),
+                Some(active) if active != alert => Some(WeatherEvent::AlertUpdated {
+                    zone: zone(),
+                    update_id: update_id(),
+                    alert_id,
+                    alert: alert.clone(),
+                }),
+                Some(_) => None,
+            };
+
+            events.extend(event);
+        }
+
+        let mut withdrawn: Vec<_> = state
+            .active_alerts()
+            .iter()
+            .filter(|(alert_id, _)| !reported.contains(alert_id.as_str()))
+            .collect();
+        withdrawn.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+
+        for (alert_id, alert) in withdrawn {
+            let alert_id = alert_id.clone();
+            let event = if alert.is_expired(self.as_of) {
+                WeatherEvent::AlertExpired { zone: zone(), update_id: update_id(), alert_id }
+            } else {
+                WeatherEvent::AlertDeactivated {
+                    zone: zone(),
+                    update_id: update_id(),
+                    alert_id: Some(alert_id),
+                }
+            };
+            events.push(event);
+        }
 
-        let events: Vec<Self::Event> = event.into_iter().collect();
         Ok(events)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        AlertCategory, AlertCertainty, AlertMessageType, AlertResponse, AlertSeverity, AlertStatus,
+        AlertUrgency,
+    };
+    use crate::testing;
+    use chrono::TimeZone;
+    use once_cell::sync::Lazy;
+    use WeatherEvent as E;
+
+    static ZONE: Lazy<LocationZoneCode> = Lazy::new(|| LocationZoneCode::new("WAZ558"));
+    static UPDATE_ID: Lazy<UpdateWeatherId> =
+        Lazy::new(|| UpdateWeatherId::for_labeled("update-1"));
+    static NOW: Lazy<DateTime<Utc>> =
+        Lazy::new(|| Utc.with_ymd_and_hms(2024, 3, 7, 12, 0, 0).unwrap());
+
+    fn alert(id: &str, event: &str, expires_in: chrono::Duration) -> WeatherAlert {
+        WeatherAlert {
+            id: id.to_string(),
+            affected_zones: vec![ZONE.clone()],
+            status: AlertStatus::Actual,
+            message_type: AlertMessageType::Alert,
+            sent: *NOW - chrono::Duration::hours(1),
+            effective: *NOW - chrono::Duration::hours(1),
+            onset: None,
+            expires: *NOW + expires_in,
+            ends: None,
+            category: AlertCategory::Met,
+            severity: AlertSeverity::Moderate,
+            certainty: AlertCertainty::Likely,
+            urgency: AlertUrgency::Expected,
+            event: event.to_string(),
+            headline: None,
+            description: format!("{event} description"),
+            instruction: None,
+            response: AlertResponse::Monitor,
+        }
+    }
+
+    fn activated(alert: &WeatherAlert) -> WeatherEvent {
+        E::AlertActivated {
+            zone: ZONE.clone(),
+            update_id: UPDATE_ID.clone(),
+            alert_id: alert.id.clone(),
+            alert: Arc::new(alert.clone()),
+        }
+    }
+
+    fn note(alerts: Vec<WeatherAlert>) -> NoteAlerts {
+        NoteAlerts::as_of(ZONE.clone(), UPDATE_ID.clone(), alerts, *NOW)
+    }
+
+    #[test]
+    fn it_activates_each_concurrent_alert() {
+        let wind = alert("wind", "High Wind Watch", chrono::Duration::hours(2));
+        let flood = alert("flood", "Flood Warning", chrono::Duration::hours(6));
+
+        testing::TestHarness::given([])
+            .when(note(vec![wind.clone(), flood.clone(), wind.clone()]))
+            .then([activated(&wind), activated(&flood)]);
+    }
+
+    #[test]
+    fn it_updates_changed_alert_and_ignores_unchanged_alert() {
+        let wind = alert("wind", "High Wind Watch", chrono::Duration::hours(2));
+        let flood = alert("flood", "Flood Warning", chrono::Duration::hours(6));
+        let wind_upgrade = WeatherAlert { severity: AlertSeverity::Severe, ..wind.clone() };
+
+        testing::TestHarness::given([activated(&wind), activated(&flood)])
+            .when(note(vec![wind_upgrade.clone(), flood]))
+            .then([E::AlertUpdated {
+                zone: ZONE.clone(),
+                update_id: UPDATE_ID.clone(),
+                alert_id: "wind".to_string(),
+                alert: Arc::new(wind_upgrade),
+            }]);
+    }
+
+    #[test]
+    fn it_expires_and_deactivates_alerts_no_longer_in_force() {
+        let wind = alert("wind", "High Wind Watch", chrono::Duration::hours(2));
+        let wind_expired = WeatherAlert {
+            expires: *NOW - chrono::Duration::minutes(5),
+            ..wind.clone()
+        };
+        let flood = alert("flood", "Flood Warning", chrono::Duration::hours(6));
+        let stale = WeatherAlert {
+            ends: Some(*NOW - chrono::Duration::minutes(1)),
+            ..alert("stale", "Winter Storm Warning", chrono::Duration::hours(-1))
+        };
+        let stale_fresh = alert("fresh", "Winter Storm Watch", chrono::Duration::hours(1));
+
+        testing::TestHarness::given([
+            activated(&wind),
+            activated(&flood),
+            activated(&stale),
+            activated(&stale_fresh),
+        ])
+        .when(note(vec![wind_expired, flood]))
+        .then([
+            E::AlertExpired {
+                zone: ZONE.clone(),
+                update_id: UPDATE_ID.clone(),
+                alert_id: "wind".to_string(),
+            },
+            E::AlertDeactivated {
+                zone: ZONE.clone(),
+                update_id: UPDATE_ID.clone(),
+                alert_id: Some("fresh".to_string()),
+            },
+            E::AlertExpired {
+                zone: ZONE.clone(),
+                update_id: UPDATE_ID.clone(),
+                alert_id: "stale".to_string(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn it_ignores_expired_alert_not_already_active() {
+        let expired = alert("old", "Frost Advisory", chrono::Duration::hours(-2));
+
+        testing::TestHarness::given([]).when(note(vec![expired])).then([]);
+    }
+}
@@ -1,10 +1,15 @@
+use crate::model::weather::zone::changes::{ZoneWeatherChange, ZoneWeatherChangeSender};
 use crate::model::weather::zone::LocationZoneError;
 use crate::model::weather::LocationZoneEvent;
-use crate::model::{LocationZoneCode, WeatherAlert, WeatherFrame, ZoneForecast};
+use crate::model::zone_metadata;
+use crate::model::{
+    ForecastDetail, LocationZoneCode, StationObservation, WeatherAlert, WeatherFrame, ZoneForecast,
+};
 use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use disintegrate::{query, EventListener, PersistedEvent, StreamQuery};
+use geojson::{Feature, Geometry};
 use once_cell::sync::{Lazy, OnceCell};
 use sql_query_builder as sql;
 use sqlx::postgres::PgQueryResult;
@@ -26,13 +31,15 @@ static PRIMARY_KEY: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone").un
 static CURRENT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("current").unwrap());
 static FORECAST_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("forecast").unwrap());
 static ALERT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("alert").unwrap());
+static STATIONS_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("stations").unwrap());
 
-static COLUMNS: Lazy<[TableColumn; 5]> = Lazy::new(|| {
+static COLUMNS: Lazy<[TableColumn; 6]> = Lazy::new(|| {
     [
         PRIMARY_KEY.clone(),
         CURRENT_COL.clone(),
         FORECAST_COL.clone(),
         ALERT_COL.clone(),
+        STATIONS_COL.clone(),
         LAST_UPDATED_AT_COL.clone(),
     ]
 });
@@ -48,6 +55,8 @@ impl WeatherRepository {
         Self { pool }
     }
 
+    /// The zone's weather, or `None` if the zone has no weather. Alerts no longer in force are left
+    /// out, even before they are noted as expired.
     pub async fn weather_by_zone(
         &self, zone: &LocationZoneCode,
     ) -> Result<Option<ZoneWeather>, sqlx::Error> {
@@ -60,19 +69,209 @@ impl WeatherRepository {
                 .to_string()
         });
 
-        sqlx::query_as(sql).bind(zone).fetch_optional(&self.pool).await
+        let mut weather: Option<ZoneWeather> =
+            sqlx::query_as(sql).bind(zone).fetch_optional(&self.pool).await?;
+        if let Some(weather) = weather.as_mut() {
+            let now = Utc::now();
+            weather.alerts.retain(|alert| !alert.is_expired(now));
+        }
+        Ok(weather)
+    }
+
+    /// The zones holding an alert no longer in force as of the given time, which is yet to be
+    /// noted as expired.
+    pub async fn zones_with_lapsed_alerts(
+        &self, as_of: DateTime<Utc>,
+    ) -> Result<Vec<LocationZoneCode>, sqlx::Error> {
+        static ZONES_WITH_LAPSED_ALERTS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ZONES_WITH_LAPSED_ALERTS_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(format!("DISTINCT {}", PRIMARY_KEY.as_str()).as_str())
+                .from(
+                    format!(
+                        "{table}, LATERAL jsonb_array_elements(COALESCE({alert}, '[]'::JSONB)) \
+                        AS a(entry)",
+                        table = ZONE_WEATHER_TABLE.as_str(),
+                        alert = ALERT_COL.as_str(),
+                    )
+                    .as_str(),
+                )
+                .where_clause(
+                    "COALESCE(a.entry ->> 'ends', a.entry ->> 'expires')::TIMESTAMPTZ <= $1",
+                )
+                .to_string()
+        });
+
+        sqlx::query_scalar(sql).bind(as_of).fetch_all(&self.pool).await
+    }
+
+    /// The latest observation of each station reporting for the zone, or `None` if the zone has
+    /// no weather.
+    pub async fn stations_by_zone(
+        &self, zone: &LocationZoneCode,
+    ) -> Result<Option<Vec<StationObservation>>, sqlx::Error> {
+        static STATIONS_BY_ZONE_SQL: OnceCell<String> = OnceCell::new();
+        let sql = STATIONS_BY_ZONE_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(STATIONS_COL.as_str())
+                .from(&ZONE_WEATHER_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .to_string()
+        });
+
+        let stations: Option<Option<Json<Vec<StationObservation>>>> =
+            sqlx::query_scalar(sql).bind(zone).fetch_optional(&self.pool).await?;
+        Ok(stations.map(|s| s.map(|s| s.0).unwrap_or_default()))
+    }
+
+    /// The zone's forecast periods overlapping the time range, in forecast order, or `None` if the
+    /// zone has no weather. A missing bound leaves the range open on that side, and periods without
+    /// times, as in zone forecasts, are only included if the range is unbounded.
+    pub async fn forecast_periods(
+        &self, zone: &LocationZoneCode, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>,
+    ) -> Result<Option<ForecastPeriods>, sqlx::Error> {
+        static FORECAST_PERIODS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = FORECAST_PERIODS_SQL.get_or_init(|| {
+            let start_time = "( p.period ->> 'startTime' )::TIMESTAMPTZ";
+            let end_time = "( p.period ->> 'endTime' )::TIMESTAMPTZ";
+            let overlapping = format!(
+                "p.period IS NOT NULL \
+                AND ( $2::TIMESTAMPTZ IS NULL OR $2 < {end_time} ) \
+                AND ( $3::TIMESTAMPTZ IS NULL OR {start_time} < $3 )"
+            );
+
+            let periods = format!(
+                "COALESCE( jsonb_agg(p.period ORDER BY p.idx) FILTER (WHERE {overlapping}), \
+                '[]'::JSONB ) AS periods"
+            );
+
+            let untimed = "COALESCE( bool_or( p.period IS NOT NULL \
+                AND ( p.period ->> 'startTime' IS NULL OR p.period ->> 'endTime' IS NULL ) ), \
+                FALSE ) AS untimed";
+
+            sql::Select::new()
+                .select(&periods)
+                .select(untimed)
+                .from(&ZONE_WEATHER_TABLE)
+                .left_join(
+                    format!(
+                        "LATERAL jsonb_array_elements({forecast} -> 'periods') WITH ORDINALITY \
+                        AS p(period, idx) ON TRUE",
+                        forecast = FORECAST_COL.as_str(),
+                    )
+                    .as_str(),
+                )
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .group_by(PRIMARY_KEY.as_str())
+                .to_string()
+        });
+
+        let periods: Option<(Json<Vec<ForecastDetail>>, bool)> = sqlx::query_as(sql)
+            .bind(zone)
+            .bind(from)
+            .bind(to)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(periods.map(|(periods, untimed)| ForecastPeriods { periods: periods.0, untimed }))
+    }
+
+    /// The alerts in force as of the given time for any of the zones, most recently sent first. An
+    /// alert affecting several of the zones is included once. Alerts past their expiry or end are
+    /// left out, even before they are noted as expired.
+    pub async fn alerts_by_zones(
+        &self, zones: &[LocationZoneCode], as_of: DateTime<Utc>,
+    ) -> Result<Vec<WeatherAlert>, sqlx::Error> {
+        static ALERTS_BY_ZONES_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ALERTS_BY_ZONES_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(ALERT_COL.as_str())
+                .from(&ZONE_WEATHER_TABLE)
+                .where_clause(format!("{} = ANY($1)", PRIMARY_KEY.as_str()).as_str())
+                .to_string()
+        });
+
+        let zones: Vec<String> = zones.iter().map(|zone| zone.to_string()).collect();
+        let zone_alerts: Vec<Option<Json<Vec<WeatherAlert>>>> =
+            sqlx::query_scalar(sql).bind(zones).fetch_all(&self.pool).await?;
+
+        let alerts = zone_alerts.into_iter().flatten().flat_map(|a| a.0);
+        Ok(alerts_in_force(alerts, as_of))
+    }
+
+    /// The alert with the NOAA alert id for any zone, if it is in force as of the given time.
+    pub async fn alert_by_id(
+        &self, id: &str, as_of: DateTime<Utc>,
+    ) -> Result<Option<WeatherAlert>, sqlx::Error> {
+        static ALERT_BY_ID_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ALERT_BY_ID_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select("a.entry")
+                .from(
+                    format!(
+                        "{table}, LATERAL jsonb_array_elements(COALESCE({alert}, '[]'::JSONB)) \
+                        AS a(entry)",
+                        table = ZONE_WEATHER_TABLE.as_str(),
+                        alert = ALERT_COL.as_str(),
+                    )
+                    .as_str(),
+                )
+                .where_clause("a.entry ->> 'id' = $1")
+                .where_and("$2 < COALESCE(a.entry ->> 'ends', a.entry ->> 'expires')::TIMESTAMPTZ")
+                .order_by("( a.entry ->> 'sent' )::TIMESTAMPTZ DESC")
+                .limit("1")
+                .to_string()
+        });
+
+        let alert: Option<Json<WeatherAlert>> = sqlx::query_scalar(sql)
+            .bind(id)
+            .bind(as_of)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(alert.map(|a| a.0))
     }
 }
 
+/// The alerts in force as of the given time, most recently sent first and each alert once.
+fn alerts_in_force(
+    alerts: impl IntoIterator<Item = WeatherAlert>, as_of: DateTime<Utc>,
+) -> Vec<WeatherAlert> {
+    let mut alerts: Vec<_> = alerts.into_iter().filter(|a| !a.is_expired(as_of)).collect();
+    alerts.sort_by(|lhs, rhs| rhs.sent.cmp(&lhs.sent).then_with(|| lhs.id.cmp(&rhs.id)));
+    alerts.dedup_by(|lhs, rhs| lhs.id == rhs.id);
+    alerts
+}
+
+/// A zone's forecast periods selected by time range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastPeriods {
+    pub periods: Vec<ForecastDetail>,
+
+    /// Whether any of the zone's forecast periods, selected or not, lacks times. A time range
+    /// cannot select among untimed periods.
+    pub untimed: bool,
+}
+
 #[derive(Debug, PartialEq, Serialize)]
 pub struct ZoneWeather {
     pub zone: LocationZoneCode,
     pub current: Option<WeatherFrame>,
     pub forecast: Option<ZoneForecast>,
-    pub alert: Option<WeatherAlert>,
+
+    /// Serialized as `alert`, the name it had when zones held a single alert.
+    #[serde(rename = "alert")]
+    pub alerts: Vec<WeatherAlert>,
+
     pub last_updated_at: DateTime<Utc>,
 }
 
+impl ZoneWeather {
+    /// The zone's weather as a GeoJSON feature identified by its zone code over the zone's
+    /// boundary, if known.
+    pub fn to_feature(&self, geometry: Option<Geometry>) -> Result<Feature, serde_json::Error> {
+        zone_metadata::feature_of(self.zone.to_string(), geometry, self)
+    }
+}
+
 impl<'r, R> sqlx::FromRow<'r, R> for ZoneWeather
 where
     R: sqlx::Row,
@@ -80,7 +279,7 @@ where
         sqlx::Decode<'r, <R as sqlx::Row>::Database> + sqlx::Type<<R as sqlx::Row>::Database>,
     Json<ZoneForecast>:
         sqlx::Decode<'r, <R as sqlx::Row>::Database> + sqlx::Type<<R as sqlx::Row>::Database>,
-    Json<WeatherAlert>:
+    Json<Vec<WeatherAlert>>:
         sqlx::Decode<'r, <R as sqlx::Row>::Database> + sqlx::Type<<R as sqlx::Row>::Database>,
     String: sqlx::Decode<'r, <R as sqlx::Row>::Database> + sqlx::Type<<R as sqlx::Row>::Database>,
     DateTime<Utc>:
@@ -102,12 +301,12 @@ where
 
         let alert_json = row.try_get::<Option<Json<_>>, _>(ALERT_COL.clone());
         debug!("DMR: alert_json={alert_json:?}");
-        let alert = alert_json?.map(|a| a.0);
+        let alerts = alert_json?.map(|a| a.0).unwrap_or_default();
         // let alert = serde_json::from_value(alert_json).map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
 
         let last_updated_at = row.try_get(LAST_UPDATED_AT_COL.clone())?;
 
-        Ok(Self { zone, current, forecast, alert, last_updated_at })
+        Ok(Self { zone, current, forecast, alerts, last_updated_at })
     }
 }
 
@@ -115,10 +314,11 @@ where
 pub struct ZoneWeatherProjection {
     query: StreamQuery<LocationZoneEvent>,
     pool: PgPool,
+    changes: ZoneWeatherChangeSender,
 }
 
 impl ZoneWeatherProjection {
-    pub async fn new(pool: PgPool) -> Result<Self, sqlx::Error> {
+    pub async fn new(pool: PgPool, changes: ZoneWeatherChangeSender) -> Result<Self, sqlx::Error> {
         static CREATE_TABLE_DDL: OnceCell<String> = OnceCell::new();
         let sql = CREATE_TABLE_DDL.get_or_init(|| {
             format!(
@@ -128,6 +328,7 @@ impl ZoneWeatherProjection {
                     {current} JSONB,
                     {forecast} JSONB,
                     {alert} JSONB,
+                    {stations} JSONB,
                     {last_updated_at} TIMESTAMPTZ NULL DEFAULT clock_timestamp()
                 )"#,
                 table = ZONE_WEATHER_TABLE.as_str(),
@@ -135,12 +336,38 @@ impl ZoneWeatherProjection {
                 current = CURRENT_COL.as_str(),
                 forecast = FORECAST_COL.as_str(),
                 alert = ALERT_COL.as_str(),
+                stations = STATIONS_COL.as_str(),
                 last_updated_at = LAST_UPDATED_AT_COL.as_str(),
             )
         });
 
         sqlx::query(sql).execute(&pool).await?;
-        Ok(Self { query: query(None), pool })
+
+        // -- alert column originally held a single alert object; it now holds the list of alerts
+        static ALERT_LIST_DML: OnceCell<String> = OnceCell::new();
+        let sql = ALERT_LIST_DML.get_or_init(|| {
+            let alert = ALERT_COL.as_str();
+            sql::Update::new()
+                .update(&ZONE_WEATHER_TABLE)
+                .set(format!("{alert} = jsonb_build_array({alert})").as_str())
+                .where_clause(format!("jsonb_typeof({alert}) = 'object'").as_str())
+                .to_string()
+        });
+
+        sqlx::query(sql).execute(&pool).await?;
+
+        // -- station observations were added after the table
+        static STATIONS_DDL: OnceCell<String> = OnceCell::new();
+        let sql = STATIONS_DDL.get_or_init(|| {
+            format!(
+                "ALTER TABLE {table} ADD COLUMN IF NOT EXISTS {stations} JSONB",
+                table = ZONE_WEATHER_TABLE.as_str(),
+                stations = STATIONS_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&pool).await?;
+        Ok(Self { query: query(None), pool, changes })
     }
 }
 
@@ -159,30 +386,50 @@ impl EventListener<LocationZoneEvent> for ZoneWeatherProjection {
     #[allow(clippy::blocks_in_conditions)]
     #[instrument(level = "debug", skip(self), err)]
     async fn handle(&self, event: PersistedEvent<LocationZoneEvent>) -> Result<(), Self::Error> {
+        let change = ZoneWeatherChange::from(&event);
         let mut tx = sqlx::Acquire::begin(&self.pool).await?;
         let result: PgQueryResult = match event.into_inner() {
-            LocationZoneEvent::ObservationUpdated { zone, weather, .. } => {
-                Self::update_or_insert_weather(zone, weather, &mut tx).await?
+            LocationZoneEvent::ObservationUpdated { zone, weather, stations, .. } => {
+                Self::update_or_insert_weather(zone, weather, stations, &mut tx).await?
             },
 
             LocationZoneEvent::ForecastUpdated { zone, forecast, .. } => {
                 Self::update_or_insert_forecast(zone, forecast, &mut tx).await?
             },
 
-            LocationZoneEvent::AlertActivated { zone, alert, .. } => {
-                Self::update_or_insert_alert(zone, Some(alert), &mut tx).await?
+            // -- hourly forecasts are projected separately, but the change is still published
+            LocationZoneEvent::HourlyForecastUpdated { .. } => PgQueryResult::default(),
+
+            LocationZoneEvent::AlertActivated { zone, alert_id, alert, .. }
+            | LocationZoneEvent::AlertUpdated { zone, alert_id, alert, .. } => {
+                let alert_id = if alert_id.is_empty() { alert.id.clone() } else { alert_id };
+                let mut alerts = Self::zone_alerts(&zone, &mut tx).await?;
+                alerts.retain(|a| a.id != alert_id);
+                alerts.push(alert.as_ref().clone());
+                Self::update_or_insert_alerts(zone, alerts, &mut tx).await?
+            },
+
+            LocationZoneEvent::AlertExpired { zone, alert_id, .. }
+            | LocationZoneEvent::AlertDeactivated { zone, alert_id: Some(alert_id), .. } => {
+                let mut alerts = Self::zone_alerts(&zone, &mut tx).await?;
+                alerts.retain(|a| a.id != alert_id);
+                Self::update_or_insert_alerts(zone, alerts, &mut tx).await?
             },
 
-            LocationZoneEvent::AlertDeactivated { zone, .. } => {
-                Self::update_or_insert_alert(zone, None, &mut tx).await?
+            LocationZoneEvent::AlertDeactivated { zone, alert_id: None, .. } => {
+                Self::update_or_insert_alerts(zone, vec![], &mut tx).await?
             },
         };
 
         let outcome = tx.commit().await;
-        if let Err(ref error) = outcome {
-            error!(
+        match &outcome {
+            Ok(()) => {
+                // -- no subscribers is not an error
+                let _ = self.changes.send(change);
+            },
+            Err(error) => error!(
                 "postgres projection failed to commit location zone event transaction: {error:?}"
-            );
+            ),
         }
 
         debug!("location zone projection postgres query result: {result:?}");
@@ -233,16 +480,17 @@ impl ZoneWeatherProjection {
             .to_string()
     }
 
-    #[instrument(level = "debug", skip(weather, tx), ret, err)]
+    #[instrument(level = "debug", skip(weather, stations, tx), ret, err)]
     async fn update_or_insert_weather(
-        zone: LocationZoneCode, weather: Arc<WeatherFrame>, tx: &mut PgConnection,
+        zone: LocationZoneCode, weather: Arc<WeatherFrame>, stations: Arc<Vec<StationObservation>>,
+        tx: &mut PgConnection,
     ) -> Result<PgQueryResult, LocationZoneError> {
         static UPDATE_OR_INSERT_WEATHER_SQL: OnceCell<String> = OnceCell::new();
         let sql = UPDATE_OR_INSERT_WEATHER_SQL.get_or_init(|| {
-            Self::build_insert(
-                sql::Update::new()
-                    .set("current = EXCLUDED.current, last_updated_at = EXCLUDED.last_updated_at"),
-            )
+            Self::build_insert(sql::Update::new().set(
+                "current = EXCLUDED.current, stations = EXCLUDED.stations, \
+                last_updated_at = EXCLUDED.last_updated_at",
+            ))
         });
 
         debug!("sql: {sql}");
@@ -252,6 +500,7 @@ impl ZoneWeatherProjection {
             .bind(Some(serde_json::to_value(weather)?)) // weather
             .bind(None::<serde_json::Value>) // forecast
             .bind(None::<serde_json::Value>) // alert
+            .bind(Some(serde_json::to_value(stations)?)) // stations
             .bind(Utc::now()) // last_updated_at
             .execute(tx)
             .await
@@ -277,18 +526,38 @@ impl ZoneWeatherProjection {
             .bind(None::<serde_json::Value>) // current
             .bind(Some(serde_json::to_value(forecast)?)) // forecast
             .bind(None::<serde_json::Value>) // alert
+            .bind(None::<serde_json::Value>) // stations
             .bind(Utc::now()) // last_updated_at
             .execute(tx)
             .await
             .map_err(|err| err.into())
     }
 
-    #[instrument(level = "debug", skip(alert, tx), ret, err)]
-    async fn update_or_insert_alert(
-        zone: LocationZoneCode, alert: Option<Arc<WeatherAlert>>, tx: &mut PgConnection,
+    /// Loads the zone's current alerts, locking the zone's row for the rest of the transaction.
+    #[instrument(level = "debug", skip(tx), err)]
+    async fn zone_alerts(
+        zone: &LocationZoneCode, tx: &mut PgConnection,
+    ) -> Result<Vec<WeatherAlert>, LocationZoneError> {
+        static ZONE_ALERTS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ZONE_ALERTS_SQL.get_or_init(|| {
+            let select = sql::Select::new()
+                .select(ALERT_COL.as_str())
+                .from(&ZONE_WEATHER_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str());
+            format!("{select} FOR UPDATE")
+        });
+
+        let alerts: Option<Option<Json<Vec<WeatherAlert>>>> =
+            sqlx::query_scalar(sql).bind(zone).fetch_optional(tx).await?;
+        Ok(alerts.flatten().map(|a| a.0).unwrap_or_default())
+    }
+
+    #[instrument(level = "debug", skip(alerts, tx), ret, err)]
+    async fn update_or_insert_alerts(
+        zone: LocationZoneCode, alerts: Vec<WeatherAlert>, tx: &mut PgConnection,
     ) -> Result<PgQueryResult, LocationZoneError> {
-        static UPDATE_OR_INSERT_ALERT_SQL: OnceCell<String> = OnceCell::new();
-        let sql = UPDATE_OR_INSERT_ALERT_SQL.get_or_init(|| {
+        static UPDATE_OR_INSERT_ALERTS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = UPDATE_OR_INSERT_ALERTS_SQL.get_or_init(|| {
             Self::build_insert(
                 sql::Update::new()
                     .set("alert = EXCLUDED.alert, last_updated_at = EXCLUDED.last_updated_at"),
@@ -297,17 +566,67 @@ impl ZoneWeatherProjection {
 
         debug!("sql: {sql}");
 
-        let query = sqlx::query(sql)
+        sqlx::query(sql)
             .bind(zone) // zone
             .bind(None::<serde_json::Value>) // weather
-            .bind(None::<serde_json::Value>); // forecast
-
-        let alert_binding = alert.map(serde_json::to_value).transpose()?;
-        query
-            .bind(alert_binding)
-            .bind(Utc::now())
+            .bind(None::<serde_json::Value>) // forecast
+            .bind(Some(serde_json::to_value(alerts)?)) // alert
+            .bind(None::<serde_json::Value>) // stations
+            .bind(Utc::now()) // last_updated_at
             .execute(tx)
             .await
             .map_err(|err| err.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{
+        AlertCategory, AlertCertainty, AlertMessageType, AlertResponse, AlertSeverity, AlertStatus,
+        AlertUrgency,
+    };
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    fn alert(id: &str, sent: DateTime<Utc>, expires: DateTime<Utc>) -> WeatherAlert {
+        WeatherAlert {
+            id: id.to_string(),
+            affected_zones: vec![LocationZoneCode::new("WAZ558")],
+            status: AlertStatus::Actual,
+            message_type: AlertMessageType::Alert,
+            sent,
+            effective: sent,
+            onset: None,
+            expires,
+            ends: None,
+            category: AlertCategory::Met,
+            severity: AlertSeverity::Moderate,
+            certainty: AlertCertainty::Likely,
+            urgency: AlertUrgency::Expected,
+            event: "Wind Advisory".to_string(),
+            headline: None,
+            description: "Winds 25 to 35 mph.".to_string(),
+            instruction: None,
+            response: AlertResponse::Execute,
+        }
+    }
+
+    #[test]
+    fn test_alerts_in_force() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 22, 12, 0, 0).unwrap();
+        let hours = chrono::Duration::hours;
+
+        let wind = alert("wind", now - hours(2), now + hours(4));
+        let flood = alert("flood", now - hours(1), now + hours(6));
+        let expired = alert("expired", now - hours(8), now - hours(1));
+        let mut ended = alert("ended", now - hours(3), now + hours(6));
+        ended.ends = Some(now);
+
+        let actual = alerts_in_force(
+            vec![wind.clone(), expired, flood.clone(), ended, wind.clone()],
+            now,
+        );
+        assert_eq!(actual, vec![flood, wind]);
+    }
+}
@@ -1,7 +1,14 @@
-use crate::model::{LocationZoneCode, LocationZoneType, WeatherFrame, ZoneForecast};
-use crate::services::noaa::{NoaaWeatherError, NoaaWeatherServices, ZoneWeatherApi};
+use crate::model::zone_metadata::ZoneMetadataRepository;
+use crate::model::{
+    GeoPoint, Gridpoint, HourlyForecast, LocationZoneCode, LocationZoneType, ObservationReport,
+    ZoneForecast,
+};
+use crate::services::noaa::{
+    GridpointForecastApi, NoaaWeatherError, NoaaWeatherServices, ZoneWeatherApi,
+};
 use once_cell::sync::OnceCell;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
 
 pub type LocationZoneServicesRef = Arc<LocationZoneServices>;
 
@@ -19,25 +26,89 @@ pub fn services() -> LocationZoneServicesRef {
     SERVICES.get().expect("LocationZoneServices are not initialized").clone()
 }
 
+/// NOAA services for location zones. A zone's representative gridpoint does not change, so it is
+/// resolved once per zone, from the zone's stored boundary when there is one.
 #[derive(Debug, Clone)]
-pub struct LocationZoneServices(NoaaWeatherServices);
+pub struct LocationZoneServices {
+    noaa: NoaaWeatherServices,
+    zone_metadata: ZoneMetadataRepository,
+    gridpoints: Arc<Mutex<HashMap<LocationZoneCode, Gridpoint>>>,
+}
 
 impl LocationZoneServices {
-    pub fn new(noaa: NoaaWeatherServices) -> Self {
-        Self(noaa)
+    pub fn new(noaa: NoaaWeatherServices, zone_metadata: ZoneMetadataRepository) -> Self {
+        Self {
+            noaa,
+            zone_metadata,
+            gridpoints: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Centroid of the zone's stored boundary, which spares fetching the zone's full geometry
+    /// from NOAA.
+    async fn stored_centroid(&self, zone: &LocationZoneCode) -> Option<GeoPoint> {
+        match self.zone_metadata.zone(zone).await {
+            Ok(metadata) => {
+                metadata.and_then(|m| m.geometry).as_ref().and_then(GeoPoint::centroid_of)
+            },
+            Err(error) => {
+                warn!(
+                    ?error, %zone,
+                    "failed to load stored zone metadata - fetching zone from NOAA"
+                );
+                None
+            },
+        }
     }
 }
 
 impl ZoneWeatherApi for LocationZoneServices {
     async fn zone_observation(
-        &self, zone: &LocationZoneCode,
-    ) -> Result<WeatherFrame, NoaaWeatherError> {
-        self.0.zone_observation(zone).await
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ObservationReport, NoaaWeatherError> {
+        self.noaa.zone_observation(zone_type, zone).await
     }
 
     async fn zone_forecast(
         &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
     ) -> Result<ZoneForecast, NoaaWeatherError> {
-        self.0.zone_forecast(zone_type, zone).await
+        self.noaa.zone_forecast(zone_type, zone).await
+    }
+}
+
+impl GridpointForecastApi for LocationZoneServices {
+    async fn zone_gridpoint(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<Gridpoint, NoaaWeatherError> {
+        let cached = self
+            .gridpoints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(zone)
+            .cloned();
+        if let Some(gridpoint) = cached {
+            return Ok(gridpoint);
+        }
+
+        let gridpoint = match self.stored_centroid(zone).await {
+            Some(centroid) => self.noaa.point_gridpoint(&centroid).await?,
+            None => self.noaa.zone_gridpoint(zone_type, zone).await?,
+        };
+        info!(%zone, %gridpoint, "resolved location zone gridpoint");
+        self.gridpoints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(zone.clone(), gridpoint.clone());
+        Ok(gridpoint)
+    }
+
+    async fn point_gridpoint(&self, point: &GeoPoint) -> Result<Gridpoint, NoaaWeatherError> {
+        self.noaa.point_gridpoint(point).await
+    }
+
+    async fn hourly_forecast(
+        &self, gridpoint: &Gridpoint,
+    ) -> Result<HourlyForecast, NoaaWeatherError> {
+        self.noaa.hourly_forecast(gridpoint).await
     }
 }
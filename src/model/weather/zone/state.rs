@@ -1,6 +1,7 @@
 use crate::model::weather::LocationZoneEvent;
-use crate::model::{LocationZoneCode, WeatherAlert, WeatherFrame, ZoneForecast};
+use crate::model::{HourlyForecast, LocationZoneCode, WeatherAlert, WeatherFrame, ZoneForecast};
 use disintegrate::{StateMutate, StateQuery};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tagid::Label;
 
@@ -48,33 +49,67 @@ impl StateMutate for LocationZoneForecast {
     }
 }
 
+#[derive(Debug, Clone, StateQuery, Label, Serialize, Deserialize)]
+#[state_query(LocationZoneEvent)]
+pub struct LocationZoneHourlyForecast {
+    #[id]
+    zone: LocationZoneCode,
+    forecast: Option<Arc<HourlyForecast>>,
+}
+
+impl LocationZoneHourlyForecast {
+    pub fn new(zone: LocationZoneCode) -> Self {
+        Self { zone, forecast: None }
+    }
+}
+
+impl StateMutate for LocationZoneHourlyForecast {
+    fn mutate(&mut self, event: Self::Event) {
+        if let LocationZoneEvent::HourlyForecastUpdated { forecast, .. } = event {
+            self.forecast = Some(forecast);
+        }
+    }
+}
+
 #[derive(Debug, Clone, StateQuery, Label, Serialize, Deserialize)]
 #[state_query(LocationZoneEvent)]
 pub struct LocationZoneAlert {
     #[id]
     zone: LocationZoneCode,
-    alert: Option<Arc<WeatherAlert>>,
+    alerts: HashMap<String, Arc<WeatherAlert>>,
 }
 
 impl LocationZoneAlert {
     pub fn new(zone: LocationZoneCode) -> Self {
-        Self { zone, alert: None }
+        Self { zone, alerts: HashMap::new() }
     }
 
     #[inline]
-    pub fn active_alert(&self) -> bool {
-        self.alert.is_some()
+    pub fn active_alerts(&self) -> &HashMap<String, Arc<WeatherAlert>> {
+        &self.alerts
+    }
+
+    #[inline]
+    pub fn active_alert(&self, alert_id: &str) -> Option<&Arc<WeatherAlert>> {
+        self.alerts.get(alert_id)
     }
 }
 
 impl StateMutate for LocationZoneAlert {
     fn mutate(&mut self, event: Self::Event) {
+        use LocationZoneEvent as E;
+
         match event {
-            LocationZoneEvent::AlertActivated { alert, .. } => {
-                self.alert = Some(alert);
+            E::AlertActivated { alert_id, alert, .. } | E::AlertUpdated { alert_id, alert, .. } => {
+                let alert_id = if alert_id.is_empty() { alert.id.clone() } else { alert_id };
+                self.alerts.insert(alert_id, alert);
+            },
+            E::AlertExpired { alert_id, .. }
+            | E::AlertDeactivated { alert_id: Some(alert_id), .. } => {
+                self.alerts.remove(&alert_id);
             },
-            LocationZoneEvent::AlertDeactivated { .. } => {
-                self.alert = None;
+            E::AlertDeactivated { alert_id: None, .. } => {
+                self.alerts.clear();
             },
             _ => {},
         }
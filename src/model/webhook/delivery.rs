@@ -0,0 +1,479 @@
+use super::repository::{WebhookDelivery, WebhookRepository, WebhookSecret, WebhookSubscription};
+use super::WebhookError;
+use crate::model::weather::update::UpdateWeatherId;
+use crate::model::weather::WeatherEvent;
+use crate::model::{LocationZoneCode, WeatherAlert};
+use crate::settings::WebhookSettings;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use disintegrate::{query, EventListener, PersistedEvent, StreamQuery};
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::StatusCode;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use strum_macros::Display;
+use thiserror::Error;
+use tokio_util::task::TaskTracker;
+
+pub const WEBHOOK_DELIVERIES_LISTENER: &str = "webhook_deliveries";
+
+/// How often the delivery worker checks for deliveries that are due.
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most deliveries attempted by the worker at once.
+const MAX_CLAIMED_DELIVERIES: usize = 100;
+
+/// Hex HMAC-SHA256 signature of the payload, prefixed with `sha256=`.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Unix timestamp of the delivery attempt, which is included in the signature.
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+
+/// Id of the alert's event, which is the same across attempts so endpoints can de-duplicate.
+pub const EVENT_ID_HEADER: &str = "X-Webhook-Event-Id";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, ToSchema, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AlertNoticeType {
+    AlertActivated,
+
+    /// NOAA no longer reports the alert active before it expired.
+    AlertDeactivated,
+
+    /// The alert is past its expiry or end.
+    AlertExpired,
+}
+
+impl AlertNoticeType {
+    /// Whether the alert is no longer in force for the zone after the notice.
+    pub const fn ends_alert(&self) -> bool {
+        matches!(self, Self::AlertDeactivated | Self::AlertExpired)
+    }
+}
+
+/// Payload delivered to webhook subscriptions when an alert is activated, deactivated or expires
+/// for a zone.
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertNotice {
+    pub event_id: i64,
+
+    #[serde(rename = "type")]
+    pub notice_type: AlertNoticeType,
+
+    pub zone: LocationZoneCode,
+    pub update_id: UpdateWeatherId,
+
+    /// Absent for the deactivation of all of a zone's alerts recorded before alerts were tracked
+    /// by id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert_id: Option<String>,
+
+    /// For a deactivation or expiry, the alert as last activated or updated if noted by the
+    /// dispatcher.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<WeatherAlert>)]
+    pub alert: Option<Arc<WeatherAlert>>,
+}
+
+/// Signs the body of a delivery with the subscription secret: the HMAC-SHA256 of
+/// `"{timestamp}.{body}"`, hex encoded. Including the timestamp lets endpoints reject replays.
+pub fn sign(secret: &WebhookSecret, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose().as_bytes())
+        .expect("HMAC accepts a key of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[derive(Debug, Error)]
+enum DeliveryFailure {
+    #[error("webhook endpoint responded with {0}")]
+    Status(StatusCode),
+
+    #[error("webhook request failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+impl DeliveryFailure {
+    /// Network failures, timeouts and server errors are retried; other rejections by the endpoint
+    /// will not succeed on retry.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status(status) => {
+                status.is_server_error()
+                    || *status == StatusCode::REQUEST_TIMEOUT
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+            },
+            Self::Request(_) => true,
+        }
+    }
+}
+
+/// Enqueues alert activations, deactivations and expiries for delivery to the matching webhook
+/// subscriptions. Deliveries are attempted by the `WebhookDeliveryWorker` rather than while the
+/// event is handled, so a failing endpoint does not hold up the event listener.
+///
+/// The dispatcher notes each alert as last activated or updated, so the alert's deactivation can
+/// be delivered with it, until the alert is deactivated or expires.
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    query: StreamQuery<WeatherEvent>,
+    repository: WebhookRepository,
+}
+
+impl WebhookDispatcher {
+    pub fn new(repository: WebhookRepository) -> Self {
+        Self {
+            query: query!(
+                WeatherEvent,
+                events[AlertActivated, AlertUpdated, AlertExpired, AlertDeactivated]
+            ),
+            repository,
+        }
+    }
+}
+
+#[async_trait]
+impl EventListener<WeatherEvent> for WebhookDispatcher {
+    type Error = WebhookError;
+
+    fn id(&self) -> &'static str {
+        WEBHOOK_DELIVERIES_LISTENER
+    }
+
+    fn query(&self) -> &StreamQuery<WeatherEvent> {
+        &self.query
+    }
+
+    #[allow(clippy::blocks_in_conditions)]
+    #[instrument(level = "debug", skip(self), err)]
+    async fn handle(&self, event: PersistedEvent<WeatherEvent>) -> Result<(), Self::Error> {
+        let event_id = event.id();
+        let notice = match event.into_inner() {
+            WeatherEvent::AlertActivated { zone, update_id, alert_id, alert } => {
+                let alert_id = if alert_id.is_empty() { alert.id.clone() } else { alert_id };
+                self.repository.note_alert(&zone, &alert_id, event_id, &alert).await?;
+                AlertNotice {
+                    event_id,
+                    notice_type: AlertNoticeType::AlertActivated,
+                    zone,
+                    update_id,
+                    alert_id: Some(alert_id),
+                    alert: Some(alert),
+                }
+            },
+            WeatherEvent::AlertUpdated { zone, alert_id, alert, .. } => {
+                return self.repository.note_alert(&zone, &alert_id, event_id, &alert).await;
+            },
+            WeatherEvent::AlertExpired { zone, update_id, alert_id } => {
+                let alert = self.repository.noted_alert(&zone, &alert_id).await?;
+                AlertNotice {
+                    event_id,
+                    notice_type: AlertNoticeType::AlertExpired,
+                    zone,
+                    update_id,
+                    alert_id: Some(alert_id),
+                    alert: alert.map(Arc::new),
+                }
+            },
+            WeatherEvent::AlertDeactivated { zone, update_id, alert_id } => {
+                let alert = match alert_id.as_deref() {
+                    Some(alert_id) => self.repository.noted_alert(&zone, alert_id).await?,
+                    None => None,
+                };
+                AlertNotice {
+                    event_id,
+                    notice_type: AlertNoticeType::AlertDeactivated,
+                    zone,
+                    update_id,
+                    alert_id,
+                    alert: alert.map(Arc::new),
+                }
+            },
+            _ => return Ok(()),
+        };
+
+        let subscription_ids: Vec<_> = self
+            .repository
+            .subscriptions()
+            .await?
+            .into_iter()
+            .filter(|subscription| subscription.filter.matches(&notice))
+            .map(|subscription| subscription.id)
+            .collect();
+
+        let payload = serde_json::to_value(&notice)?;
+        self.repository
+            .enqueue_deliveries(event_id, &payload, &subscription_ids)
+            .await?;
+
+        // -- forgotten only once enqueued, so the deactivation or expiry is delivered with the alert
+        // if the event is handled again
+        if notice.notice_type.ends_alert() {
+            self.repository
+                .forget_alerts(&notice.zone, notice.alert_id.as_deref())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Attempts the enqueued webhook deliveries as they come due. A failed delivery is retried with
+/// backoff per the webhook settings and dead-lettered once its attempts are exhausted. The worker
+/// is tracked under the application `TaskTracker` and stops on `crate::shutdown()`.
+#[derive(Debug, Clone)]
+pub struct WebhookDeliveryWorker {
+    repository: WebhookRepository,
+    client: reqwest::Client,
+    settings: WebhookSettings,
+}
+
+impl WebhookDeliveryWorker {
+    pub fn new(
+        repository: WebhookRepository, settings: &WebhookSettings,
+    ) -> Result<Self, WebhookError> {
+        let client = reqwest::Client::builder().timeout(settings.timeout).build()?;
+        Ok(Self { repository, client, settings: settings.clone() })
+    }
+
+    pub fn spawn(self, task_tracker: &TaskTracker) {
+        task_tracker.spawn(async move { self.run().await });
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn run(self) {
+        info!(settings=?self.settings, "starting webhook delivery worker");
+
+        let shutdown = crate::shutdown();
+        tokio::pin!(shutdown);
+
+        let mut ticks = tokio::time::interval(DELIVERY_POLL_INTERVAL);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    info!("webhook delivery worker shutting down");
+                    break;
+                },
+
+                _ = ticks.tick() => {
+                    if let Err(error) = self.deliver_due().await {
+                        warn!(?error, "failed to attempt due webhook deliveries -- will retry");
+                    }
+                },
+            }
+        }
+    }
+
+    /// Attempts the deliveries that are due, concurrently so that a slow endpoint does not hold
+    /// up the others, returning the number attempted.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn deliver_due(&self) -> Result<usize, WebhookError> {
+        // -- claim long enough for every attempt to conclude before it is claimed again
+        let claimed_until = time_after(self.settings.timeout.saturating_mul(2));
+        let deliveries = self
+            .repository
+            .claim_due_deliveries(claimed_until, MAX_CLAIMED_DELIVERIES)
+            .await?;
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        let subscriptions: HashMap<_, _> = self
+            .repository
+            .subscriptions()
+            .await?
+            .into_iter()
+            .map(|subscription| (subscription.id.clone(), subscription))
+            .collect();
+
+        // -- deliveries to a deleted subscription are deleted along with it
+        let attempts = deliveries.iter().filter_map(|delivery| {
+            let subscription = subscriptions.get(&delivery.subscription_id)?;
+            Some(self.attempt(subscription, delivery))
+        });
+
+        let mut nr_attempted = 0;
+        for outcome in futures::future::join_all(attempts).await {
+            nr_attempted += 1;
+            if let Err(error) = outcome {
+                error!(?error, "failed to note webhook delivery attempt");
+            }
+        }
+        Ok(nr_attempted)
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self, subscription, delivery),
+        fields(subscription_id=%subscription.id, event_id=%delivery.event_id),
+        err
+    )]
+    async fn attempt(
+        &self, subscription: &WebhookSubscription, delivery: &WebhookDelivery,
+    ) -> Result<(), WebhookError> {
+        let attempt = delivery.attempts + 1;
+        let body = serde_json::to_vec(&delivery.payload)?;
+        let failure = match self.post(subscription, delivery.event_id, &body).await {
+            Ok(()) => return self.repository.complete_delivery(delivery).await,
+            Err(failure) => failure,
+        };
+
+        if failure.is_retryable() && attempt < self.settings.max_attempts {
+            let backoff = self.settings.backoff_for(attempt);
+            warn!(%attempt, ?backoff, %failure, "webhook delivery failed - retrying");
+            self.repository
+                .reschedule_delivery(delivery, attempt, &failure.to_string(), time_after(backoff))
+                .await
+        } else {
+            error!(%attempt, %failure, "webhook delivery failed - dead-lettering alert notice");
+            self.repository
+                .dead_letter_delivery(delivery, attempt, &failure.to_string())
+                .await
+        }
+    }
+
+    async fn post(
+        &self, subscription: &WebhookSubscription, event_id: i64, body: &[u8],
+    ) -> Result<(), DeliveryFailure> {
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .client
+            .post(subscription.url.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                sign(&subscription.secret, timestamp, body),
+            )
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(EVENT_ID_HEADER, event_id.to_string())
+            .body(body.to_vec())
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(DeliveryFailure::Status(status))
+        }
+    }
+}
+
+fn time_after(delay: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(delay)
+        .ok()
+        .and_then(|delay| Utc::now().checked_add_signed(delay))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::webhook::AlertFilter;
+    use crate::model::{
+        AlertCategory, AlertCertainty, AlertMessageType, AlertResponse, AlertSeverity, AlertStatus,
+        AlertUrgency,
+    };
+    use chrono::TimeZone;
+    use claims::*;
+    use maplit::hashset;
+    use pretty_assertions::assert_eq;
+
+    fn alert(severity: AlertSeverity, urgency: AlertUrgency) -> Arc<WeatherAlert> {
+        let sent = Utc.with_ymd_and_hms(2024, 3, 20, 12, 0, 0).unwrap();
+        Arc::new(WeatherAlert {
+            id: "alert-1".to_string(),
+            affected_zones: vec![LocationZoneCode::new("WAZ558")],
+            status: AlertStatus::Actual,
+            message_type: AlertMessageType::Alert,
+            sent,
+            effective: sent,
+            onset: None,
+            expires: sent + chrono::Duration::hours(6),
+            ends: None,
+            category: AlertCategory::Met,
+            severity,
+            certainty: AlertCertainty::Likely,
+            urgency,
+            event: "Wind Advisory".to_string(),
+            headline: None,
+            description: "Southwest winds 25 to 35 mph.".to_string(),
+            instruction: None,
+            response: AlertResponse::Prepare,
+        })
+    }
+
+    fn notice(zone: &str, alert: Option<Arc<WeatherAlert>>) -> AlertNotice {
+        AlertNotice {
+            event_id: 7,
+            notice_type: AlertNoticeType::AlertDeactivated,
+            zone: LocationZoneCode::new(zone),
+            update_id: UpdateWeatherId::for_labeled("update-1"),
+            alert_id: Some("alert-1".to_string()),
+            alert,
+        }
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let secret = WebhookSecret::new("my-secret");
+        let signature = sign(&secret, 1_710_936_000, br#"{"type":"alert_activated"}"#);
+        assert_eq!(
+            signature,
+            "sha256=6e1975c7495d13723000f38d2980dfc6b0d0bcde1dd6b5a96e30a6964d3e9562"
+        );
+    }
+
+    #[test]
+    fn test_alert_filter_matches() {
+        let severe = alert(AlertSeverity::Severe, AlertUrgency::Expected);
+        let minor = alert(AlertSeverity::Minor, AlertUrgency::Expected);
+
+        let any = AlertFilter::default();
+        assert!(any.matches(&notice("WAZ558", Some(minor.clone()))));
+        assert!(any.matches(&notice("WAZ558", None)));
+
+        let zones = AlertFilter {
+            zones: hashset! { LocationZoneCode::new("WAZ558") },
+            ..AlertFilter::default()
+        };
+        assert!(zones.matches(&notice("WAZ558", None)));
+        assert!(!zones.matches(&notice("WAZ315", Some(severe.clone()))));
+
+        let severities = AlertFilter {
+            severities: hashset! { AlertSeverity::Extreme, AlertSeverity::Severe },
+            ..AlertFilter::default()
+        };
+        assert!(severities.matches(&notice("WAZ315", Some(severe.clone()))));
+        assert!(!severities.matches(&notice("WAZ315", Some(minor))));
+        assert!(!severities.matches(&notice("WAZ315", None)));
+
+        let urgencies = AlertFilter {
+            severities: hashset! { AlertSeverity::Severe },
+            urgencies: hashset! { AlertUrgency::Immediate },
+            ..AlertFilter::default()
+        };
+        assert!(!urgencies.matches(&notice("WAZ558", Some(severe))));
+    }
+
+    #[test]
+    fn test_alert_notice_type() {
+        assert!(!AlertNoticeType::AlertActivated.ends_alert());
+        assert!(AlertNoticeType::AlertDeactivated.ends_alert());
+        assert!(AlertNoticeType::AlertExpired.ends_alert());
+
+        let mut expired = notice("WAZ558", None);
+        expired.notice_type = AlertNoticeType::AlertExpired;
+        let payload = assert_ok!(serde_json::to_value(&expired));
+        assert_eq!(payload["type"], "alert_expired");
+        assert_eq!(payload["alertId"], "alert-1");
+    }
+}
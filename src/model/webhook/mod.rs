@@ -0,0 +1,82 @@
+mod delivery;
+mod repository;
+
+pub use delivery::{
+    sign, AlertNotice, AlertNoticeType, WebhookDeliveryWorker, WebhookDispatcher, EVENT_ID_HEADER,
+    SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+pub use errors::WebhookError;
+pub use repository::{
+    AlertFilter, WebhookDeadLetter, WebhookDelivery, WebhookId, WebhookRepository, WebhookSecret,
+    WebhookSubscription, MAX_DEAD_LETTERS, WEBHOOK_ALERTS_TABLE, WEBHOOK_DEAD_LETTERS_TABLE,
+    WEBHOOK_DELIVERIES_TABLE, WEBHOOK_SUBSCRIPTIONS_TABLE,
+};
+pub use support::WebhookSupport;
+
+mod errors {
+    use strum_macros::{Display, EnumDiscriminants};
+    use thiserror::Error;
+
+    #[derive(Debug, Error, EnumDiscriminants)]
+    #[strum_discriminants(derive(Display, Serialize, Deserialize))]
+    #[strum_discriminants(name(WebhookFailure))]
+    pub enum WebhookError {
+        #[error("failed to build webhook HTTP client: {0}")]
+        HttpClient(#[from] reqwest::Error),
+
+        #[error("{0}")]
+        JsonSerde(#[from] serde_json::Error),
+
+        #[error("{0}")]
+        Sql(#[from] sqlx::Error),
+
+        #[error("{0}")]
+        Postgres(#[from] disintegrate_postgres::Error),
+    }
+}
+
+mod support {
+    use super::{WebhookDeliveryWorker, WebhookDispatcher, WebhookError, WebhookRepository};
+    use crate::model::weather::WeatherEventStore;
+    use crate::settings::WebhookSettings;
+    use anyhow::anyhow;
+    use disintegrate_postgres::{PgEventListener, PgEventListenerConfig};
+    use sqlx::PgPool;
+    use std::time::Duration;
+    use tokio_util::task::TaskTracker;
+
+    #[derive(Debug, Clone)]
+    pub struct WebhookSupport {
+        pub repository: WebhookRepository,
+    }
+
+    impl WebhookSupport {
+        #[instrument(level = "debug", name = "WebhookSupport::new", skip(es), err)]
+        pub async fn new(
+            pool: PgPool, es: WeatherEventStore, settings: &WebhookSettings,
+            task_tracker: &TaskTracker,
+        ) -> Result<Self, WebhookError> {
+            let repository = WebhookRepository::new(pool);
+            repository.initialize().await?;
+
+            WebhookDeliveryWorker::new(repository.clone(), settings)?.spawn(task_tracker);
+
+            let dispatcher = WebhookDispatcher::new(repository.clone());
+            task_tracker.spawn(async move {
+                PgEventListener::builder(es)
+                    .register_listener(
+                        dispatcher,
+                        PgEventListenerConfig::poller(Duration::from_millis(50)),
+                    )
+                    .start_with_shutdown(crate::shutdown())
+                    .await
+                    .map_err(|e| {
+                        anyhow!("webhook delivery event listener exited with error: {e}")
+                    })?;
+                Ok::<(), anyhow::Error>(())
+            });
+
+            Ok(Self { repository })
+        }
+    }
+}
@@ -0,0 +1,766 @@
+use super::delivery::AlertNotice;
+use super::WebhookError;
+use crate::model::{AlertCategory, AlertSeverity, AlertUrgency, LocationZoneCode, WeatherAlert};
+use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
+use chrono::{DateTime, Utc};
+use once_cell::sync::{Lazy, OnceCell};
+use rand::RngCore;
+use sql_query_builder as sql;
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, Row};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+pub const MAX_DEAD_LETTERS: usize = 100;
+
+pub const WEBHOOK_SUBSCRIPTIONS_VIEW: &str = "webhook_subscriptions";
+pub static WEBHOOK_SUBSCRIPTIONS_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(WEBHOOK_SUBSCRIPTIONS_VIEW).unwrap());
+static PRIMARY_KEY: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("subscription_id").unwrap());
+static URL_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("url").unwrap());
+static SECRET_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("secret").unwrap());
+static FILTER_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("filter").unwrap());
+static CREATED_AT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("created_at").unwrap());
+
+static COLUMNS: Lazy<[TableColumn; 6]> = Lazy::new(|| {
+    [
+        PRIMARY_KEY.clone(),
+        URL_COL.clone(),
+        SECRET_COL.clone(),
+        FILTER_COL.clone(),
+        CREATED_AT_COL.clone(),
+        LAST_UPDATED_AT_COL.clone(),
+    ]
+});
+static COLUMNS_REP: Lazy<String> = Lazy::new(|| COLUMNS.join(", "));
+
+pub const WEBHOOK_DEAD_LETTERS_VIEW: &str = "webhook_dead_letters";
+pub static WEBHOOK_DEAD_LETTERS_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(WEBHOOK_DEAD_LETTERS_VIEW).unwrap());
+static DEAD_LETTER_KEY: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("dead_letter_id").unwrap());
+static EVENT_ID_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("event_id").unwrap());
+static PAYLOAD_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("payload").unwrap());
+static ATTEMPTS_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("attempts").unwrap());
+static LAST_ERROR_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("last_error").unwrap());
+static FAILED_AT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("failed_at").unwrap());
+
+static DEAD_LETTER_COLUMNS: Lazy<[TableColumn; 7]> = Lazy::new(|| {
+    [
+        DEAD_LETTER_KEY.clone(),
+        PRIMARY_KEY.clone(),
+        EVENT_ID_COL.clone(),
+        PAYLOAD_COL.clone(),
+        ATTEMPTS_COL.clone(),
+        LAST_ERROR_COL.clone(),
+        FAILED_AT_COL.clone(),
+    ]
+});
+
+pub const WEBHOOK_DELIVERIES_VIEW: &str = "webhook_deliveries";
+pub static WEBHOOK_DELIVERIES_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(WEBHOOK_DELIVERIES_VIEW).unwrap());
+static NEXT_ATTEMPT_AT_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("next_attempt_at").unwrap());
+
+static DELIVERY_COLUMNS: Lazy<[TableColumn; 6]> = Lazy::new(|| {
+    [
+        PRIMARY_KEY.clone(),
+        EVENT_ID_COL.clone(),
+        PAYLOAD_COL.clone(),
+        ATTEMPTS_COL.clone(),
+        LAST_ERROR_COL.clone(),
+        NEXT_ATTEMPT_AT_COL.clone(),
+    ]
+});
+
+pub const WEBHOOK_ALERTS_VIEW: &str = "webhook_alerts";
+pub static WEBHOOK_ALERTS_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(WEBHOOK_ALERTS_VIEW).unwrap());
+static ZONE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone").unwrap());
+static ALERT_ID_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("alert_id").unwrap());
+static ALERT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("alert").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, sqlx::Type, ToSchema, Serialize, Deserialize)]
+#[repr(transparent)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct WebhookId(String);
+
+impl fmt::Display for WebhookId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl WebhookId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn random() -> Self {
+        Self::new(cuid2::create_id())
+    }
+}
+
+/// Secret shared with the webhook endpoint to verify the signature of the payloads it receives.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct WebhookSecret(String);
+
+impl fmt::Debug for WebhookSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebhookSecret(<redacted>)")
+    }
+}
+
+impl WebhookSecret {
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// A random 256-bit secret, hex encoded.
+    pub fn generate() -> Self {
+        let mut bytes = [0_u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(hex::encode(bytes))
+    }
+
+    #[inline]
+    pub fn expose(&self) -> &str {
+        self.0.as_str()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Alert notices delivered to a subscription. Each criterion left empty matches any notice;
+/// otherwise the notice must match one of its values. Notices without the alert, i.e., the
+/// deactivation of an alert whose activation is not found, only match subscriptions that do not
+/// filter on the alert's severity, urgency or category.
+#[derive(Debug, Default, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AlertFilter {
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub zones: HashSet<LocationZoneCode>,
+
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub severities: HashSet<AlertSeverity>,
+
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub urgencies: HashSet<AlertUrgency>,
+
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub categories: HashSet<AlertCategory>,
+}
+
+impl AlertFilter {
+    pub fn matches(&self, notice: &AlertNotice) -> bool {
+        if !self.zones.is_empty() && !self.zones.contains(&notice.zone) {
+            return false;
+        }
+
+        let filters_alert = !(self.severities.is_empty()
+            && self.urgencies.is_empty()
+            && self.categories.is_empty());
+        match notice.alert.as_deref() {
+            None => !filters_alert,
+            Some(alert) => {
+                (self.severities.is_empty() || self.severities.contains(&alert.severity))
+                    && (self.urgencies.is_empty() || self.urgencies.contains(&alert.urgency))
+                    && (self.categories.is_empty() || self.categories.contains(&alert.category))
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    pub id: WebhookId,
+
+    #[schema(value_type = String)]
+    pub url: Url,
+
+    #[serde(skip)]
+    pub secret: WebhookSecret,
+
+    pub filter: AlertFilter,
+    pub created_at: DateTime<Utc>,
+    pub last_updated_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for WebhookSubscription {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let url: String = row.try_get(URL_COL.clone())?;
+        let url = Url::parse(&url).map_err(|err| sqlx::Error::ColumnDecode {
+            index: URL_COL.to_string(),
+            source: Box::new(err),
+        })?;
+        let secret: String = row.try_get(SECRET_COL.clone())?;
+        let filter: Json<AlertFilter> = row.try_get(FILTER_COL.clone())?;
+
+        Ok(Self {
+            id: row.try_get(PRIMARY_KEY.clone())?,
+            url,
+            secret: WebhookSecret::new(secret),
+            filter: filter.0,
+            created_at: row.try_get(CREATED_AT_COL.clone())?,
+            last_updated_at: row.try_get(LAST_UPDATED_AT_COL.clone())?,
+        })
+    }
+}
+
+/// An alert notice whose delivery to the subscription failed after all attempts.
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeadLetter {
+    pub id: i64,
+    pub subscription_id: WebhookId,
+    pub event_id: i64,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for WebhookDeadLetter {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let payload: Json<serde_json::Value> = row.try_get(PAYLOAD_COL.clone())?;
+
+        Ok(Self {
+            id: row.try_get(DEAD_LETTER_KEY.clone())?,
+            subscription_id: row.try_get(PRIMARY_KEY.clone())?,
+            event_id: row.try_get(EVENT_ID_COL.clone())?,
+            payload: payload.0,
+            attempts: row.try_get(ATTEMPTS_COL.clone())?,
+            last_error: row.try_get(LAST_ERROR_COL.clone())?,
+            failed_at: row.try_get(FAILED_AT_COL.clone())?,
+        })
+    }
+}
+
+/// An alert notice enqueued for delivery to the subscription, which is attempted until it
+/// succeeds or is dead-lettered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub subscription_id: WebhookId,
+    pub event_id: i64,
+    pub payload: serde_json::Value,
+
+    /// Attempts of the delivery made so far.
+    pub attempts: u32,
+}
+
+impl<'r> sqlx::FromRow<'r, PgRow> for WebhookDelivery {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let payload: Json<serde_json::Value> = row.try_get(PAYLOAD_COL.clone())?;
+        let attempts: i32 = row.try_get(ATTEMPTS_COL.clone())?;
+
+        Ok(Self {
+            subscription_id: row.try_get(PRIMARY_KEY.clone())?,
+            event_id: row.try_get(EVENT_ID_COL.clone())?,
+            payload: payload.0,
+            attempts: u32::try_from(attempts).unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates the subscription, dead letter, delivery and alert tables if they do not exist.
+    pub async fn initialize(&self) -> Result<(), WebhookError> {
+        static CREATE_SUBSCRIPTIONS_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_SUBSCRIPTIONS_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {primary_key} TEXT PRIMARY KEY,
+                    {url} TEXT NOT NULL,
+                    {secret} TEXT NOT NULL,
+                    {filter} JSONB NOT NULL DEFAULT '{{}}',
+                    {created_at} TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp(),
+                    {last_updated_at} TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp()
+                )"#,
+                table = WEBHOOK_SUBSCRIPTIONS_TABLE.as_str(),
+                primary_key = PRIMARY_KEY.as_str(),
+                url = URL_COL.as_str(),
+                secret = SECRET_COL.as_str(),
+                filter = FILTER_COL.as_str(),
+                created_at = CREATED_AT_COL.as_str(),
+                last_updated_at = LAST_UPDATED_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&self.pool).await?;
+
+        static CREATE_DEAD_LETTERS_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_DEAD_LETTERS_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {dead_letter_id} BIGINT PRIMARY KEY GENERATED ALWAYS AS IDENTITY,
+                    {subscription_id} TEXT NOT NULL
+                        REFERENCES {subscriptions} ( {subscription_id} ) ON DELETE CASCADE,
+                    {event_id} BIGINT NOT NULL,
+                    {payload} JSONB NOT NULL,
+                    {attempts} INTEGER NOT NULL,
+                    {last_error} TEXT NOT NULL,
+                    {failed_at} TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp()
+                )"#,
+                table = WEBHOOK_DEAD_LETTERS_TABLE.as_str(),
+                dead_letter_id = DEAD_LETTER_KEY.as_str(),
+                subscription_id = PRIMARY_KEY.as_str(),
+                subscriptions = WEBHOOK_SUBSCRIPTIONS_TABLE.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                payload = PAYLOAD_COL.as_str(),
+                attempts = ATTEMPTS_COL.as_str(),
+                last_error = LAST_ERROR_COL.as_str(),
+                failed_at = FAILED_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&self.pool).await?;
+
+        static CREATE_DELIVERIES_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_DELIVERIES_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {subscription_id} TEXT NOT NULL
+                        REFERENCES {subscriptions} ( {subscription_id} ) ON DELETE CASCADE,
+                    {event_id} BIGINT NOT NULL,
+                    {payload} JSONB NOT NULL,
+                    {attempts} INTEGER NOT NULL DEFAULT 0,
+                    {last_error} TEXT NULL,
+                    {next_attempt_at} TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp(),
+                    PRIMARY KEY ( {event_id}, {subscription_id} )
+                )"#,
+                table = WEBHOOK_DELIVERIES_TABLE.as_str(),
+                subscription_id = PRIMARY_KEY.as_str(),
+                subscriptions = WEBHOOK_SUBSCRIPTIONS_TABLE.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                payload = PAYLOAD_COL.as_str(),
+                attempts = ATTEMPTS_COL.as_str(),
+                last_error = LAST_ERROR_COL.as_str(),
+                next_attempt_at = NEXT_ATTEMPT_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&self.pool).await?;
+
+        static CREATE_ALERTS_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_ALERTS_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {zone} TEXT NOT NULL,
+                    {alert_id} TEXT NOT NULL,
+                    {event_id} BIGINT NOT NULL,
+                    {alert} JSONB NOT NULL,
+                    PRIMARY KEY ( {zone}, {alert_id} )
+                )"#,
+                table = WEBHOOK_ALERTS_TABLE.as_str(),
+                zone = ZONE_COL.as_str(),
+                alert_id = ALERT_ID_COL.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                alert = ALERT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn subscriptions(&self) -> Result<Vec<WebhookSubscription>, WebhookError> {
+        static SUBSCRIPTIONS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = SUBSCRIPTIONS_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(&COLUMNS_REP)
+                .from(&WEBHOOK_SUBSCRIPTIONS_TABLE)
+                .order_by(CREATED_AT_COL.as_str())
+                .to_string()
+        });
+
+        let subscriptions = sqlx::query_as(sql).fetch_all(&self.pool).await?;
+        Ok(subscriptions)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn subscription(
+        &self, id: &WebhookId,
+    ) -> Result<Option<WebhookSubscription>, WebhookError> {
+        static SUBSCRIPTION_SQL: OnceCell<String> = OnceCell::new();
+        let sql = SUBSCRIPTION_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(&COLUMNS_REP)
+                .from(&WEBHOOK_SUBSCRIPTIONS_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .to_string()
+        });
+
+        let subscription = sqlx::query_as(sql).bind(id).fetch_optional(&self.pool).await?;
+        Ok(subscription)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn create(
+        &self, url: Url, secret: WebhookSecret, filter: AlertFilter,
+    ) -> Result<WebhookSubscription, WebhookError> {
+        static CREATE_SQL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_SQL.get_or_init(|| {
+            let values = (1..=COLUMNS.len()).map(|i| format!("${i}")).collect::<Vec<_>>();
+            let insert = sql::Insert::new()
+                .insert_into(
+                    format!(
+                        "{table} ( {columns} )",
+                        table = WEBHOOK_SUBSCRIPTIONS_TABLE.as_str(),
+                        columns = COLUMNS_REP.as_str(),
+                    )
+                    .as_str(),
+                )
+                .values(format!("( {} )", values.join(", ")).as_str());
+            format!("{insert} RETURNING {}", COLUMNS_REP.as_str())
+        });
+
+        let now = Utc::now();
+        let subscription = sqlx::query_as(sql)
+            .bind(WebhookId::random())
+            .bind(url.as_str())
+            .bind(secret.expose())
+            .bind(Json(filter))
+            .bind(now)
+            .bind(now)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(subscription)
+    }
+
+    /// Replaces the subscription's endpoint and filter, and its secret if given.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn update(
+        &self, id: &WebhookId, url: Url, secret: Option<WebhookSecret>, filter: AlertFilter,
+    ) -> Result<Option<WebhookSubscription>, WebhookError> {
+        static UPDATE_SQL: OnceCell<String> = OnceCell::new();
+        let sql = UPDATE_SQL.get_or_init(|| {
+            let update = sql::Update::new()
+                .update(&WEBHOOK_SUBSCRIPTIONS_TABLE)
+                .set(format!("{} = $2", URL_COL.as_str()).as_str())
+                .set(
+                    format!(
+                        "{secret} = COALESCE($3, {secret})",
+                        secret = SECRET_COL.as_str()
+                    )
+                    .as_str(),
+                )
+                .set(format!("{} = $4", FILTER_COL.as_str()).as_str())
+                .set(format!("{} = $5", LAST_UPDATED_AT_COL.as_str()).as_str())
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str());
+            format!("{update} RETURNING {}", COLUMNS_REP.as_str())
+        });
+
+        let subscription = sqlx::query_as(sql)
+            .bind(id)
+            .bind(url.as_str())
+            .bind(secret.as_ref().map(|s| s.expose()))
+            .bind(Json(filter))
+            .bind(Utc::now())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(subscription)
+    }
+
+    /// Deletes the subscription along with its dead letters, returning whether it existed.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn delete(&self, id: &WebhookId) -> Result<bool, WebhookError> {
+        static DELETE_SQL: OnceCell<String> = OnceCell::new();
+        let sql = DELETE_SQL.get_or_init(|| {
+            sql::Delete::new()
+                .delete_from(&WEBHOOK_SUBSCRIPTIONS_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .to_string()
+        });
+
+        let result = sqlx::query(sql).bind(id).execute(&self.pool).await?;
+        Ok(0 < result.rows_affected())
+    }
+
+    /// The subscription's most recent dead letters, up to `MAX_DEAD_LETTERS`.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn dead_letters(
+        &self, id: &WebhookId,
+    ) -> Result<Vec<WebhookDeadLetter>, WebhookError> {
+        static DEAD_LETTERS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = DEAD_LETTERS_SQL.get_or_init(|| {
+            let select = sql::Select::new()
+                .select(DEAD_LETTER_COLUMNS.join(", ").as_str())
+                .from(&WEBHOOK_DEAD_LETTERS_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .order_by(format!("{} DESC", DEAD_LETTER_KEY.as_str()).as_str());
+            format!("{select} LIMIT {MAX_DEAD_LETTERS}")
+        });
+
+        let dead_letters = sqlx::query_as(sql).bind(id).fetch_all(&self.pool).await?;
+        Ok(dead_letters)
+    }
+
+    /// Enqueues the alert notice for delivery to each of the subscriptions. Enqueuing is
+    /// idempotent, so a notice enqueued again, e.g., when its event is handled again, is not
+    /// delivered twice.
+    #[instrument(level = "debug", skip(self, payload), err)]
+    pub async fn enqueue_deliveries(
+        &self, event_id: i64, payload: &serde_json::Value, subscription_ids: &[WebhookId],
+    ) -> Result<(), WebhookError> {
+        static ENQUEUE_DELIVERIES_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ENQUEUE_DELIVERIES_SQL.get_or_init(|| {
+            format!(
+                r#"
+                INSERT INTO {table} ( {subscription_id}, {event_id}, {payload} )
+                SELECT subscription.id, $2, $3 FROM UNNEST($1::TEXT[]) AS subscription(id)
+                ON CONFLICT ( {event_id}, {subscription_id} ) DO NOTHING"#,
+                table = WEBHOOK_DELIVERIES_TABLE.as_str(),
+                subscription_id = PRIMARY_KEY.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                payload = PAYLOAD_COL.as_str(),
+            )
+        });
+
+        if subscription_ids.is_empty() {
+            return Ok(());
+        }
+
+        let subscription_ids: Vec<String> =
+            subscription_ids.iter().map(|id| id.to_string()).collect();
+        sqlx::query(sql)
+            .bind(subscription_ids)
+            .bind(event_id)
+            .bind(Json(payload))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Claims up to `limit` deliveries due by now, in the order they are due, by deferring their
+    /// next attempt to `claimed_until`. A delivery whose attempt does not conclude before then,
+    /// e.g., because the worker stopped, is attempted again.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn claim_due_deliveries(
+        &self, claimed_until: DateTime<Utc>, limit: usize,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        static CLAIM_DUE_DELIVERIES_SQL: OnceCell<String> = OnceCell::new();
+        let sql = CLAIM_DUE_DELIVERIES_SQL.get_or_init(|| {
+            format!(
+                r#"
+                UPDATE {table} SET {next_attempt_at} = $2
+                WHERE ( {event_id}, {subscription_id} ) IN (
+                    SELECT {event_id}, {subscription_id} FROM {table}
+                    WHERE {next_attempt_at} <= $1
+                    ORDER BY {next_attempt_at}
+                    LIMIT $3
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING {columns}"#,
+                table = WEBHOOK_DELIVERIES_TABLE.as_str(),
+                next_attempt_at = NEXT_ATTEMPT_AT_COL.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                subscription_id = PRIMARY_KEY.as_str(),
+                columns = DELIVERY_COLUMNS.join(", "),
+            )
+        });
+
+        let mut deliveries: Vec<WebhookDelivery> = sqlx::query_as(sql)
+            .bind(Utc::now())
+            .bind(claimed_until)
+            .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+            .fetch_all(&self.pool)
+            .await?;
+        deliveries.sort_by_key(|delivery| delivery.event_id);
+        Ok(deliveries)
+    }
+
+    /// Notes the failed attempt of the delivery, which is attempted again at `next_attempt_at`.
+    #[instrument(level = "debug", skip(self, delivery), fields(event_id=%delivery.event_id), err)]
+    pub async fn reschedule_delivery(
+        &self, delivery: &WebhookDelivery, attempts: u32, last_error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<(), WebhookError> {
+        static RESCHEDULE_DELIVERY_SQL: OnceCell<String> = OnceCell::new();
+        let sql = RESCHEDULE_DELIVERY_SQL.get_or_init(|| {
+            sql::Update::new()
+                .update(&WEBHOOK_DELIVERIES_TABLE)
+                .set(format!("{} = $3", ATTEMPTS_COL.as_str()).as_str())
+                .set(format!("{} = $4", LAST_ERROR_COL.as_str()).as_str())
+                .set(format!("{} = $5", NEXT_ATTEMPT_AT_COL.as_str()).as_str())
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .where_and(format!("{} = $2", EVENT_ID_COL.as_str()).as_str())
+                .to_string()
+        });
+
+        sqlx::query(sql)
+            .bind(&delivery.subscription_id)
+            .bind(delivery.event_id)
+            .bind(i32::try_from(attempts).unwrap_or(i32::MAX))
+            .bind(last_error)
+            .bind(next_attempt_at)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the delivery once it succeeded.
+    #[instrument(level = "debug", skip(self, delivery), fields(event_id=%delivery.event_id), err)]
+    pub async fn complete_delivery(&self, delivery: &WebhookDelivery) -> Result<(), WebhookError> {
+        let mut conn = self.pool.acquire().await?;
+        Self::remove_delivery(delivery, &mut *conn).await
+    }
+
+    /// Moves the delivery whose attempts are exhausted to the subscription's dead letters.
+    #[instrument(level = "debug", skip(self, delivery), fields(event_id=%delivery.event_id), err)]
+    pub async fn dead_letter_delivery(
+        &self, delivery: &WebhookDelivery, attempts: u32, last_error: &str,
+    ) -> Result<(), WebhookError> {
+        static RECORD_DEAD_LETTER_SQL: OnceCell<String> = OnceCell::new();
+        let sql = RECORD_DEAD_LETTER_SQL.get_or_init(|| {
+            // -- dead_letter_id is generated
+            let columns = DEAD_LETTER_COLUMNS[1..].join(", ");
+            let values =
+                (1..DEAD_LETTER_COLUMNS.len()).map(|i| format!("${i}")).collect::<Vec<_>>();
+            sql::Insert::new()
+                .insert_into(
+                    format!(
+                        "{table} ( {columns} )",
+                        table = WEBHOOK_DEAD_LETTERS_TABLE.as_str(),
+                    )
+                    .as_str(),
+                )
+                .values(format!("( {} )", values.join(", ")).as_str())
+                .to_string()
+        });
+
+        let mut tx = sqlx::Acquire::begin(&self.pool).await?;
+        sqlx::query(sql)
+            .bind(&delivery.subscription_id)
+            .bind(delivery.event_id)
+            .bind(Json(&delivery.payload))
+            .bind(i32::try_from(attempts).unwrap_or(i32::MAX))
+            .bind(last_error)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?;
+        Self::remove_delivery(delivery, &mut *tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn remove_delivery(
+        delivery: &WebhookDelivery, conn: &mut PgConnection,
+    ) -> Result<(), WebhookError> {
+        static REMOVE_DELIVERY_SQL: OnceCell<String> = OnceCell::new();
+        let sql = REMOVE_DELIVERY_SQL.get_or_init(|| {
+            sql::Delete::new()
+                .delete_from(&WEBHOOK_DELIVERIES_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .where_and(format!("{} = $2", EVENT_ID_COL.as_str()).as_str())
+                .to_string()
+        });
+
+        sqlx::query(sql)
+            .bind(&delivery.subscription_id)
+            .bind(delivery.event_id)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Notes the alert as activated or updated for the zone by the event, so its deactivation can
+    /// be delivered with it. An alert noted by a later event is kept.
+    #[instrument(level = "debug", skip(self, alert), err)]
+    pub async fn note_alert(
+        &self, zone: &LocationZoneCode, alert_id: &str, event_id: i64, alert: &WeatherAlert,
+    ) -> Result<(), WebhookError> {
+        static NOTE_ALERT_SQL: OnceCell<String> = OnceCell::new();
+        let sql = NOTE_ALERT_SQL.get_or_init(|| {
+            format!(
+                r#"
+                INSERT INTO {table} ( {zone}, {alert_id}, {event_id}, {alert} )
+                VALUES ( $1, $2, $3, $4 )
+                ON CONFLICT ( {zone}, {alert_id} ) DO UPDATE
+                SET {event_id} = EXCLUDED.{event_id}, {alert} = EXCLUDED.{alert}
+                WHERE {table}.{event_id} < EXCLUDED.{event_id}"#,
+                table = WEBHOOK_ALERTS_TABLE.as_str(),
+                zone = ZONE_COL.as_str(),
+                alert_id = ALERT_ID_COL.as_str(),
+                event_id = EVENT_ID_COL.as_str(),
+                alert = ALERT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql)
+            .bind(zone)
+            .bind(alert_id)
+            .bind(event_id)
+            .bind(Json(alert))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// The alert as last activated or updated for the zone, if noted.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn noted_alert(
+        &self, zone: &LocationZoneCode, alert_id: &str,
+    ) -> Result<Option<WeatherAlert>, WebhookError> {
+        static NOTED_ALERT_SQL: OnceCell<String> = OnceCell::new();
+        let sql = NOTED_ALERT_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(ALERT_COL.as_str())
+                .from(&WEBHOOK_ALERTS_TABLE)
+                .where_clause(format!("{} = $1", ZONE_COL.as_str()).as_str())
+                .where_and(format!("{} = $2", ALERT_ID_COL.as_str()).as_str())
+                .to_string()
+        });
+
+        let alert: Option<Json<WeatherAlert>> = sqlx::query_scalar(sql)
+            .bind(zone)
+            .bind(alert_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(alert.map(|a| a.0))
+    }
+
+    /// Forgets the zone's alert once it is no longer in force, or all of the zone's alerts if no
+    /// alert id is given.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn forget_alerts(
+        &self, zone: &LocationZoneCode, alert_id: Option<&str>,
+    ) -> Result<(), WebhookError> {
+        static FORGET_ALERTS_SQL: OnceCell<String> = OnceCell::new();
+        let sql = FORGET_ALERTS_SQL.get_or_init(|| {
+            sql::Delete::new()
+                .delete_from(&WEBHOOK_ALERTS_TABLE)
+                .where_clause(format!("{} = $1", ZONE_COL.as_str()).as_str())
+                .where_and(
+                    format!("( $2::TEXT IS NULL OR {} = $2 )", ALERT_ID_COL.as_str()).as_str(),
+                )
+                .to_string()
+        });
+
+        sqlx::query(sql).bind(zone).bind(alert_id).execute(&self.pool).await?;
+        Ok(())
+    }
+}
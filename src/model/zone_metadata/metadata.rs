@@ -0,0 +1,181 @@
+use super::spatial::{self, BoundingBox};
+use crate::errors::WeatherError;
+use crate::model::{GeoPoint, LocationZone, LocationZoneCode, LocationZoneType, PropertyExtractor};
+use chrono::{DateTime, Utc};
+use geojson::{feature, Feature, Geometry, JsonObject, JsonValue};
+
+/// What NOAA's `/zones/{type}/{zone}` describes of a zone: its name, the state and forecast offices
+/// it belongs to, its time zone and its boundary.
+#[derive(Debug, Clone, PartialEq, ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneMetadata {
+    pub zone: LocationZoneCode,
+    pub zone_type: LocationZoneType,
+
+    /// Name of the zone, e.g., `Seattle and Vicinity`.
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
+    /// County warning areas, i.e., the forecast offices responsible for the zone, e.g., `SEW`.
+    #[serde(default)]
+    pub cwa: Vec<String>,
+
+    /// IANA time zone of the zone, e.g., `America/Los_Angeles`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+
+    /// Boundary of the zone, which NOAA omits for some zones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub geometry: Option<Geometry>,
+
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl ZoneMetadata {
+    /// Parses NOAA's `/zones/{type}/{zone}` feature for the zone.
+    pub fn from_feature(
+        zone: &LocationZone, feature: Feature, fetched_at: DateTime<Utc>,
+    ) -> Result<Self, WeatherError> {
+        let extract = PropertyExtractor::new("zone_metadata", &feature);
+        let name = extract.property("name")?;
+
+        Ok(Self {
+            zone: zone.code.clone(),
+            zone_type: zone.zone_type,
+            name,
+            state: strings_property(&feature, "state").into_iter().next(),
+            cwa: strings_property(&feature, "cwa"),
+            time_zone: strings_property(&feature, "timeZone").into_iter().next(),
+            geometry: feature.geometry,
+            fetched_at,
+        })
+    }
+
+    pub fn location_zone(&self) -> LocationZone {
+        LocationZone::new(self.zone.clone(), self.zone_type)
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.geometry.as_ref().and_then(BoundingBox::of)
+    }
+
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        self.geometry
+            .as_ref()
+            .is_some_and(|geometry| spatial::contains(geometry, point))
+    }
+
+    /// Distance in kilometers from the point to the zone, which is 0 if the zone contains the
+    /// point, or `None` if the zone has no boundary.
+    pub fn distance_km(&self, point: &GeoPoint) -> Option<f64> {
+        self.geometry
+            .as_ref()
+            .and_then(|geometry| spatial::distance_km(geometry, point))
+    }
+
+    /// The zone as a GeoJSON feature identified by its zone code.
+    pub fn to_feature(&self) -> Feature {
+        let mut properties = JsonObject::new();
+        properties.insert("zone".to_string(), self.zone.to_string().into());
+        properties.insert("zoneType".to_string(), self.zone_type.to_string().into());
+        properties.insert("name".to_string(), self.name.clone().into());
+        if let Some(state) = &self.state {
+            properties.insert("state".to_string(), state.clone().into());
+        }
+        properties.insert("cwa".to_string(), self.cwa.clone().into());
+        if let Some(time_zone) = &self.time_zone {
+            properties.insert("timeZone".to_string(), time_zone.clone().into());
+        }
+        properties.insert("fetchedAt".to_string(), self.fetched_at.to_rfc3339().into());
+
+        Feature {
+            bbox: None,
+            geometry: self.geometry.clone(),
+            id: Some(feature::Id::String(self.zone.to_string())),
+            properties: Some(properties),
+            foreign_members: None,
+        }
+    }
+}
+
+/// A GeoJSON feature identified by `id` whose properties are the fields of the serialized value.
+pub fn feature_of<T: serde::Serialize>(
+    id: impl Into<String>, geometry: Option<Geometry>, value: &T,
+) -> Result<Feature, serde_json::Error> {
+    let properties = match serde_json::to_value(value)? {
+        JsonValue::Object(properties) => properties,
+        other => {
+            let mut properties = JsonObject::new();
+            properties.insert("value".to_string(), other);
+            properties
+        },
+    };
+
+    Ok(Feature {
+        bbox: None,
+        geometry,
+        id: Some(feature::Id::String(id.into())),
+        properties: Some(properties),
+        foreign_members: None,
+    })
+}
+
+/// NOAA reports some zone properties, e.g., `timeZone`, as either a string or a list of strings.
+fn strings_property(feature: &Feature, property: &str) -> Vec<String> {
+    match feature.property(property) {
+        Some(JsonValue::String(value)) => vec![value.clone()],
+        Some(JsonValue::Array(values)) => {
+            values.iter().filter_map(|v| v.as_str()).map(|v| v.to_string()).collect()
+        },
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_zone_metadata_from_feature() {
+        let body = assert_ok!(std::fs::read_to_string(
+            "./tests/data/geojson-waz558-zone-metadata-1.json"
+        ));
+        let feature = assert_ok!(Feature::from_str(&body));
+        let zone = LocationZone::forecast("WAZ558");
+        let fetched_at = Utc::now();
+
+        let metadata = assert_ok!(ZoneMetadata::from_feature(&zone, feature, fetched_at));
+        assert_eq!(metadata.location_zone(), zone);
+        assert_eq!(metadata.name, "Seattle and Vicinity");
+        assert_eq!(metadata.state.as_deref(), Some("WA"));
+        assert_eq!(metadata.cwa, vec!["SEW".to_string()]);
+        assert_eq!(metadata.time_zone.as_deref(), Some("America/Los_Angeles"));
+
+        let seattle = assert_ok!(GeoPoint::from_str("47.6062,-122.3321"));
+        assert!(metadata.contains(&seattle));
+        assert_eq!(metadata.distance_km(&seattle), Some(0.0));
+
+        let tacoma = assert_ok!(GeoPoint::from_str("47.2529,-122.4443"));
+        assert!(!metadata.contains(&tacoma));
+        let distance = assert_some!(metadata.distance_km(&tacoma));
+        assert!(0.0 < distance && distance < 50.0, "distance: {distance}");
+
+        let feature = metadata.to_feature();
+        assert_eq!(feature.id, Some(feature::Id::String("WAZ558".to_string())));
+        assert_eq!(
+            feature.property("name"),
+            Some(&JsonValue::from("Seattle and Vicinity"))
+        );
+        assert_eq!(
+            feature.property("zoneType"),
+            Some(&JsonValue::from("forecast"))
+        );
+        assert_some!(feature.geometry);
+    }
+}
@@ -0,0 +1,65 @@
+mod metadata;
+mod refresh;
+mod repository;
+mod spatial;
+
+pub use errors::ZoneMetadataError;
+pub use metadata::{feature_of, ZoneMetadata};
+pub use refresh::ZoneMetadataRefresher;
+pub use repository::{NearbyZone, ZoneMetadataRepository, MAX_WITHIN_KM, ZONE_METADATA_TABLE};
+pub use spatial::{merge_polygons, BoundingBox, EARTH_RADIUS_KM};
+pub use support::ZoneMetadataSupport;
+
+mod errors {
+    use strum_macros::{Display, EnumDiscriminants};
+    use thiserror::Error;
+
+    #[derive(Debug, Error, EnumDiscriminants)]
+    #[strum_discriminants(derive(Display, Serialize, Deserialize))]
+    #[strum_discriminants(name(ZoneMetadataFailure))]
+    pub enum ZoneMetadataError {
+        #[error("failed to fetch zone metadata from NOAA: {0}")]
+        Noaa(#[from] crate::services::noaa::NoaaWeatherError),
+
+        #[error("{0}")]
+        Registrar(#[from] crate::model::registrar::RegistrarError),
+
+        #[error("{0}")]
+        Sql(#[from] sqlx::Error),
+    }
+}
+
+mod support {
+    use super::{ZoneMetadataError, ZoneMetadataRefresher, ZoneMetadataRepository};
+    use crate::model::registrar::MonitoredZonesRepository;
+    use crate::services::noaa::NoaaWeatherServices;
+    use crate::settings::ZoneMetadataSettings;
+    use sqlx::PgPool;
+    use tokio_util::task::TaskTracker;
+
+    #[derive(Debug, Clone)]
+    pub struct ZoneMetadataSupport {
+        pub repository: ZoneMetadataRepository,
+    }
+
+    impl ZoneMetadataSupport {
+        #[instrument(
+            level = "debug",
+            name = "ZoneMetadataSupport::new",
+            skip(monitored),
+            err
+        )]
+        pub async fn new(
+            pool: PgPool, noaa: NoaaWeatherServices, monitored: MonitoredZonesRepository,
+            settings: &ZoneMetadataSettings, task_tracker: &TaskTracker,
+        ) -> Result<Self, ZoneMetadataError> {
+            let repository = ZoneMetadataRepository::new(pool);
+            repository.initialize().await?;
+
+            ZoneMetadataRefresher::new(repository.clone(), monitored, noaa, settings)
+                .spawn(task_tracker);
+
+            Ok(Self { repository })
+        }
+    }
+}
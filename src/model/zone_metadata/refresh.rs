@@ -0,0 +1,155 @@
+use super::{ZoneMetadataError, ZoneMetadataRepository};
+use crate::model::registrar::MonitoredZonesRepository;
+use crate::model::{LocationZone, LocationZoneCode};
+use crate::services::noaa::{NoaaWeatherServices, ZoneMetadataApi};
+use crate::settings::ZoneMetadataSettings;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio_util::task::TaskTracker;
+
+/// Periodically fetches the metadata of monitored zones that have none stored yet or whose
+/// metadata has grown stale. The refresher is tracked under the application `TaskTracker` and
+/// stops on `crate::shutdown()`.
+#[derive(Debug, Clone)]
+pub struct ZoneMetadataRefresher {
+    repository: ZoneMetadataRepository,
+    monitored: MonitoredZonesRepository,
+    noaa: NoaaWeatherServices,
+    check_interval: Duration,
+    max_age: Duration,
+}
+
+impl ZoneMetadataRefresher {
+    pub fn new(
+        repository: ZoneMetadataRepository, monitored: MonitoredZonesRepository,
+        noaa: NoaaWeatherServices, settings: &ZoneMetadataSettings,
+    ) -> Self {
+        Self {
+            repository,
+            monitored,
+            noaa,
+            check_interval: settings.check_interval,
+            max_age: settings.max_age,
+        }
+    }
+
+    pub fn spawn(self, task_tracker: &TaskTracker) {
+        if self.check_interval.is_zero() {
+            warn!("zone metadata check interval is zero - refresher not started");
+            return;
+        }
+
+        task_tracker.spawn(async move { self.run().await });
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn run(self) {
+        info!(
+            check_interval=?self.check_interval, max_age=?self.max_age,
+            "starting zone metadata refresher"
+        );
+
+        let shutdown = crate::shutdown();
+        tokio::pin!(shutdown);
+
+        let mut ticks = tokio::time::interval(self.check_interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    info!("zone metadata refresher shutting down");
+                    break;
+                },
+
+                _ = ticks.tick() => {
+                    if let Err(error) = self.refresh_due().await {
+                        warn!(?error, "failed to refresh zone metadata -- will retry");
+                    }
+                },
+            }
+        }
+    }
+
+    /// Fetches and stores the metadata of the monitored zones that are due, returning the number
+    /// of zones refreshed. A zone that fails to refresh is retried on the next check.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn refresh_due(&self) -> Result<usize, ZoneMetadataError> {
+        let monitored: Vec<_> = self.monitored.monitored().await?.iter().cloned().collect();
+        let fetched_at = self.repository.fetched_at().await?;
+
+        let mut nr_refreshed = 0;
+        for zone in zones_due(&monitored, &fetched_at, Utc::now(), self.max_age) {
+            match self.noaa.zone_metadata(zone.zone_type, &zone.code).await {
+                Ok(metadata) => {
+                    self.repository.save(&metadata).await?;
+                    nr_refreshed += 1;
+                },
+                Err(error) => warn!(?error, %zone, "failed to fetch zone metadata -- skipping"),
+            }
+        }
+
+        if 0 < nr_refreshed {
+            info!(%nr_refreshed, "refreshed zone metadata");
+        }
+        Ok(nr_refreshed)
+    }
+}
+
+/// The monitored zones without metadata or whose metadata is at least `max_age` old, in zone
+/// order.
+fn zones_due(
+    monitored: &[LocationZone], fetched_at: &HashMap<LocationZoneCode, DateTime<Utc>>,
+    now: DateTime<Utc>, max_age: Duration,
+) -> Vec<LocationZone> {
+    let mut due: Vec<_> = monitored
+        .iter()
+        .filter(|zone| {
+            let Some(fetched_at) = fetched_at.get(&zone.code) else {
+                return true;
+            };
+
+            match now.signed_duration_since(*fetched_at).to_std() {
+                Ok(age) => max_age <= age,
+                Err(_) => false,
+            }
+        })
+        .cloned()
+        .collect();
+    due.sort();
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_zones_due() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 22, 12, 0, 0).unwrap();
+        let (otis, stella, neo) = (
+            LocationZone::forecast("otis"),
+            LocationZone::forecast("stella"),
+            LocationZone::forecast("neo"),
+        );
+        let monitored = vec![stella.clone(), otis.clone(), neo.clone()];
+        let fetched_at = maplit::hashmap! {
+            otis.code.clone() => now - chrono::Duration::hours(2),
+            stella.code.clone() => now - chrono::Duration::days(8),
+            LocationZoneCode::new("cherry") => now - chrono::Duration::days(30),
+        };
+
+        let max_age = Duration::from_secs(7 * 24 * 60 * 60);
+        assert_eq!(
+            zones_due(&monitored, &fetched_at, now, max_age),
+            vec![neo.clone(), stella.clone()]
+        );
+        assert_eq!(
+            zones_due(&monitored, &fetched_at, now, Duration::from_secs(60 * 60)),
+            vec![neo, otis, stella]
+        );
+    }
+}
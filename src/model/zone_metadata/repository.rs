@@ -0,0 +1,284 @@
+use super::spatial::BoundingBox;
+use super::{ZoneMetadata, ZoneMetadataError};
+use crate::model::{GeoPoint, LocationZoneCode};
+use crate::postgres::{TableColumn, TableName, LAST_UPDATED_AT_COL};
+use chrono::{DateTime, Utc};
+use geojson::Geometry;
+use once_cell::sync::{Lazy, OnceCell};
+use sql_query_builder as sql;
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Radius of the widest `zones within` query.
+pub const MAX_WITHIN_KM: f64 = 500.0;
+
+pub const ZONE_METADATA_VIEW: &str = "zone_metadata";
+pub static ZONE_METADATA_TABLE: Lazy<TableName> =
+    Lazy::new(|| TableName::from_str(ZONE_METADATA_VIEW).unwrap());
+static PRIMARY_KEY: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone").unwrap());
+static ZONE_TYPE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("zone_type").unwrap());
+static NAME_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("name").unwrap());
+static STATE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("state").unwrap());
+static CWA_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("cwa").unwrap());
+static TIME_ZONE_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("time_zone").unwrap());
+static GEOMETRY_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("geometry").unwrap());
+static MIN_LONGITUDE_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("min_longitude").unwrap());
+static MIN_LATITUDE_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("min_latitude").unwrap());
+static MAX_LONGITUDE_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("max_longitude").unwrap());
+static MAX_LATITUDE_COL: Lazy<TableColumn> =
+    Lazy::new(|| TableColumn::new("max_latitude").unwrap());
+static FETCHED_AT_COL: Lazy<TableColumn> = Lazy::new(|| TableColumn::new("fetched_at").unwrap());
+
+static COLUMNS: Lazy<[TableColumn; 13]> = Lazy::new(|| {
+    [
+        PRIMARY_KEY.clone(),
+        ZONE_TYPE_COL.clone(),
+        NAME_COL.clone(),
+        STATE_COL.clone(),
+        CWA_COL.clone(),
+        TIME_ZONE_COL.clone(),
+        GEOMETRY_COL.clone(),
+        MIN_LONGITUDE_COL.clone(),
+        MIN_LATITUDE_COL.clone(),
+        MAX_LONGITUDE_COL.clone(),
+        MAX_LATITUDE_COL.clone(),
+        FETCHED_AT_COL.clone(),
+        LAST_UPDATED_AT_COL.clone(),
+    ]
+});
+static COLUMNS_REP: Lazy<String> = Lazy::new(|| COLUMNS.join(", "));
+static VALUES_REP: Lazy<String> = Lazy::new(|| {
+    let values = (1..=COLUMNS.len()).map(|i| format!("${i}")).collect::<Vec<_>>().join(", ");
+
+    format!("( {values} )")
+});
+
+impl<'r> sqlx::FromRow<'r, PgRow> for ZoneMetadata {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let cwa: Json<Vec<String>> = row.try_get(CWA_COL.clone())?;
+        let geometry: Option<Json<Geometry>> = row.try_get(GEOMETRY_COL.clone())?;
+
+        Ok(Self {
+            zone: row.try_get(PRIMARY_KEY.clone())?,
+            zone_type: row.try_get(ZONE_TYPE_COL.clone())?,
+            name: row.try_get(NAME_COL.clone())?,
+            state: row.try_get(STATE_COL.clone())?,
+            cwa: cwa.0,
+            time_zone: row.try_get(TIME_ZONE_COL.clone())?,
+            geometry: geometry.map(|g| g.0),
+            fetched_at: row.try_get(FETCHED_AT_COL.clone())?,
+        })
+    }
+}
+
+/// A zone within the distance of a `zones within` query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearbyZone {
+    pub metadata: ZoneMetadata,
+    pub distance_km: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ZoneMetadataRepository {
+    pool: PgPool,
+}
+
+impl ZoneMetadataRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates the zone metadata table if it does not exist.
+    pub async fn initialize(&self) -> Result<(), ZoneMetadataError> {
+        static CREATE_TABLE_DDL: OnceCell<String> = OnceCell::new();
+        let sql = CREATE_TABLE_DDL.get_or_init(|| {
+            format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table} (
+                    {primary_key} TEXT PRIMARY KEY,
+                    {zone_type} TEXT NOT NULL,
+                    {name} TEXT NOT NULL,
+                    {state} TEXT NULL,
+                    {cwa} JSONB NOT NULL DEFAULT '[]',
+                    {time_zone} TEXT NULL,
+                    {geometry} JSONB NULL,
+                    {min_longitude} DOUBLE PRECISION NULL,
+                    {min_latitude} DOUBLE PRECISION NULL,
+                    {max_longitude} DOUBLE PRECISION NULL,
+                    {max_latitude} DOUBLE PRECISION NULL,
+                    {fetched_at} TIMESTAMPTZ NOT NULL,
+                    {last_updated_at} TIMESTAMPTZ NULL DEFAULT clock_timestamp()
+                )"#,
+                table = ZONE_METADATA_TABLE.as_str(),
+                primary_key = PRIMARY_KEY.as_str(),
+                zone_type = ZONE_TYPE_COL.as_str(),
+                name = NAME_COL.as_str(),
+                state = STATE_COL.as_str(),
+                cwa = CWA_COL.as_str(),
+                time_zone = TIME_ZONE_COL.as_str(),
+                geometry = GEOMETRY_COL.as_str(),
+                min_longitude = MIN_LONGITUDE_COL.as_str(),
+                min_latitude = MIN_LATITUDE_COL.as_str(),
+                max_longitude = MAX_LONGITUDE_COL.as_str(),
+                max_latitude = MAX_LATITUDE_COL.as_str(),
+                fetched_at = FETCHED_AT_COL.as_str(),
+                last_updated_at = LAST_UPDATED_AT_COL.as_str(),
+            )
+        });
+
+        sqlx::query(sql).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self, metadata), fields(zone=%metadata.zone), err)]
+    pub async fn save(&self, metadata: &ZoneMetadata) -> Result<(), ZoneMetadataError> {
+        static SAVE_SQL: OnceCell<String> = OnceCell::new();
+        let sql = SAVE_SQL.get_or_init(|| {
+            let update_clause = COLUMNS
+                .iter()
+                .filter(|col| col.as_str() != PRIMARY_KEY.as_str())
+                .fold(sql::Update::new(), |clause, col| {
+                    clause.set(format!("{col} = EXCLUDED.{col}").as_str())
+                });
+
+            let conflict_clause = format!("( {} ) DO UPDATE {update_clause}", PRIMARY_KEY.as_str());
+
+            sql::Insert::new()
+                .insert_into(
+                    format!(
+                        "{table} ( {columns} )",
+                        table = ZONE_METADATA_TABLE.as_str(),
+                        columns = COLUMNS_REP.as_str()
+                    )
+                    .as_str(),
+                )
+                .values(&VALUES_REP)
+                .on_conflict(conflict_clause.as_str())
+                .to_string()
+        });
+
+        let bbox = metadata.bounding_box();
+        sqlx::query(sql)
+            .bind(&metadata.zone)
+            .bind(metadata.zone_type)
+            .bind(metadata.name.as_str())
+            .bind(metadata.state.as_deref())
+            .bind(Json(&metadata.cwa))
+            .bind(metadata.time_zone.as_deref())
+            .bind(metadata.geometry.as_ref().map(Json))
+            .bind(bbox.map(|b| b.min_longitude))
+            .bind(bbox.map(|b| b.min_latitude))
+            .bind(bbox.map(|b| b.max_longitude))
+            .bind(bbox.map(|b| b.max_latitude))
+            .bind(metadata.fetched_at)
+            .bind(Utc::now()) // last_updated_at
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn zone(
+        &self, zone: &LocationZoneCode,
+    ) -> Result<Option<ZoneMetadata>, ZoneMetadataError> {
+        static ZONE_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ZONE_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(&COLUMNS_REP)
+                .from(&ZONE_METADATA_TABLE)
+                .where_clause(format!("{} = $1", PRIMARY_KEY.as_str()).as_str())
+                .to_string()
+        });
+
+        let metadata = sqlx::query_as(sql).bind(zone).fetch_optional(&self.pool).await?;
+        Ok(metadata)
+    }
+
+    /// The stored metadata of the zones, in zone order, optionally limited to the zones whose
+    /// bounding box intersects the given box. Zones without stored metadata are left out.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn zones(
+        &self, zones: &[LocationZoneCode], within: Option<BoundingBox>,
+    ) -> Result<Vec<ZoneMetadata>, ZoneMetadataError> {
+        static ZONES_SQL: OnceCell<String> = OnceCell::new();
+        let sql = ZONES_SQL.get_or_init(|| {
+            let intersects = format!(
+                "( $2::DOUBLE PRECISION IS NULL OR ( {min_lon} <= $4 AND $2 <= {max_lon} \
+                AND {min_lat} <= $5 AND $3 <= {max_lat} ) )",
+                min_lon = MIN_LONGITUDE_COL.as_str(),
+                max_lon = MAX_LONGITUDE_COL.as_str(),
+                min_lat = MIN_LATITUDE_COL.as_str(),
+                max_lat = MAX_LATITUDE_COL.as_str(),
+            );
+
+            sql::Select::new()
+                .select(&COLUMNS_REP)
+                .from(&ZONE_METADATA_TABLE)
+                .where_clause(format!("{} = ANY($1)", PRIMARY_KEY.as_str()).as_str())
+                .where_and(&intersects)
+                .order_by(PRIMARY_KEY.as_str())
+                .to_string()
+        });
+
+        let zones: Vec<String> = zones.iter().map(|z| z.to_string()).collect();
+        let metadata = sqlx::query_as(sql)
+            .bind(zones)
+            .bind(within.map(|b| b.min_longitude))
+            .bind(within.map(|b| b.min_latitude))
+            .bind(within.map(|b| b.max_longitude))
+            .bind(within.map(|b| b.max_latitude))
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(metadata)
+    }
+
+    /// The zones whose boundary contains the point, in zone order.
+    pub async fn zones_containing(
+        &self, zones: &[LocationZoneCode], point: &GeoPoint,
+    ) -> Result<Vec<ZoneMetadata>, ZoneMetadataError> {
+        let candidates = self.zones(zones, Some(BoundingBox::around(point, 0.0))).await?;
+        Ok(candidates.into_iter().filter(|z| z.contains(point)).collect())
+    }
+
+    /// The zones within the distance of the point, nearest first. Zones containing the point are
+    /// at distance 0.
+    pub async fn zones_within(
+        &self, zones: &[LocationZoneCode], point: &GeoPoint, distance_km: f64,
+    ) -> Result<Vec<NearbyZone>, ZoneMetadataError> {
+        let candidates = self.zones(zones, Some(BoundingBox::around(point, distance_km))).await?;
+        let mut nearby: Vec<_> = candidates
+            .into_iter()
+            .filter_map(|metadata| {
+                let distance = metadata.distance_km(point)?;
+                (distance <= distance_km).then_some(NearbyZone { metadata, distance_km: distance })
+            })
+            .collect();
+        nearby.sort_by(|lhs, rhs| lhs.distance_km.total_cmp(&rhs.distance_km));
+        Ok(nearby)
+    }
+
+    /// When each zone's stored metadata was fetched from NOAA.
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn fetched_at(
+        &self,
+    ) -> Result<HashMap<LocationZoneCode, DateTime<Utc>>, ZoneMetadataError> {
+        static FETCHED_AT_SQL: OnceCell<String> = OnceCell::new();
+        let sql = FETCHED_AT_SQL.get_or_init(|| {
+            sql::Select::new()
+                .select(PRIMARY_KEY.as_str())
+                .select(FETCHED_AT_COL.as_str())
+                .from(&ZONE_METADATA_TABLE)
+                .to_string()
+        });
+
+        let fetched: Vec<(LocationZoneCode, DateTime<Utc>)> =
+            sqlx::query_as(sql).fetch_all(&self.pool).await?;
+        Ok(fetched.into_iter().collect())
+    }
+}
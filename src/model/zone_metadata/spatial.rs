@@ -0,0 +1,279 @@
+use crate::model::GeoPoint;
+use geojson::{Geometry, PolygonType};
+use rust_decimal::prelude::ToPrimitive;
+
+/// Mean radius of the Earth in kilometers.
+pub const EARTH_RADIUS_KM: f64 = 6_371.0088;
+
+/// Bounding box of a geometry in decimal degrees, used to narrow spatial queries before testing
+/// zone boundaries. Boxes do not wrap around the antimeridian.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingBox {
+    pub min_longitude: f64,
+    pub min_latitude: f64,
+    pub max_longitude: f64,
+    pub max_latitude: f64,
+}
+
+impl BoundingBox {
+    /// Bounding box of the geometry's polygons, or `None` if it has no polygon.
+    pub fn of(geometry: &Geometry) -> Option<Self> {
+        polygons(geometry)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|position| position_of(position))
+            .fold(None, |bbox, (lon, lat)| {
+                let bbox = bbox.unwrap_or(Self {
+                    min_longitude: lon,
+                    min_latitude: lat,
+                    max_longitude: lon,
+                    max_latitude: lat,
+                });
+
+                Some(Self {
+                    min_longitude: bbox.min_longitude.min(lon),
+                    min_latitude: bbox.min_latitude.min(lat),
+                    max_longitude: bbox.max_longitude.max(lon),
+                    max_latitude: bbox.max_latitude.max(lat),
+                })
+            })
+    }
+
+    /// Bounding box of the points within the radius of the point. Near the poles, where a degree
+    /// of longitude shrinks toward nothing, the box spans every longitude.
+    pub fn around(point: &GeoPoint, radius_km: f64) -> Self {
+        let (longitude, latitude) = degrees_of(point);
+        let latitude_delta = (radius_km / EARTH_RADIUS_KM).to_degrees();
+        let min_latitude = (latitude - latitude_delta).max(-90.0);
+        let max_latitude = (latitude + latitude_delta).min(90.0);
+
+        let widest = min_latitude.abs().max(max_latitude.abs()).to_radians().cos();
+        let (min_longitude, max_longitude) = if widest <= f64::EPSILON {
+            (-180.0, 180.0)
+        } else {
+            let longitude_delta = latitude_delta / widest;
+            (
+                (longitude - longitude_delta).max(-180.0),
+                (longitude + longitude_delta).min(180.0),
+            )
+        };
+
+        Self {
+            min_longitude,
+            min_latitude,
+            max_longitude,
+            max_latitude,
+        }
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_longitude <= other.max_longitude
+            && other.min_longitude <= self.max_longitude
+            && self.min_latitude <= other.max_latitude
+            && other.min_latitude <= self.max_latitude
+    }
+}
+
+/// Whether the point lies within the geometry's polygons, excluding their holes.
+pub fn contains(geometry: &Geometry, point: &GeoPoint) -> bool {
+    let point = degrees_of(point);
+    polygons(geometry).into_iter().any(|polygon| {
+        // -- even-odd crossings of the exterior and holes exclude points within the holes
+        polygon.iter().filter(|ring| ring_contains(ring, point)).count() % 2 == 1
+    })
+}
+
+/// Distance in kilometers from the point to the nearest edge of the geometry's polygons, which is
+/// 0 if the geometry contains the point, or `None` if the geometry has no polygon. Distances are
+/// measured in an equirectangular projection centered on the point, which is accurate for the
+/// short distances of nearby zones but not across continents.
+pub fn distance_km(geometry: &Geometry, point: &GeoPoint) -> Option<f64> {
+    let polygons = polygons(geometry);
+    if polygons.is_empty() {
+        return None;
+    }
+
+    if contains(geometry, point) {
+        return Some(0.0);
+    }
+
+    let origin = degrees_of(point);
+    let project = |(lon, lat): (f64, f64)| {
+        let delta_longitude = (lon - origin.0 + 180.0).rem_euclid(360.0) - 180.0;
+        (
+            delta_longitude.to_radians() * origin.1.to_radians().cos() * EARTH_RADIUS_KM,
+            (lat - origin.1).to_radians() * EARTH_RADIUS_KM,
+        )
+    };
+
+    polygons
+        .into_iter()
+        .flatten()
+        .flat_map(|ring| {
+            let positions: Vec<_> = ring.iter().filter_map(|p| position_of(p)).collect();
+            positions
+                .iter()
+                .zip(positions.iter().skip(1))
+                .map(|(a, b)| (project(*a), project(*b)))
+                .collect::<Vec<_>>()
+        })
+        .map(|(a, b)| distance_from_origin_to_segment(a, b))
+        .min_by(|lhs, rhs| lhs.total_cmp(rhs))
+}
+
+/// Merges the polygons of the geometries into one geometry, e.g., the area of an alert affecting
+/// several zones, or `None` if none of the geometries has a polygon. Polygons are collected as is
+/// rather than dissolved along their shared boundaries.
+pub fn merge_polygons<'g>(geometries: impl IntoIterator<Item = &'g Geometry>) -> Option<Geometry> {
+    let mut merged: Vec<PolygonType> = geometries.into_iter().flat_map(polygons).cloned().collect();
+
+    let value = match merged.len() {
+        0 => return None,
+        1 => geojson::Value::Polygon(merged.remove(0)),
+        _ => geojson::Value::MultiPolygon(merged),
+    };
+    Some(Geometry::new(value))
+}
+
+fn polygons(geometry: &Geometry) -> Vec<&PolygonType> {
+    match &geometry.value {
+        geojson::Value::Polygon(polygon) => vec![polygon],
+        geojson::Value::MultiPolygon(polygons) => polygons.iter().collect(),
+        _ => vec![],
+    }
+}
+
+fn position_of(position: &[f64]) -> Option<(f64, f64)> {
+    Some((*position.first()?, *position.get(1)?))
+}
+
+fn degrees_of(point: &GeoPoint) -> (f64, f64) {
+    (
+        point.longitude.to_f64().unwrap_or_default(),
+        point.latitude.to_f64().unwrap_or_default(),
+    )
+}
+
+fn ring_contains(ring: &[Vec<f64>], (x, y): (f64, f64)) -> bool {
+    let positions: Vec<_> = ring.iter().filter_map(|p| position_of(p)).collect();
+    let mut inside = false;
+    for (&(x0, y0), &(x1, y1)) in positions.iter().zip(positions.iter().cycle().skip(1)) {
+        if (y0 > y) != (y1 > y) && x < (x1 - x0) * (y - y0) / (y1 - y0) + x0 {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn distance_from_origin_to_segment((x0, y0): (f64, f64), (x1, y1): (f64, f64)) -> f64 {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared <= f64::EPSILON {
+        0.0
+    } else {
+        (-(x0 * dx + y0 * dy) / length_squared).clamp(0.0, 1.0)
+    };
+
+    (x0 + t * dx).hypot(y0 + t * dy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use claims::*;
+    use std::str::FromStr;
+
+    fn ring(positions: &[(f64, f64)]) -> Vec<Vec<f64>> {
+        positions.iter().map(|(x, y)| vec![*x, *y]).collect()
+    }
+
+    fn square_with_hole() -> Geometry {
+        Geometry::new(geojson::Value::Polygon(vec![
+            ring(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]),
+            ring(&[(0.4, 0.4), (0.6, 0.4), (0.6, 0.6), (0.4, 0.6), (0.4, 0.4)]),
+        ]))
+    }
+
+    fn point(rep: &str) -> GeoPoint {
+        assert_ok!(GeoPoint::from_str(rep))
+    }
+
+    #[test]
+    fn test_contains_excludes_holes() {
+        let geometry = square_with_hole();
+        assert!(contains(&geometry, &point("0.2,0.2")));
+        assert!(!contains(&geometry, &point("0.5,0.5")));
+        assert!(!contains(&geometry, &point("1.5,0.5")));
+
+        let point_geometry = Geometry::new(geojson::Value::Point(vec![0.5, 0.5]));
+        assert!(!contains(&point_geometry, &point("0.5,0.5")));
+    }
+
+    #[test]
+    fn test_distance_km() {
+        let geometry = square_with_hole();
+        assert_eq!(distance_km(&geometry, &point("0.2,0.2")), Some(0.0));
+
+        // -- a degree of latitude is about 111.2 km
+        let distance = assert_some!(distance_km(&geometry, &point("2,0.5")));
+        assert_relative_eq!(distance, 111.19, epsilon = 0.1);
+
+        // -- from inside the hole, the nearest edge is the hole's
+        let distance = assert_some!(distance_km(&geometry, &point("0.5,0.5")));
+        assert_relative_eq!(distance, 11.12, epsilon = 0.1);
+
+        let point_geometry = Geometry::new(geojson::Value::Point(vec![0.5, 0.5]));
+        assert_none!(distance_km(&point_geometry, &point("0.5,0.5")));
+    }
+
+    #[test]
+    fn test_merge_polygons() {
+        let square = square_with_hole();
+        let point_geometry = Geometry::new(geojson::Value::Point(vec![0.5, 0.5]));
+        assert_none!(merge_polygons([&point_geometry]));
+        assert_none!(merge_polygons(Vec::<&Geometry>::new()));
+
+        let single = assert_some!(merge_polygons([&square, &point_geometry]));
+        assert_eq!(single, square);
+
+        let other = Geometry::new(geojson::Value::Polygon(vec![ring(&[
+            (2.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 1.0),
+            (2.0, 0.0),
+        ])]));
+        let merged = assert_some!(merge_polygons([&square, &other]));
+        match &merged.value {
+            geojson::Value::MultiPolygon(polygons) => assert_eq!(polygons.len(), 2),
+            value => panic!("expected MultiPolygon but was {value:?}"),
+        }
+        assert!(contains(&merged, &point("0.5,2.8")));
+        assert!(contains(&merged, &point("0.2,0.2")));
+        assert!(!contains(&merged, &point("0.5,0.5")));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bbox = assert_some!(BoundingBox::of(&square_with_hole()));
+        assert_eq!(
+            bbox,
+            BoundingBox {
+                min_longitude: 0.0,
+                min_latitude: 0.0,
+                max_longitude: 1.0,
+                max_latitude: 1.0,
+            }
+        );
+
+        let around = BoundingBox::around(&point("0.5,2"), 111.19);
+        assert_relative_eq!(around.min_latitude, -0.5, epsilon = 1e-3);
+        assert_relative_eq!(around.min_longitude, 1.0, epsilon = 1e-3);
+        assert!(around.intersects(&bbox));
+        assert!(!BoundingBox::around(&point("0.5,2"), 100.0).intersects(&bbox));
+
+        let polar = BoundingBox::around(&point("89.99,0"), 50.0);
+        assert_eq!((polar.min_longitude, polar.max_longitude), (-180.0, 180.0));
+    }
+}
@@ -1,8 +1,11 @@
+mod alert_routes;
 mod api_errors;
 mod api_result;
 mod health_routes;
+mod response_format;
 mod state;
 mod weather_routes;
+mod webhook_routes;
 
 pub use crate::server::state::AppState;
 
@@ -144,6 +147,8 @@ pub async fn run_http_server(
     let api_routes = Router::new()
         .nest("/health", health_routes::api())
         .nest("/weather", weather_routes::api())
+        .nest("/webhooks", webhook_routes::api())
+        .nest("/alerts", alert_routes::api())
         .with_state(state);
 
     let app = Router::new()
@@ -152,6 +157,14 @@ pub async fn run_http_server(
                 SwaggerUrl::with_primary("weather_api", "/api-doc/weather-openapi.json", true),
                 weather_routes::WeatherApiDoc::openapi(),
             ),
+            (
+                SwaggerUrl::with_primary("webhook_api", "/api-doc/webhook-openapi.json", true),
+                webhook_routes::WebhookApiDoc::openapi(),
+            ),
+            (
+                SwaggerUrl::with_primary("alert_api", "/api-doc/alert-openapi.json", true),
+                alert_routes::AlertApiDoc::openapi(),
+            ),
             (
                 SwaggerUrl::with_primary("health_api", "/api-doc/health-openapi.json", true),
                 health_routes::HealthApiDoc::openapi(),
@@ -0,0 +1,92 @@
+use crate::model::cap::{self, ATOM_MEDIA_TYPE, CAP_MEDIA_TYPE};
+use crate::model::registrar::MonitoredZonesRepository;
+use crate::model::weather::zone::WeatherRepository;
+use crate::server::api_errors::ApiError;
+use crate::server::api_result::OptionalResult;
+use crate::server::state::AppState;
+use axum::extract::{Path, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{routing, Router};
+use chrono::Utc;
+
+const ALERT_FEED_ID: &str = "urn:weather-poc:alerts:monitored";
+const CAP_EXTENSION: &str = ".cap";
+
+#[derive(OpenApi)]
+#[openapi(
+paths(serve_alert_feed, serve_cap_alert),
+components(
+schemas(ApiError)
+),
+tags((name = "alerts", description = "Alert CAP v1.2 Export API"))
+)]
+pub struct AlertApiDoc;
+
+pub fn api() -> Router<AppState> {
+    Router::new()
+        .route("/feed", routing::get(serve_alert_feed))
+        .route("/:alert_file", routing::get(serve_cap_alert))
+}
+
+#[utoipa::path(
+get,
+path = "/feed",
+context_path = "/api/v1/alerts",
+tag = "alerts",
+responses(
+(status = 200, description = "Atom feed of the alerts in force for monitored zones, with one CAP v1.2 alert per entry", content_type = "application/atom+xml", body = String),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(monitored_repo, repo))]
+async fn serve_alert_feed(
+    State(monitored_repo): State<MonitoredZonesRepository>, State(repo): State<WeatherRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let monitored: Vec<_> = monitored_repo
+        .monitored()
+        .await?
+        .iter()
+        .map(|zone| zone.code.clone())
+        .collect();
+    let now = Utc::now();
+    let alerts = repo.alerts_by_zones(&monitored, now).await?;
+    debug!(nr_alerts=%alerts.len(), "serving alert feed for monitored zones");
+
+    let feed = cap::cap_feed(
+        ALERT_FEED_ID,
+        "Weather alerts in force for monitored zones",
+        &alerts,
+        now,
+    );
+    Ok(([(CONTENT_TYPE, ATOM_MEDIA_TYPE)], feed))
+}
+
+#[utoipa::path(
+get,
+path = "/{alert_id}.cap",
+context_path = "/api/v1/alerts",
+tag = "alerts",
+params(
+("alert_id" = String, Path, description = "NOAA alert identifier, e.g., `urn:oid:2.49.0.1...`"),
+),
+responses(
+(status = 200, description = "CAP v1.2 document of the alert", content_type = "application/cap+xml", body = String),
+(status = 404, description = "alert not found or no longer in force"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_cap_alert(
+    Path(alert_file): Path<String>, State(repo): State<WeatherRepository>,
+) -> Result<Response, ApiError> {
+    // -- the router cannot match a suffix within a path segment, so `.cap` is stripped here
+    let Some(alert_id) = alert_file.strip_suffix(CAP_EXTENSION) else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let alert = repo.alert_by_id(alert_id, Utc::now()).await?;
+    let document = alert.map(|a| ([(CONTENT_TYPE, CAP_MEDIA_TYPE)], cap::cap_document(&a)));
+    Ok(OptionalResult(document).into_response())
+}
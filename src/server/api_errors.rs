@@ -11,6 +11,15 @@ pub enum ApiError {
     #[error("call to update weather failed: {0}")]
     UpdateWeather(#[from] crate::model::weather::update::UpdateWeatherError),
 
+    #[error("call to location zone failed: {0}")]
+    LocationZone(#[from] crate::model::weather::zone::LocationZoneError),
+
+    #[error("call to webhooks failed: {0}")]
+    Webhook(#[from] crate::model::webhook::WebhookError),
+
+    #[error("call to zone metadata failed: {0}")]
+    ZoneMetadata(#[from] crate::model::zone_metadata::ZoneMetadataError),
+
     // #[error("{0}")]
     // ParseUrl(#[from] url::ParseError),
     #[error("{0}")]
@@ -19,9 +28,21 @@ pub enum ApiError {
     #[error("Invalid URL path input: {0}")]
     Path(#[from] axum::extract::rejection::PathRejection),
 
+    #[error("Invalid query parameter: {0}")]
+    InvalidQuery(String),
+
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    #[error("Request conflicts with current state: {0}")]
+    Conflict(String),
+
     #[error("Invalid JSON payload: {0}")]
     Json(#[from] axum::extract::rejection::JsonRejection),
 
+    #[error("failed to render response: {0}")]
+    Render(#[from] serde_json::Error),
+
     #[error("HTTP engine error: {0}")]
     HttpEngine(#[from] hyper::Error),
 
@@ -64,6 +85,12 @@ pub enum ApiBootstrapError {
     #[error("failed to initialize Update Locations subsystem: {0}")]
     UpdateLocations(#[from] crate::model::weather::update::UpdateWeatherError),
 
+    #[error("failed to initialize Webhooks subsystem: {0}")]
+    Webhook(#[from] crate::model::webhook::WebhookError),
+
+    #[error("failed to initialize Zone Metadata subsystem: {0}")]
+    ZoneMetadata(#[from] crate::model::zone_metadata::ZoneMetadataError),
+
     #[error("invalid HTTP header value")]
     InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
 
@@ -1,4 +1,5 @@
 use crate::server::api_errors::ApiError;
+use crate::services::noaa::NoaaWeatherError;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -59,6 +60,7 @@ impl From<anyhow::Error> for ErrorReport {
 pub enum HttpError {
     BadRequest { error: ErrorReport },
     NotFound { message: Cow<'static, str> },
+    Conflict { error: ErrorReport },
     Internal { error: ErrorReport },
 }
 
@@ -66,12 +68,22 @@ impl From<anyhow::Error> for HttpError {
     fn from(error: anyhow::Error) -> Self {
         error!("HTTP handler error: {error:?}");
         match error.downcast_ref::<ApiError>() {
-            Some(ApiError::Path(_)) => Self::BadRequest { error: error.into() },
+            Some(ApiError::Path(_) | ApiError::InvalidQuery(_) | ApiError::InvalidRequest(_)) => {
+                Self::BadRequest { error: error.into() }
+            },
+            Some(ApiError::Conflict(_)) => Self::Conflict { error: error.into() },
+            Some(ApiError::Noaa(NoaaWeatherError::NotFound { .. })) => {
+                Self::NotFound { message: error.to_string().into() }
+            },
             Some(
                 ApiError::Registrar(_)
                 | ApiError::UpdateWeather(_)
+                | ApiError::LocationZone(_)
+                | ApiError::Webhook(_)
+                | ApiError::ZoneMetadata(_)
                 | ApiError::Noaa(_)
                 | ApiError::Json(_)
+                | ApiError::Render(_)
                 | ApiError::HttpEngine(_)
                 | ApiError::IO(_)
                 | ApiError::Sql(_)
@@ -90,6 +102,7 @@ impl IntoResponse for HttpError {
         match self {
             Self::NotFound { message } => (StatusCode::NOT_FOUND, Json(message)).into_response(),
             Self::BadRequest { error } => (StatusCode::BAD_REQUEST, Json(error)).into_response(),
+            Self::Conflict { error } => (StatusCode::CONFLICT, Json(error)).into_response(),
             Self::Internal { error } => {
                 (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
             },
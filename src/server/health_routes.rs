@@ -1,5 +1,5 @@
 use super::state::AppState;
-// use crate::model::registrar::MONITORED_ZONES_VIEW;
+use crate::model::registrar::MONITORED_ZONES_TABLE;
 use crate::model::weather::zone::ZONE_WEATHER_TABLE;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -120,19 +120,19 @@ async fn check_health(state: AppState) -> (HealthStatus, HashMap<HealthStatus, V
         .map_err(|err| err.into())
         .map(|_| ());
 
-    // static MONITORED_ZONES_SQL: OnceCell<String> = OnceCell::new();
-    // let monitored_zones_view_select_sql = MONITORED_ZONES_SQL.get_or_init(|| {
-    //     sql::Select::new()
-    //         .select("last_updated_at")
-    //         .from(MONITORED_ZONES_VIEW)
-    //         .to_string()
-    // });
-    let monitored_zones_view_status: Result<(), anyhow::Error> = Ok(());
-    // sqlx::query(monitored_zones_view_select_sql)
-    //     .fetch_optional(&state.db_pool)
-    //     .await
-    //     .map_err(|err| err.into())
-    //     .map(|_| ());
+    static MONITORED_ZONES_SQL: OnceCell<String> = OnceCell::new();
+    let monitored_zones_view_select_sql = MONITORED_ZONES_SQL.get_or_init(|| {
+        sql::Select::new()
+            .select("last_updated_at")
+            .from(&MONITORED_ZONES_TABLE)
+            .to_string()
+    });
+    let monitored_zones_view_status: Result<(), anyhow::Error> =
+        sqlx::query(monitored_zones_view_select_sql)
+            .fetch_optional(&state.db_pool)
+            .await
+            .map_err(|err| err.into())
+            .map(|_| ());
 
     static EVENTS_SQL: OnceCell<String> = OnceCell::new();
     let model_select_sql = EVENTS_SQL
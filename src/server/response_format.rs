@@ -0,0 +1,69 @@
+use crate::server::api_errors::ApiError;
+use async_trait::async_trait;
+use axum::extract::{FromRequestParts, Query};
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::request::Parts;
+use axum::http::HeaderValue;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+pub const GEO_JSON_MEDIA_TYPE: &str = "application/geo+json";
+
+/// Representation of a response, negotiated from the `format` query parameter or else from the
+/// `Accept` header. Responses are plain JSON unless GeoJSON is asked for.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    GeoJson,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FormatParam {
+    /// Response format, `json` or `geojson`; takes precedence over the `Accept` header, where
+    /// `application/geo+json` asks for GeoJSON.
+    format: Option<ResponseFormat>,
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(param) = Query::<FormatParam>::try_from_uri(&parts.uri)
+            .map_err(|rejection| ApiError::InvalidQuery(rejection.to_string()))?;
+        if let Some(format) = param.format {
+            return Ok(format);
+        }
+
+        let accepts_geo_json = parts
+            .headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|accept| accept.to_str().ok())
+            .flat_map(|accept| accept.split(','))
+            .filter_map(|media_range| media_range.split(';').next())
+            .any(|media_type| media_type.trim().eq_ignore_ascii_case(GEO_JSON_MEDIA_TYPE));
+
+        Ok(if accepts_geo_json { Self::GeoJson } else { Self::Json })
+    }
+}
+
+/// GeoJSON response body, served as `application/geo+json`.
+#[derive(Debug, Clone)]
+#[repr(transparent)]
+pub struct GeoJson<T>(pub T);
+
+impl<T: Serialize> IntoResponse for GeoJson<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self.0).into_response();
+        if response.status().is_success() {
+            response
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(GEO_JSON_MEDIA_TYPE));
+        }
+        response
+    }
+}
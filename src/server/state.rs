@@ -1,24 +1,34 @@
+use crate::model::registrar::schedule::{AlertExpiryScheduler, UpdateScheduler};
 use crate::model::registrar::support::RegistrarSupport;
 use crate::model::registrar::{
-    MonitoredLocationZonesRef, RegistrarDecisionMakerRef, RegistrarEventSerde,
+    MonitoredZonesRepository, RegistrarDecisionMakerRef, RegistrarEventSerde,
 };
 use crate::model::weather::update::{
-    UpdateWeatherRepository, UpdateWeatherServices, UpdateWeatherServicesRef, UpdateWeatherSupport,
+    UpdateProgressSender, UpdateWeatherRepository, UpdateWeatherServices, UpdateWeatherServicesRef,
+    UpdateWeatherSupport, UpdateWeatherWatchdog,
 };
-use crate::model::weather::zone::{LocationZoneSupport, WeatherRepository};
-use crate::model::weather::{WeatherDecisionMakerRef, WeatherEventSerde, WeatherSupport};
+use crate::model::weather::zone::{
+    HourlyForecastRepository, LocationZoneSupport, ObservationHistoryRepository, WeatherRepository,
+    ZoneWeatherChangeSender,
+};
+use crate::model::weather::{
+    WeatherDecisionMakerRef, WeatherEventSerde, WeatherEventStore, WeatherSupport,
+};
+use crate::model::webhook::{WebhookRepository, WebhookSupport};
+use crate::model::zone_metadata::{ZoneMetadataRepository, ZoneMetadataSupport};
 use crate::server::api_errors::ApiBootstrapError;
 use crate::server::get_connection_pool;
-use crate::services::noaa::{NoaaWeatherApi, NoaaWeatherServices};
+use crate::services::noaa::{
+    NoaaWeatherApi, NoaaWeatherServices, PointResolver, ReplayWeatherServices,
+};
+use crate::settings::UpdateWeatherSettings;
 use crate::Settings;
 use axum::extract::FromRef;
 use disintegrate_postgres::PgEventStore;
 use sqlx::PgPool;
 use std::fmt;
-use std::str::FromStr;
 use std::sync::Arc;
 use tokio_util::task::TaskTracker;
-use url::Url;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -26,6 +36,9 @@ pub struct AppState {
     pub weather_support: WeatherSupport,
     pub location_zone_support: LocationZoneSupport,
     pub update_weather_support: UpdateWeatherSupport,
+    pub webhook_support: WebhookSupport,
+    pub zone_metadata_support: ZoneMetadataSupport,
+    pub point_resolver: PointResolver,
     pub db_pool: PgPool,
 }
 
@@ -47,15 +60,39 @@ impl FromRef<AppState> for WeatherDecisionMakerRef {
     }
 }
 
+impl FromRef<AppState> for WeatherEventStore {
+    fn from_ref(app: &AppState) -> Self {
+        app.weather_support.event_store.clone()
+    }
+}
+
 impl FromRef<AppState> for WeatherRepository {
     fn from_ref(app: &AppState) -> Self {
         app.location_zone_support.weather_repository.clone()
     }
 }
 
-impl FromRef<AppState> for MonitoredLocationZonesRef {
+impl FromRef<AppState> for ObservationHistoryRepository {
+    fn from_ref(app: &AppState) -> Self {
+        app.location_zone_support.observation_repository.clone()
+    }
+}
+
+impl FromRef<AppState> for HourlyForecastRepository {
+    fn from_ref(app: &AppState) -> Self {
+        app.location_zone_support.hourly_forecast_repository.clone()
+    }
+}
+
+impl FromRef<AppState> for ZoneWeatherChangeSender {
     fn from_ref(app: &AppState) -> Self {
-        app.registrar_support.monitored.clone()
+        app.location_zone_support.changes.clone()
+    }
+}
+
+impl FromRef<AppState> for MonitoredZonesRepository {
+    fn from_ref(app: &AppState) -> Self {
+        app.registrar_support.monitored_repository.clone()
     }
 }
 
@@ -71,6 +108,36 @@ impl FromRef<AppState> for UpdateWeatherServicesRef {
     }
 }
 
+impl FromRef<AppState> for UpdateProgressSender {
+    fn from_ref(app: &AppState) -> Self {
+        app.update_weather_support.progress.clone()
+    }
+}
+
+impl FromRef<AppState> for UpdateWeatherSettings {
+    fn from_ref(app: &AppState) -> Self {
+        app.update_weather_support.settings.clone()
+    }
+}
+
+impl FromRef<AppState> for WebhookRepository {
+    fn from_ref(app: &AppState) -> Self {
+        app.webhook_support.repository.clone()
+    }
+}
+
+impl FromRef<AppState> for PointResolver {
+    fn from_ref(app: &AppState) -> Self {
+        app.point_resolver.clone()
+    }
+}
+
+impl FromRef<AppState> for ZoneMetadataRepository {
+    fn from_ref(app: &AppState) -> Self {
+        app.zone_metadata_support.repository.clone()
+    }
+}
+
 // impl FromRef<AppState> for UpdateWeatherHistoryProjection {
 //     fn from_ref(app: &AppState) -> Self {
 //         app.update_weather_support.update_history_projection.clone()
@@ -98,15 +165,32 @@ impl AppState {
         let weather_event_store =
             PgEventStore::new(db_pool.clone(), WeatherEventSerde::default()).await?;
 
-        let user_agent = reqwest::header::HeaderValue::from_str("(here.com, contact@example.com)")?;
-        let base_url = Url::from_str("https://api.weather.gov")?;
-        let noaa_api = NoaaWeatherApi::new(base_url, user_agent)?;
-        let noaa = NoaaWeatherServices::Noaa(noaa_api);
-        let update_weather_services = Arc::new(UpdateWeatherServices::new(noaa.clone()));
+        let noaa = match settings.noaa.replay_dir.as_ref() {
+            Some(replay_dir) => {
+                warn!(
+                    ?replay_dir,
+                    "replaying captured NOAA responses instead of calling NOAA"
+                );
+                NoaaWeatherServices::Replay(
+                    ReplayWeatherServices::load(replay_dir)?
+                        .with_aggregation(settings.aggregation.clone()),
+                )
+            },
+            None => NoaaWeatherServices::Noaa(
+                NoaaWeatherApi::new(&settings.noaa)?.with_aggregation(settings.aggregation.clone()),
+            ),
+        };
+        let update_weather_services = Arc::new(
+            UpdateWeatherServices::new(noaa.clone())
+                .with_zone_retry(settings.update_weather.zone_retry.clone())
+                .with_hourly_forecast_timeout(settings.update_weather.hourly_forecast_timeout),
+        );
+        let point_resolver = PointResolver::new(noaa.clone(), &settings.noaa.points);
         // -- Weather Core --
 
         // -- Registrar --
         let registrar_support = RegistrarSupport::new(
+            db_pool.clone(),
             registrar_event_store,
             update_weather_services.clone(),
             task_tracker,
@@ -131,13 +215,63 @@ impl AppState {
         // -- Update Weather --
         let update_weather_support = UpdateWeatherSupport::new(
             db_pool.clone(),
-            weather_event_store,
-            update_weather_services,
+            weather_event_store.clone(),
+            update_weather_services.clone(),
+            &settings.update_weather,
             task_tracker,
         )
         .await?;
+
+        UpdateWeatherWatchdog::from_settings(
+            &settings.update_weather,
+            update_weather_support.history_repository.clone(),
+            weather_support.decision_maker.clone(),
+        )
+        .spawn(task_tracker);
         // -- Update WeIIIather --
 
+        // -- Webhooks --
+        let webhook_support = WebhookSupport::new(
+            db_pool.clone(),
+            weather_event_store,
+            &settings.webhook,
+            task_tracker,
+        )
+        .await?;
+        // -- Webhooks --
+
+        // -- Zone Metadata --
+        let zone_metadata_support = ZoneMetadataSupport::new(
+            db_pool.clone(),
+            noaa.clone(),
+            registrar_support.monitored_repository.clone(),
+            &settings.zone_metadata,
+            task_tracker,
+        )
+        .await?;
+        // -- Zone Metadata --
+
+        // -- Update Schedule --
+        AlertExpiryScheduler::from_settings(
+            &settings.update_weather,
+            location_zone_support.weather_repository.clone(),
+            registrar_support.monitored_repository.clone(),
+            weather_support.decision_maker.clone(),
+            update_weather_services.clone(),
+        )
+        .spawn(task_tracker);
+
+        if settings.schedule.enabled {
+            UpdateScheduler::from_settings(
+                &settings.schedule,
+                registrar_support.monitored_repository.clone(),
+                weather_support.decision_maker.clone(),
+                update_weather_services,
+            )?
+            .spawn(task_tracker);
+        }
+        // -- Update Schedule --
+
         // let journal_storage_config =
         //     settings::storage_config_from(&settings.database, &settings.zone);
         // let journal_storage_provider =
@@ -182,6 +316,9 @@ impl AppState {
             weather_support,
             location_zone_support,
             update_weather_support,
+            webhook_support,
+            zone_metadata_support,
+            point_resolver,
             db_pool,
         })
     }
@@ -1,34 +1,87 @@
 use crate::model::registrar::{
-    MonitoredLocationZonesRef, MonitoredLocationZonesView, RegistrarDecisionMakerRef,
+    MonitoredLocationZonesView, MonitoredZonesRepository, RegistrarDecisionMakerRef,
 };
 use crate::model::weather::update::{
-    UpdateWeatherId, UpdateWeatherRepository, UpdateWeatherServicesRef, UpdateWeatherStatusView,
+    SortOrder, UpdateHistory, UpdateHistoryQuery, UpdateProgress, UpdateProgressChange,
+    UpdateProgressSender, UpdateWeatherError, UpdateWeatherId, UpdateWeatherRepository,
+    UpdateWeatherServicesRef, UpdateWeatherStateDiscriminants, UpdateWeatherStatusView,
+    MAX_UPDATE_HISTORY_LIMIT,
+};
+use crate::model::weather::zone::observation_history::{
+    BucketStatistics, ObservationBucket, ObservationSeries, ObservedValue, ZoneObservation,
+    MAX_OBSERVATION_LIMIT,
+};
+use crate::model::weather::zone::{
+    HourlyForecastRepository, ObservationHistory, ObservationHistoryQuery,
+    ObservationHistoryRepository, WeatherRepository, ZoneWeather, ZoneWeatherChangeSender,
+    ZoneWeatherMessage,
+};
+use crate::model::weather::{update, zone, WeatherDecisionMakerRef, WeatherEventStore};
+use crate::model::zone_metadata::{
+    merge_polygons, ZoneMetadata, ZoneMetadataRepository, MAX_WITHIN_KM,
+};
+use crate::model::{
+    registrar, CompassPoint, ForecastDetail, ForecastQuantity, GeoPoint, Gridpoint, HourlyForecast,
+    HourlyForecastPeriod, LocationZone, LocationZoneCode, LocationZoneType, NearbyStation,
+    PointLocation, StationLocation, StationObservation, TemperatureTrend, TemperatureUnit,
+    UnitSystem, WeatherAlert,
 };
-use crate::model::weather::zone::WeatherRepository;
-use crate::model::weather::{update, WeatherDecisionMakerRef};
-use crate::model::{registrar, LocationZoneCode};
 use crate::server::api_errors::ApiError;
 use crate::server::api_result::OptionalResult;
+use crate::server::response_format::{FormatParam, GeoJson, ResponseFormat};
 use crate::server::state::AppState;
-use axum::extract::{Path, State};
+use crate::services::noaa::{NoaaWeatherError, PointResolver};
+use crate::settings::UpdateWeatherSettings;
+use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::{routing, Json, Router};
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use geojson::{feature, Feature, FeatureCollection};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(OpenApi)]
 #[openapi(
 paths(
 update_weather,
+serve_update_history,
 serve_update_status,
+serve_update_events,
+serve_stuck_updates,
+retry_update,
+serve_weather_at,
 serve_location_weather,
+serve_zone_alerts,
+serve_zone_stations,
+serve_zone_forecast,
+serve_zone_hourly_forecast,
+serve_observation_history,
+serve_zone_changes,
 serve_all_zones,
+serve_zone_features,
+serve_zone_feature,
 delete_all_zones,
-add_forecast_zone,
-remove_forecast_zone,
+add_zone,
+remove_zone,
 ),
 components(
 schemas(
-LocationZoneCode, UpdateWeatherStatusView, MonitoredLocationZonesView,
+LocationZoneCode, LocationZoneType, LocationZone, UnitSystem, StationObservation, StationLocation,
+ForecastDetail, ForecastQuantity, TemperatureUnit, TemperatureTrend, CompassPoint,
+HourlyForecast, HourlyForecastPeriod,
+GeoPoint, Gridpoint, NearbyStation, PointLocation, PointWeather, ResponseFormat,
+WeatherAlert,
+UpdateWeatherStatusView, UpdateHistory,
+UpdateProgress, UpdateProgressChange,
+MonitoredLocationZonesView,
+ObservationHistory, ObservationSeries, ZoneObservation, ObservedValue, ObservationBucket,
+BucketStatistics,
 crate::errors::WeatherError, ApiError,
 )
 ),
@@ -39,16 +92,35 @@ pub struct WeatherApiDoc;
 pub fn api() -> Router<AppState> {
     Router::new()
         .route("/", routing::post(update_weather))
+        .route("/updates", routing::get(serve_update_history))
+        .route("/updates/stuck", routing::get(serve_stuck_updates))
         .route("/updates/:update_id", routing::get(serve_update_status))
+        .route(
+            "/updates/:update_id/events",
+            routing::get(serve_update_events),
+        )
+        .route("/updates/:update_id/retry", routing::post(retry_update))
+        .route("/at", routing::get(serve_weather_at))
         .route("/:zone", routing::get(serve_location_weather))
+        .route("/:zone/alerts", routing::get(serve_zone_alerts))
+        .route("/:zone/stations", routing::get(serve_zone_stations))
+        .route("/:zone/forecast", routing::get(serve_zone_forecast))
         .route(
-            "/zones",
-            routing::get(serve_all_zones).delete(delete_all_zones),
+            "/:zone/forecast/hourly",
+            routing::get(serve_zone_hourly_forecast),
         )
         .route(
-            "/zones/:zone",
-            routing::post(add_forecast_zone).delete(remove_forecast_zone),
+            "/:zone/observations",
+            routing::get(serve_observation_history),
         )
+        .route(
+            "/zones",
+            routing::get(serve_all_zones).delete(delete_all_zones),
+        )
+        .route("/zones/changes", routing::get(serve_zone_changes))
+        .route("/zones/metadata", routing::get(serve_zone_features))
+        .route("/zones/:zone/metadata", routing::get(serve_zone_feature))
+        .route("/zones/:zone", routing::post(add_zone).delete(remove_zone))
 }
 
 #[utoipa::path(
@@ -64,10 +136,10 @@ responses(
 #[axum::debug_handler(state = AppState)]
 #[instrument(level = "debug", skip(monitored_repo, dm, services), ret, err)]
 async fn update_weather(
-    State(monitored_repo): State<MonitoredLocationZonesRef>,
+    State(monitored_repo): State<MonitoredZonesRepository>,
     State(dm): State<WeatherDecisionMakerRef>, State(services): State<UpdateWeatherServicesRef>,
-) -> impl IntoResponse {
-    let monitored: Vec<_> = monitored_repo.monitored().iter().cloned().collect();
+) -> Result<impl IntoResponse, ApiError> {
+    let monitored: Vec<_> = monitored_repo.monitored().await?.iter().cloned().collect();
     update::update_weather(monitored.as_slice(), dm, services)
         .await
         .map_err::<ApiError, _>(|err| err.into())
@@ -108,13 +180,112 @@ async fn update_weather(
 //     }
 // }
 
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct UpdateHistoryParams {
+    /// Update state: `Active` or `Finished`.
+    state: Option<String>,
+
+    /// Earliest last updated time included (RFC 3339).
+    from: Option<DateTime<Utc>>,
+
+    /// Updates last updated before this time are included (RFC 3339).
+    to: Option<DateTime<Utc>>,
+
+    /// Includes only updates of this location zone.
+    zone: Option<LocationZoneCode>,
+
+    /// Order of last updated time: `asc` or `desc`; defaults to `desc`.
+    order: Option<String>,
+
+    /// Maximum number of updates in the page; defaults to 50.
+    limit: Option<usize>,
+
+    /// The `nextCursor` of the prior page.
+    cursor: Option<String>,
+}
+
+impl TryFrom<UpdateHistoryParams> for UpdateHistoryQuery {
+    type Error = ApiError;
+
+    fn try_from(params: UpdateHistoryParams) -> Result<Self, Self::Error> {
+        let mut query = Self::default();
+
+        if let Some((from, to)) = params.from.zip(params.to) {
+            if to <= from {
+                return Err(ApiError::InvalidQuery(format!(
+                    "`to` ({to}) must be after `from` ({from})"
+                )));
+            }
+        }
+        query.from = params.from;
+        query.to = params.to;
+        query.zone = params.zone;
+
+        if let Some(rep) = params.state.as_deref() {
+            let state = UpdateWeatherStateDiscriminants::from_str(rep)
+                .map_err(|err| ApiError::InvalidQuery(format!("state {rep:?}: {err}")))?;
+            query.state = Some(state);
+        }
+
+        if let Some(rep) = params.order.as_deref() {
+            query.order = SortOrder::from_str(rep)
+                .map_err(|err| ApiError::InvalidQuery(format!("order {rep:?}: {err}")))?;
+        }
+
+        match params.limit {
+            Some(limit) if limit == 0 || MAX_UPDATE_HISTORY_LIMIT < limit => {
+                return Err(ApiError::InvalidQuery(format!(
+                    "`limit` must be between 1 and {MAX_UPDATE_HISTORY_LIMIT}"
+                )));
+            },
+            Some(limit) => query.limit = limit,
+            None => {},
+        }
+
+        if let Some(rep) = params.cursor.as_deref() {
+            let cursor = rep
+                .parse()
+                .map_err(|err: UpdateWeatherError| ApiError::InvalidQuery(err.to_string()))?;
+            query.cursor = Some(cursor);
+        }
+
+        Ok(query)
+    }
+}
+
 #[utoipa::path(
 get,
 path = "/updates",
 context_path = "/api/v1/weather",
 tag = "weather",
+params(UpdateHistoryParams),
+responses(
+(status = 200, description = "Update weather process history", body = UpdateHistory),
+(status = 400, description = "invalid update history query"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(update_weather_repo))]
+async fn serve_update_history(
+    Query(params): Query<UpdateHistoryParams>,
+    State(update_weather_repo): State<UpdateWeatherRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let query = UpdateHistoryQuery::try_from(params)?;
+    let history = update_weather_repo.update_history(&query).await?;
+    Ok(Json(history))
+}
+
+#[utoipa::path(
+get,
+path = "/updates/{update_id}",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("update_id" = String, Path, description = "Update weather process identifier"),
+),
 responses(
-(status = 200, description = "report on update weather process", body = UpdateLocationsView),
+(status = 200, description = "report on update weather process", body = UpdateWeatherStatusView),
 (status = 404, description = "no update process found for identifier"),
 )
 )]
@@ -133,17 +304,163 @@ async fn serve_update_status(
         .map(OptionalResult)
 }
 
+#[utoipa::path(
+get,
+path = "/updates/{update_id}/events",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("update_id" = String, Path, description = "Update weather process identifier"),
+),
+responses(
+(status = 200, description = "Server-sent events stream of the update's `status`, followed by its progress until it finishes", content_type = "text/event-stream", body = UpdateProgress),
+(status = 404, description = "no update process found for identifier"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(update_weather_repo, progress))]
+async fn serve_update_events(
+    Path(update_id_rep): Path<String>, State(update_weather_repo): State<UpdateWeatherRepository>,
+    State(progress): State<UpdateProgressSender>,
+) -> Result<impl IntoResponse, ApiError> {
+    let update_id = UpdateWeatherId::for_labeled(update_id_rep);
+
+    // -- subscribe before reading the status so no progress is missed in between
+    let progress_rx = progress.subscribe();
+    let Some(view) = update_weather_repo.fetch_optional_update_status(&update_id).await? else {
+        return Ok(OptionalResult(None));
+    };
+
+    let progress_rx =
+        (view.state != UpdateWeatherStateDiscriminants::Finished).then_some(progress_rx);
+    let status =
+        futures::stream::once(async move { Event::default().event("status").json_data(view) });
+    let is_finished = move |update_id: UpdateWeatherId| {
+        let repo = update_weather_repo.clone();
+        async move {
+            match repo.fetch_optional_update_status(&update_id).await {
+                Ok(Some(view)) => view.state == UpdateWeatherStateDiscriminants::Finished,
+                Ok(None) => true,
+                Err(error) => {
+                    warn!(?error, %update_id, "failed to re-read lagged update status");
+                    false
+                },
+            }
+        }
+    };
+    let progress = futures::stream::iter(progress_rx)
+        .flat_map(move |progress_rx| {
+            update::progress_of(update_id.clone(), progress_rx, is_finished.clone())
+        })
+        .map(|progress| {
+            Event::default()
+                .event(<&'static str>::from(&progress.change))
+                .json_data(progress)
+        });
+
+    Ok(OptionalResult(Some(
+        Sse::new(status.chain(progress)).keep_alive(KeepAlive::default()),
+    )))
+}
+
+#[utoipa::path(
+post,
+path = "/updates/{update_id}/retry",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("update_id" = String, Path, description = "Update weather process identifier"),
+),
+responses(
+(status = 200, description = "Retry failed zones of update in a new update, whose identifier is returned if any zones are retried"),
+(status = 404, description = "no update process found for identifier"),
+(status = 409, description = "update process is not finished"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(
+    level = "debug",
+    skip(update_weather_repo, monitored_repo, dm, services),
+    err
+)]
+async fn retry_update(
+    Path(update_id_rep): Path<String>, State(update_weather_repo): State<UpdateWeatherRepository>,
+    State(monitored_repo): State<MonitoredZonesRepository>,
+    State(dm): State<WeatherDecisionMakerRef>, State(services): State<UpdateWeatherServicesRef>,
+) -> Result<impl IntoResponse, ApiError> {
+    let update_id = UpdateWeatherId::for_labeled(update_id_rep);
+    let Some(original) = update_weather_repo.fetch_optional_update_status(&update_id).await? else {
+        return Ok(OptionalResult(None));
+    };
+
+    let monitored: Vec<_> = monitored_repo.monitored().await?.iter().cloned().collect();
+    let retry_id = update::retry_failed_zones(&original, &monitored, dm, services)
+        .await
+        .map_err(|err| match err {
+            UpdateWeatherError::NotFinished(_) => ApiError::Conflict(err.to_string()),
+            err => err.into(),
+        })?;
+
+    Ok(OptionalResult(Some(
+        retry_id.map(|id| id.id.to_string()).unwrap_or_default(),
+    )))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct StuckUpdatesParams {
+    /// Lists updates active longer than this many seconds; defaults to the update deadline.
+    older_than_secs: Option<u64>,
+}
+
+#[utoipa::path(
+get,
+path = "/updates/stuck",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(StuckUpdatesParams),
+responses(
+(status = 200, description = "update weather processes active longer than the threshold", body = [UpdateWeatherStatusView]),
+(status = 400, description = "invalid threshold"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(update_weather_repo))]
+async fn serve_stuck_updates(
+    Query(params): Query<StuckUpdatesParams>, State(settings): State<UpdateWeatherSettings>,
+    State(update_weather_repo): State<UpdateWeatherRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let older_than = params
+        .older_than_secs
+        .map(Duration::from_secs)
+        .unwrap_or(settings.deadline);
+    let started_before = chrono::Duration::from_std(older_than)
+        .ok()
+        .and_then(|older_than| Utc::now().checked_sub_signed(older_than))
+        .ok_or_else(|| {
+            ApiError::InvalidQuery(format!("`older_than_secs` is out of range: {older_than:?}"))
+        })?;
+
+    let stuck = update_weather_repo.fetch_stuck_updates(started_before).await?;
+    Ok(Json(stuck))
+}
+
 #[utoipa::path(
 get,
 path = "/zones",
 context_path = "/api/v1/weather",
 tag = "weather",
+params(FormatParam),
 responses(
-(status = 200, description = "list all monitored zones", body = [MonitoredZonesView])
+(status = 200, description = "list all monitored zones, as a GeoJSON FeatureCollection of their boundaries if asked for", body = MonitoredLocationZonesView)
 ),
 )]
-#[instrument(level = "trace", skip(view_repo))]
-async fn serve_all_zones(State(view_repo): State<MonitoredLocationZonesRef>) -> impl IntoResponse {
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "trace", skip(view_repo, metadata_repo))]
+async fn serve_all_zones(
+    format: ResponseFormat, State(view_repo): State<MonitoredZonesRepository>,
+    State(metadata_repo): State<ZoneMetadataRepository>,
+) -> Result<Response, ApiError> {
     // let registrar_id = registrar::singleton_id();
     // let view_id: PersistenceId = registrar_id.clone().into();
     //
@@ -154,9 +471,40 @@ async fn serve_all_zones(State(view_repo): State<MonitoredLocationZonesRef>) ->
     //     .map(|v| v.map(Json))
     //     .map(OptionalResult);
 
-    let view = view_repo.monitored();
+    let view = view_repo.monitored().await?;
     debug!("registrar monitored zones: {view:?}");
-    Json(view)
+    if format == ResponseFormat::Json {
+        return Ok(Json(view).into_response());
+    }
+
+    let mut zones: Vec<_> = view.iter().cloned().collect();
+    zones.sort();
+    let codes: Vec<_> = zones.iter().map(|zone| zone.code.clone()).collect();
+    let mut metadata: HashMap<_, _> = metadata_repo
+        .zones(&codes, None)
+        .await?
+        .into_iter()
+        .map(|m| (m.zone.clone(), m))
+        .collect();
+
+    let features: FeatureCollection = zones
+        .iter()
+        .map(|zone| match metadata.remove(&zone.code) {
+            Some(m) => m.to_feature(),
+            None => {
+                // -- zones whose metadata is not yet fetched have no boundary to show
+                let mut feature = Feature {
+                    id: Some(feature::Id::String(zone.code.to_string())),
+                    ..Feature::default()
+                };
+                feature.set_property("zone", zone.code.to_string());
+                feature.set_property("zoneType", zone.zone_type.to_string());
+                feature
+            },
+        })
+        .collect();
+
+    Ok(GeoJson(features).into_response())
 }
 
 #[utoipa::path(
@@ -177,21 +525,30 @@ async fn delete_all_zones(
         .map_err::<ApiError, _>(|err| err.into())
 }
 
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ZoneTypeParam {
+    /// NOAA zone type of the monitored zone; defaults to `forecast`.
+    zone_type: Option<LocationZoneType>,
+}
+
 #[utoipa::path(
 post,
 path = "/zones",
 context_path = "/api/v1/weather",
 tag = "weather",
-params(LocationZoneCode),
+params(LocationZoneCode, ZoneTypeParam),
 responses(
 (status = 200, description = "add zone monitoring"),
 )
 )]
 #[instrument(level = "trace", skip(registrar_dm))]
-async fn add_forecast_zone(
-    Path(zone_code): Path<LocationZoneCode>, State(registrar_dm): State<RegistrarDecisionMakerRef>,
+async fn add_zone(
+    Path(zone_code): Path<LocationZoneCode>, Query(params): Query<ZoneTypeParam>,
+    State(registrar_dm): State<RegistrarDecisionMakerRef>,
 ) -> impl IntoResponse {
-    registrar::monitor_forecast_zone(zone_code, &registrar_dm)
+    let zone = LocationZone::new(zone_code, params.zone_type.unwrap_or_default());
+    registrar::monitor_zone(zone, &registrar_dm)
         .await
         .map_err::<ApiError, _>(|err| err.into())
 }
@@ -207,37 +564,619 @@ responses(
 )
 )]
 #[instrument(level = "trace", skip(registrar_dm))]
-async fn remove_forecast_zone(
+async fn remove_zone(
     Path(zone_code): Path<LocationZoneCode>, State(registrar_dm): State<RegistrarDecisionMakerRef>,
 ) -> impl IntoResponse {
-    registrar::ignore_forecast_zone(zone_code, &registrar_dm)
+    registrar::ignore_zone(zone_code, &registrar_dm)
         .await
         .map_err::<ApiError, _>(|err| err.into())
 }
 
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ZoneSpatialParams {
+    /// Latitude in decimal degrees of the point the zones contain or are near.
+    lat: Option<Decimal>,
+
+    /// Longitude in decimal degrees of the point the zones contain or are near.
+    lon: Option<Decimal>,
+
+    /// Zones within this many kilometers of the point are included, up to 500; otherwise only
+    /// zones containing the point are included.
+    within_km: Option<f64>,
+}
+
+impl ZoneSpatialParams {
+    fn point(&self) -> Result<Option<GeoPoint>, ApiError> {
+        match (self.lat, self.lon) {
+            (Some(lat), Some(lon)) => GeoPoint::new(lat, lon)
+                .map(Some)
+                .map_err(|err| ApiError::InvalidQuery(err.to_string())),
+            (None, None) => Ok(None),
+            _ => Err(ApiError::InvalidQuery(
+                "`lat` and `lon` must be given together".to_string(),
+            )),
+        }
+    }
+}
+
 #[utoipa::path(
 get,
-path = "/",
+path = "/zones/metadata",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(ZoneSpatialParams),
+responses(
+(status = 200, description = "GeoJSON FeatureCollection of the monitored zones, or of those containing or near the point; zones near the point are nearest first with their `distanceKm`", body = Object),
+(status = 400, description = "invalid point or distance"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(monitored_repo, repo))]
+async fn serve_zone_features(
+    Query(params): Query<ZoneSpatialParams>,
+    State(monitored_repo): State<MonitoredZonesRepository>,
+    State(repo): State<ZoneMetadataRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let point = params.point()?;
+    let monitored: Vec<_> =
+        monitored_repo.monitored().await?.iter().map(|z| z.code.clone()).collect();
+
+    let features: FeatureCollection = match (point, params.within_km) {
+        (None, None) => repo
+            .zones(&monitored, None)
+            .await?
+            .iter()
+            .map(ZoneMetadata::to_feature)
+            .collect(),
+
+        (None, Some(_)) => {
+            return Err(ApiError::InvalidQuery(
+                "`within_km` requires `lat` and `lon`".to_string(),
+            ));
+        },
+
+        (Some(point), None) => repo
+            .zones_containing(&monitored, &point)
+            .await?
+            .iter()
+            .map(ZoneMetadata::to_feature)
+            .collect(),
+
+        (Some(point), Some(within_km)) => {
+            if !(0.0..=MAX_WITHIN_KM).contains(&within_km) {
+                return Err(ApiError::InvalidQuery(format!(
+                    "`within_km` ({within_km}) must be between 0 and {MAX_WITHIN_KM}"
+                )));
+            }
+
+            repo.zones_within(&monitored, &point, within_km)
+                .await?
+                .into_iter()
+                .map(|nearby| {
+                    let mut feature = nearby.metadata.to_feature();
+                    feature.set_property("distanceKm", nearby.distance_km);
+                    feature
+                })
+                .collect()
+        },
+    };
+
+    Ok(Json(features))
+}
+
+#[utoipa::path(
+get,
+path = "/zones/{zone_code}/metadata",
 context_path = "/api/v1/weather",
 tag = "weather",
 params(
 ("zone_code" = String, Path, description = "Location Zone Code"),
 ),
 responses(
-(status = 200, description = "Location Weather Report", body = WeatherView),
-(status = 404, description = "location zone not found"),
+(status = 200, description = "GeoJSON Feature of the zone's boundary, name, state, forecast offices and time zone", body = Object),
+(status = 404, description = "no metadata for location zone"),
 )
 )]
 #[axum::debug_handler]
 #[instrument(level = "debug", skip(repo))]
+async fn serve_zone_feature(
+    Path(zone): Path<LocationZoneCode>, State(repo): State<ZoneMetadataRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let metadata = repo.zone(&zone).await?;
+    Ok(OptionalResult(metadata.map(|m| Json(m.to_feature()))))
+}
+
+#[utoipa::path(
+get,
+path = "/{zone_code}",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("zone_code" = String, Path, description = "Location Zone Code"),
+LocationWeatherParams,
+FormatParam,
+),
+responses(
+(status = 200, description = "Location Weather Report, as a GeoJSON Feature over the zone's boundary if asked for", body = WeatherView),
+(status = 404, description = "location zone not found"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(repo, metadata_repo))]
 async fn serve_location_weather(
-    Path(zone): Path<LocationZoneCode>, State(repo): State<WeatherRepository>,
-) -> impl IntoResponse {
-    let weather = repo.weather_by_zone(&zone).await.map_err::<ApiError, _>(|err| err.into());
+    Path(zone): Path<LocationZoneCode>, Query(params): Query<LocationWeatherParams>,
+    format: ResponseFormat, State(repo): State<WeatherRepository>,
+    State(metadata_repo): State<ZoneMetadataRepository>,
+) -> Result<Response, ApiError> {
+    let mut weather = repo.weather_by_zone(&zone).await?;
+    if let (Some(w), Some(units)) = (weather.as_mut(), params.units) {
+        w.current = w.current.as_ref().map(|frame| frame.to_units(units));
+    }
     // let view_id = PersistenceId::from_aggregate_id::<LocationZone>(zone_code.as_ref());
     // let view: Result<Option<crate::model::zone::ZoneWeather>, ApiError> =
     //     view_repo.load_projection(&view_id).await.map_err(|err| err.into());
     debug!("location {zone} weather: {weather:?}");
 
-    weather.map(|w| w.map(Json)).map(OptionalResult)
+    match (format, weather) {
+        (ResponseFormat::GeoJson, Some(weather)) => {
+            let geometry = metadata_repo.zone(&zone).await?.and_then(|m| m.geometry);
+            Ok(GeoJson(weather.to_feature(geometry)?).into_response())
+        },
+        (_, weather) => Ok(OptionalResult(weather.map(Json)).into_response()),
+    }
+}
+
+#[utoipa::path(
+get,
+path = "/{zone_code}/alerts",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("zone_code" = String, Path, description = "Location Zone Code"),
+FormatParam,
+),
+responses(
+(status = 200, description = "Alerts in force for the location zone, as a GeoJSON FeatureCollection over the affected zones' boundaries if asked for", body = [WeatherAlert]),
+(status = 404, description = "location zone not found"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(repo, metadata_repo))]
+async fn serve_zone_alerts(
+    Path(zone): Path<LocationZoneCode>, format: ResponseFormat,
+    State(repo): State<WeatherRepository>, State(metadata_repo): State<ZoneMetadataRepository>,
+) -> Result<Response, ApiError> {
+    let Some(weather) = repo.weather_by_zone(&zone).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    match format {
+        ResponseFormat::Json => Ok(Json(weather.alerts).into_response()),
+        ResponseFormat::GeoJson => {
+            let features = alert_features(&weather.alerts, &metadata_repo).await?;
+            Ok(GeoJson(features).into_response())
+        },
+    }
+}
+
+/// Renders the alerts as GeoJSON features over the merged boundaries of their affected zones;
+/// an alert has no geometry if none of its affected zones has metadata yet.
+async fn alert_features(
+    alerts: &[WeatherAlert], metadata_repo: &ZoneMetadataRepository,
+) -> Result<FeatureCollection, ApiError> {
+    let affected: HashSet<_> =
+        alerts.iter().flat_map(|a| a.affected_zones.iter().cloned()).collect();
+    let affected: Vec<_> = affected.into_iter().collect();
+    let geometries: HashMap<_, _> = metadata_repo
+        .zones(&affected, None)
+        .await?
+        .into_iter()
+        .filter_map(|m| m.geometry.map(|geometry| (m.zone, geometry)))
+        .collect();
+
+    alerts
+        .iter()
+        .map(|alert| {
+            let geometry =
+                merge_polygons(alert.affected_zones.iter().filter_map(|zone| geometries.get(zone)));
+            alert.to_feature(geometry).map_err(ApiError::from)
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherAtParams {
+    /// Latitude in decimal degrees, between -90 and 90.
+    lat: Decimal,
+
+    /// Longitude in decimal degrees, between -180 and 180.
+    lon: Decimal,
+}
+
+/// Weather at a point: what the point resolves to and the weather of its forecast zone.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct PointWeather {
+    location: PointLocation,
+
+    /// Weather of the point's forecast zone, once the zone has been updated.
+    #[schema(value_type = Option<Object>)]
+    weather: Option<ZoneWeather>,
+
+    /// Update started for the point's forecast zone if this request began monitoring the zone.
+    /// Weather is pending without an update for a zone already monitored but not yet updated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    update_id: Option<String>,
+}
+
+#[utoipa::path(
+get,
+path = "/at",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(WeatherAtParams),
+responses(
+(status = 200, description = "Point location and the weather of its forecast zone, which is monitored from then on", body = PointWeather),
+(status = 400, description = "invalid latitude or longitude"),
+(status = "5XX", description = "server error", body = WeatherError),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(
+    level = "debug",
+    skip(resolver, monitored_repo, registrar_dm, repo, dm, services)
+)]
+async fn serve_weather_at(
+    Query(params): Query<WeatherAtParams>, State(resolver): State<PointResolver>,
+    State(monitored_repo): State<MonitoredZonesRepository>,
+    State(registrar_dm): State<RegistrarDecisionMakerRef>, State(repo): State<WeatherRepository>,
+    State(dm): State<WeatherDecisionMakerRef>, State(services): State<UpdateWeatherServicesRef>,
+) -> Result<impl IntoResponse, ApiError> {
+    let point = GeoPoint::new(params.lat, params.lon)
+        .map_err(|err| ApiError::InvalidQuery(err.to_string()))?;
+    let location = resolver.resolve(&point).await.map_err(|err| match err {
+        NoaaWeatherError::NotFound { detail, .. } => {
+            ApiError::InvalidQuery(format!("point {point} is outside NOAA coverage: {detail}"))
+        },
+        err => err.into(),
+    })?;
+    let zone = LocationZone::forecast(location.forecast_zone.clone());
+
+    // the monitored zones view may lag the registrar, so it only spares the registrar decision for
+    // zones it already lists; the registrar settles which request began monitoring the zone.
+    let monitored = monitored_repo.monitored().await?;
+    let registered = !monitored.iter().any(|z| z.code == zone.code)
+        && registrar::ensure_zone_monitored(zone.clone(), &registrar_dm).await?;
+    if registered {
+        info!(%point, %zone, "monitoring zone resolved for point");
+    }
+
+    let weather = repo.weather_by_zone(&zone.code).await?;
+    let update_id = match weather {
+        None if registered => update::update_weather(&[zone], dm, services)
+            .await?
+            .map(|id| id.id.to_string()),
+        _ => None,
+    };
+
+    Ok(Json(PointWeather { location, weather, update_id }))
+}
+
+#[utoipa::path(
+get,
+path = "/{zone_code}/stations",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("zone_code" = String, Path, description = "Location Zone Code"),
+LocationWeatherParams,
+),
+responses(
+(status = 200, description = "Latest observation of each station reporting for the location zone", body = [StationObservation]),
+(status = 404, description = "location zone not found"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_zone_stations(
+    Path(zone): Path<LocationZoneCode>, Query(params): Query<LocationWeatherParams>,
+    State(repo): State<WeatherRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut stations = repo.stations_by_zone(&zone).await?;
+    if let (Some(stations), Some(units)) = (stations.as_mut(), params.units) {
+        for station in stations.iter_mut() {
+            station.weather = station.weather.to_units(units);
+        }
+    }
+
+    Ok(OptionalResult(stations.map(Json)))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ForecastPeriodParams {
+    /// Periods ending after this time are included (RFC 3339).
+    from: Option<DateTime<Utc>>,
+
+    /// Periods starting before this time are included (RFC 3339).
+    to: Option<DateTime<Utc>>,
+}
+
+impl ForecastPeriodParams {
+    const fn is_bounded(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    fn check_range(&self) -> Result<(), ApiError> {
+        if let Some((from, to)) = self.from.zip(self.to) {
+            if to <= from {
+                return Err(ApiError::InvalidQuery(format!(
+                    "`to` ({to}) must be after `from` ({from})"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[utoipa::path(
+get,
+path = "/{zone_code}/forecast",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("zone_code" = String, Path, description = "Location Zone Code"),
+ForecastPeriodParams,
+),
+responses(
+(status = 200, description = "Location zone forecast periods overlapping the time range", body = [ForecastDetail]),
+(status = 400, description = "invalid forecast period range, or a range given for a forecast whose periods are not timed, as zone forecasts are"),
+(status = 404, description = "location zone not found"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_zone_forecast(
+    Path(zone): Path<LocationZoneCode>, Query(params): Query<ForecastPeriodParams>,
+    State(repo): State<WeatherRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    params.check_range()?;
+    let periods = repo.forecast_periods(&zone, params.from, params.to).await?;
+    if periods.as_ref().is_some_and(|p| p.untimed) && params.is_bounded() {
+        return Err(ApiError::InvalidQuery(format!(
+            "zone {zone} forecast periods are not timed, so `from` and `to` cannot select them"
+        )));
+    }
+
+    Ok(OptionalResult(periods.map(|p| Json(p.periods))))
+}
+
+#[utoipa::path(
+get,
+path = "/{zone_code}/forecast/hourly",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("zone_code" = String, Path, description = "Location Zone Code"),
+ForecastPeriodParams,
+),
+responses(
+(status = 200, description = "Hourly forecast of the location zone's representative gridpoint, keeping the hours overlapping the time range", body = HourlyForecast),
+(status = 400, description = "invalid forecast period range"),
+(status = 404, description = "no hourly forecast for location zone"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_zone_hourly_forecast(
+    Path(zone): Path<LocationZoneCode>, Query(params): Query<ForecastPeriodParams>,
+    State(repo): State<HourlyForecastRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    params.check_range()?;
+    let forecast = repo.hourly_forecast(&zone, params.from, params.to).await?;
+    Ok(OptionalResult(forecast.map(Json)))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct LocationWeatherParams {
+    /// System of units the weather is converted to: `metric`, `imperial` or `si`; defaults to the
+    /// units NOAA reports.
+    units: Option<UnitSystem>,
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ObservationHistoryParams {
+    /// Earliest observation time included (RFC 3339).
+    from: Option<DateTime<Utc>>,
+
+    /// Observations before this time are included (RFC 3339).
+    to: Option<DateTime<Utc>>,
+
+    /// Comma-separated observation properties, e.g., `temperature,windSpeed`; defaults to all.
+    properties: Option<String>,
+
+    /// Maximum number of observations, or buckets, in the page; defaults to 100.
+    limit: Option<usize>,
+
+    /// The `nextCursor` of the prior page.
+    cursor: Option<DateTime<Utc>>,
+
+    /// Downsamples observations into buckets of this many seconds, each reporting the min, avg and
+    /// max of the properties observed in the bucket.
+    bucket_secs: Option<u64>,
+}
+
+impl TryFrom<ObservationHistoryParams> for ObservationHistoryQuery {
+    type Error = ApiError;
+
+    fn try_from(params: ObservationHistoryParams) -> Result<Self, Self::Error> {
+        let mut query = Self::default();
+
+        if let Some((from, to)) = params.from.zip(params.to) {
+            if to <= from {
+                return Err(ApiError::InvalidQuery(format!(
+                    "`to` ({to}) must be after `from` ({from})"
+                )));
+            }
+        }
+        query.from = params.from;
+        query.to = params.to;
+        query.cursor = params.cursor;
+
+        if let Some(rep) = params.properties.as_deref() {
+            let properties = ObservationHistoryQuery::parse_properties(rep)
+                .map_err(|err| ApiError::InvalidQuery(format!("properties {rep:?}: {err}")))?;
+            if !properties.is_empty() {
+                query.properties = properties;
+            }
+        }
+
+        match params.limit {
+            Some(limit) if limit == 0 || MAX_OBSERVATION_LIMIT < limit => {
+                return Err(ApiError::InvalidQuery(format!(
+                    "`limit` must be between 1 and {MAX_OBSERVATION_LIMIT}"
+                )));
+            },
+            Some(limit) => query.limit = limit,
+            None => {},
+        }
+
+        match params.bucket_secs {
+            Some(0) => {
+                return Err(ApiError::InvalidQuery(
+                    "`bucket_secs` must be greater than zero".to_string(),
+                ));
+            },
+            Some(secs) => query.bucket = Some(Duration::from_secs(secs)),
+            None => {},
+        }
+
+        Ok(query)
+    }
+}
+
+#[utoipa::path(
+get,
+path = "/{zone_code}/observations",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(
+("zone_code" = String, Path, description = "Location Zone Code"),
+ObservationHistoryParams,
+),
+responses(
+(status = 200, description = "Location zone observation history", body = ObservationHistory),
+(status = 400, description = "invalid observation history query"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_observation_history(
+    Path(zone): Path<LocationZoneCode>, Query(params): Query<ObservationHistoryParams>,
+    State(repo): State<ObservationHistoryRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let query = ObservationHistoryQuery::try_from(params)?;
+    let history = repo.observation_history(&zone, &query).await?;
+    Ok(Json(history))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ZoneChangesParams {
+    /// Comma-separated location zone codes to subscribe to, e.g., `WAZ558,WAZ315`.
+    zones: String,
+
+    /// Replays the zones' changes after this event id before live changes; i.e., the `eventId` of
+    /// the last change received, or the `resumeFrom` of a `lagged` or `replay_truncated` message.
+    resume_from: Option<i64>,
+}
+
+#[utoipa::path(
+get,
+path = "/zones/changes",
+context_path = "/api/v1/weather",
+tag = "weather",
+params(ZoneChangesParams),
+responses(
+(status = 101, description = "WebSocket subscription to the weather changes of the zones. A subscriber falling behind is sent a `lagged` message, and a replay of more changes than a subscription replays ends with a `replay_truncated` message; either closes the subscription."),
+(status = 400, description = "invalid zone changes subscription"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(ws, changes, es))]
+async fn serve_zone_changes(
+    ws: WebSocketUpgrade, Query(params): Query<ZoneChangesParams>,
+    State(changes): State<ZoneWeatherChangeSender>, State(es): State<WeatherEventStore>,
+) -> Result<impl IntoResponse, ApiError> {
+    let zones: HashSet<_> = params
+        .zones
+        .split(',')
+        .map(str::trim)
+        .filter(|zone| !zone.is_empty())
+        .map(LocationZoneCode::new)
+        .collect();
+    if zones.is_empty() {
+        return Err(ApiError::InvalidQuery(
+            "`zones` must name at least one location zone".to_string(),
+        ));
+    }
+
+    // -- subscribe before replaying so no change is missed in between
+    let changes_rx = changes.subscribe();
+    let changes = zone::zone_weather_changes(zones, params.resume_from, &es, changes_rx).await?;
+    Ok(ws.on_upgrade(move |socket| send_zone_changes(socket, changes)))
+}
+
+/// Sends the zone changes until the subscription ends, the client closes or the server shuts down.
+/// Sending waits on the client, so a client not keeping up falls behind the changes and is told
+/// where to resume from.
+#[instrument(level = "debug", skip(socket, changes))]
+async fn send_zone_changes(
+    mut socket: WebSocket, changes: impl Stream<Item = ZoneWeatherMessage> + Send,
+) {
+    tokio::pin!(changes);
+
+    let shutdown = crate::shutdown();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => {
+                debug!("closing zone weather subscription on shutdown");
+                break;
+            },
+
+            message = changes.next() => {
+                let Some(message) = message else { break; };
+                let text = match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(error) => {
+                        error!(?error, ?message, "failed to serialize zone weather message");
+                        continue;
+                    },
+                };
+
+                if let Err(error) = socket.send(WsMessage::Text(text)).await {
+                    debug!(?error, "zone weather subscriber disconnected");
+                    return;
+                }
+            },
+
+            received = socket.recv() => match received {
+                Some(Ok(WsMessage::Close(_))) | None => return,
+                Some(Ok(_)) => {},
+                Some(Err(error)) => {
+                    debug!(?error, "zone weather subscriber connection failed");
+                    return;
+                },
+            },
+        }
+    }
+
+    let _ = socket.send(WsMessage::Close(None)).await;
 }
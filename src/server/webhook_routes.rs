@@ -0,0 +1,239 @@
+use crate::model::webhook::{
+    AlertFilter, AlertNotice, AlertNoticeType, WebhookDeadLetter, WebhookId, WebhookRepository,
+    WebhookSecret, WebhookSubscription,
+};
+use crate::model::{AlertCategory, AlertSeverity, AlertUrgency, LocationZoneCode};
+use crate::server::api_errors::ApiError;
+use crate::server::api_result::OptionalResult;
+use crate::server::state::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::{routing, Json, Router};
+use url::Url;
+
+#[derive(OpenApi)]
+#[openapi(
+paths(
+create_webhook,
+serve_webhooks,
+serve_webhook,
+update_webhook,
+delete_webhook,
+serve_dead_letters,
+),
+components(
+schemas(
+WebhookSubscriptionRequest, WebhookSubscription, CreatedWebhookSubscription, WebhookId,
+AlertFilter, WebhookDeadLetter, AlertNotice, AlertNoticeType,
+LocationZoneCode, AlertSeverity, AlertUrgency, AlertCategory,
+ApiError,
+)
+),
+tags((name = "webhooks", description = "Alert Webhook Subscription API"))
+)]
+pub struct WebhookApiDoc;
+
+pub fn api() -> Router<AppState> {
+    Router::new()
+        .route("/", routing::get(serve_webhooks).post(create_webhook))
+        .route(
+            "/:subscription_id",
+            routing::get(serve_webhook).put(update_webhook).delete(delete_webhook),
+        )
+        .route(
+            "/:subscription_id/dead_letters",
+            routing::get(serve_dead_letters),
+        )
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct WebhookSubscriptionRequest {
+    /// HTTP or HTTPS endpoint receiving the `AlertNotice` payloads.
+    url: String,
+
+    /// Secret the payloads are signed with. On creation, a secret is generated if not given; on
+    /// update, the secret is kept if not given.
+    #[schema(value_type = Option<String>)]
+    secret: Option<WebhookSecret>,
+
+    #[serde(default)]
+    filter: AlertFilter,
+}
+
+impl WebhookSubscriptionRequest {
+    fn endpoint(&self) -> Result<Url, ApiError> {
+        let url = Url::parse(&self.url)
+            .map_err(|err| ApiError::InvalidRequest(format!("url {:?}: {err}", self.url)))?;
+
+        match url.scheme() {
+            "http" | "https" => Ok(url),
+            scheme => Err(ApiError::InvalidRequest(format!(
+                "url {:?}: scheme must be http or https, not {scheme}",
+                self.url
+            ))),
+        }
+    }
+
+    fn secret(&self) -> Result<Option<WebhookSecret>, ApiError> {
+        match self.secret.as_ref() {
+            Some(secret) if secret.is_empty() => Err(ApiError::InvalidRequest(
+                "`secret` must not be empty".to_string(),
+            )),
+            secret => Ok(secret.cloned()),
+        }
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    subscription: WebhookSubscription,
+
+    /// Secret the payloads are signed with, which is only reported on creation.
+    secret: String,
+}
+
+#[utoipa::path(
+post,
+path = "/",
+context_path = "/api/v1/webhooks",
+tag = "webhooks",
+request_body = WebhookSubscriptionRequest,
+responses(
+(status = 201, description = "Created webhook subscription, including its signing secret", body = CreatedWebhookSubscription),
+(status = 400, description = "invalid webhook subscription"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(repo, request))]
+async fn create_webhook(
+    State(repo): State<WebhookRepository>, Json(request): Json<WebhookSubscriptionRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let url = request.endpoint()?;
+    let secret = request.secret()?.unwrap_or_else(WebhookSecret::generate);
+    let subscription = repo.create(url, secret.clone(), request.filter).await?;
+    let created = CreatedWebhookSubscription { subscription, secret: secret.expose().to_string() };
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+get,
+path = "/",
+context_path = "/api/v1/webhooks",
+tag = "webhooks",
+responses(
+(status = 200, description = "Webhook subscriptions", body = [WebhookSubscription]),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_webhooks(
+    State(repo): State<WebhookRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let subscriptions = repo.subscriptions().await?;
+    Ok(Json(subscriptions))
+}
+
+#[utoipa::path(
+get,
+path = "/{subscription_id}",
+context_path = "/api/v1/webhooks",
+tag = "webhooks",
+params(
+("subscription_id" = String, Path, description = "Webhook subscription identifier"),
+),
+responses(
+(status = 200, description = "Webhook subscription", body = WebhookSubscription),
+(status = 404, description = "no webhook subscription found for identifier"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_webhook(
+    Path(subscription_id): Path<WebhookId>, State(repo): State<WebhookRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let subscription = repo.subscription(&subscription_id).await?;
+    Ok(OptionalResult(subscription.map(Json)))
+}
+
+#[utoipa::path(
+put,
+path = "/{subscription_id}",
+context_path = "/api/v1/webhooks",
+tag = "webhooks",
+params(
+("subscription_id" = String, Path, description = "Webhook subscription identifier"),
+),
+request_body = WebhookSubscriptionRequest,
+responses(
+(status = 200, description = "Updated webhook subscription", body = WebhookSubscription),
+(status = 400, description = "invalid webhook subscription"),
+(status = 404, description = "no webhook subscription found for identifier"),
+)
+)]
+#[axum::debug_handler(state = AppState)]
+#[instrument(level = "debug", skip(repo, request))]
+async fn update_webhook(
+    Path(subscription_id): Path<WebhookId>, State(repo): State<WebhookRepository>,
+    Json(request): Json<WebhookSubscriptionRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let url = request.endpoint()?;
+    let secret = request.secret()?;
+    let subscription = repo.update(&subscription_id, url, secret, request.filter).await?;
+    Ok(OptionalResult(subscription.map(Json)))
+}
+
+#[utoipa::path(
+delete,
+path = "/{subscription_id}",
+context_path = "/api/v1/webhooks",
+tag = "webhooks",
+params(
+("subscription_id" = String, Path, description = "Webhook subscription identifier"),
+),
+responses(
+(status = 204, description = "Deleted webhook subscription and its dead letters"),
+(status = 404, description = "no webhook subscription found for identifier"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn delete_webhook(
+    Path(subscription_id): Path<WebhookId>, State(repo): State<WebhookRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    let status = if repo.delete(&subscription_id).await? {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    Ok(status)
+}
+
+#[utoipa::path(
+get,
+path = "/{subscription_id}/dead_letters",
+context_path = "/api/v1/webhooks",
+tag = "webhooks",
+params(
+("subscription_id" = String, Path, description = "Webhook subscription identifier"),
+),
+responses(
+(status = 200, description = "Most recent alert notices whose delivery to the subscription failed", body = [WebhookDeadLetter]),
+(status = 404, description = "no webhook subscription found for identifier"),
+)
+)]
+#[axum::debug_handler]
+#[instrument(level = "debug", skip(repo))]
+async fn serve_dead_letters(
+    Path(subscription_id): Path<WebhookId>, State(repo): State<WebhookRepository>,
+) -> Result<impl IntoResponse, ApiError> {
+    if repo.subscription(&subscription_id).await?.is_none() {
+        return Ok(OptionalResult(None));
+    }
+
+    let dead_letters = repo.dead_letters(&subscription_id).await?;
+    Ok(OptionalResult(Some(Json(dead_letters))))
+}
@@ -0,0 +1,465 @@
+//! Offline stand-in for the NOAA weather API.
+//!
+//! Serves recorded GeoJSON fixtures for the routes used by `NoaaWeatherApi`, and can inject
+//! latency, server errors, rate limiting and malformed payloads so the client's retry and parsing
+//! behavior can be exercised without network access. Fixtures are found in a directory by the
+//! NOAA capture file names, so a capture directory can be served as well:
+//!
+//! * `geojson-<zone>-zone-observation-<n>.json` for `/zones/:zone_type/:zone/observations`
+//! * `geojson-<zone>-zone-forecast-<n>.json` for `/zones/:zone_type/:zone/forecast`
+//! * `geojson-<zone>-zone-metadata-<n>.json` for `/zones/:zone_type/:zone`
+//! * `geojson-active_alerts-<n>.json` for `/alerts/active`
+//! * `geojson-<lat>,<lon>-point-<n>.json` for `/points/:point`
+//! * `geojson-<lat>,<lon>-point-stations-<n>.json` for `/points/:point/stations`
+//! * `geojson-<office>_<x>,<y>-gridpoint-hourly-forecast-<n>.json` for
+//!   `/gridpoints/:office/:x,:y/forecast/hourly`
+//!
+//! A zone, point or gridpoint without its own fixture is served the first fixture recorded for any
+//! zone, point or gridpoint.
+use crate::services::noaa::{list_captures, CaptureKey, NoaaEndpoint};
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{routing, Router};
+use rand::Rng;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::Path as FilePath;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use url::Url;
+
+const GEOJSON_CONTENT_TYPE: &str = "application/geo+json";
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Recorded NOAA responses keyed by endpoint and zone.
+#[derive(Debug, Default, Clone)]
+pub struct FakeNoaaFixtures {
+    fixtures: BTreeMap<CaptureKey, String>,
+}
+
+impl FakeNoaaFixtures {
+    /// Loads the fixtures, or NOAA captures, in the directory. When an endpoint and zone has more
+    /// than one, the earliest is served.
+    #[instrument(level = "debug", err)]
+    pub fn load(dir: impl AsRef<FilePath> + fmt::Debug) -> std::io::Result<Self> {
+        let mut fixtures = BTreeMap::new();
+        for (key, _, path) in list_captures(dir.as_ref())? {
+            if !fixtures.contains_key(&key) {
+                debug!(?path, ?key, "loading fake NOAA fixture");
+                fixtures.insert(key, std::fs::read_to_string(&path)?);
+            }
+        }
+
+        Ok(Self { fixtures })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fixtures.is_empty()
+    }
+
+    fn fixture(&self, key: &CaptureKey) -> Option<&str> {
+        self.fixtures
+            .get(key)
+            .or_else(|| {
+                self.fixtures
+                    .iter()
+                    .find(|(k, _)| k.endpoint == key.endpoint)
+                    .map(|(_, body)| body)
+            })
+            .map(|body| body.as_str())
+    }
+}
+
+/// Fault injected into a fake NOAA response.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Fault {
+    #[default]
+    None,
+
+    /// Responds with `503 Service Unavailable`.
+    ServerError,
+
+    /// Responds with `429 Too Many Requests`.
+    TooManyRequests,
+
+    /// Responds successfully with a truncated GeoJSON payload.
+    Malformed,
+
+    /// Responds with `404 Not Found` and a problem+json body, as NOAA does for points outside its
+    /// coverage.
+    NotFound,
+}
+
+/// Faults injected by the fake NOAA service. Scripted faults are applied, in order, to the first
+/// requests served; later requests are subject to the random fault rates, each a probability in
+/// `[0, 1]`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FaultInjection {
+    pub latency: Duration,
+    pub scripted: Vec<Fault>,
+    pub server_error_rate: f64,
+    pub too_many_requests_rate: f64,
+    pub malformed_rate: f64,
+}
+
+impl FaultInjection {
+    /// Clamps the fault rates into `[0, 1]`, treating a non-finite rate as `0`.
+    fn validated(self) -> Self {
+        Self {
+            server_error_rate: probability("server_error_rate", self.server_error_rate),
+            too_many_requests_rate: probability(
+                "too_many_requests_rate",
+                self.too_many_requests_rate,
+            ),
+            malformed_rate: probability("malformed_rate", self.malformed_rate),
+            ..self
+        }
+    }
+
+    fn random_fault(&self) -> Fault {
+        let mut rng = rand::thread_rng();
+        if rng.gen_bool(self.server_error_rate) {
+            Fault::ServerError
+        } else if rng.gen_bool(self.too_many_requests_rate) {
+            Fault::TooManyRequests
+        } else if rng.gen_bool(self.malformed_rate) {
+            Fault::Malformed
+        } else {
+            Fault::None
+        }
+    }
+}
+
+fn probability(name: &str, rate: f64) -> f64 {
+    if rate.is_finite() {
+        rate.clamp(0.0, 1.0)
+    } else {
+        warn!(%rate, "fake NOAA {name} is not a number - no faults injected at this rate");
+        0.0
+    }
+}
+
+#[derive(Debug)]
+struct FakeNoaaState {
+    fixtures: FakeNoaaFixtures,
+    faults: FaultInjection,
+    scripted: Mutex<VecDeque<Fault>>,
+    requests: AtomicUsize,
+}
+
+impl FakeNoaaState {
+    fn next_fault(&self) -> Fault {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        let scripted = self.scripted.lock().ok().and_then(|mut faults| faults.pop_front());
+        scripted.unwrap_or_else(|| self.faults.random_fault())
+    }
+}
+
+/// Fake NOAA weather service; cloned handles share fixtures, faults and request counts.
+#[derive(Debug, Clone)]
+pub struct FakeNoaa {
+    state: Arc<FakeNoaaState>,
+}
+
+impl FakeNoaa {
+    pub fn new(fixtures: FakeNoaaFixtures, faults: FaultInjection) -> Self {
+        let faults = faults.validated();
+        let scripted = Mutex::new(faults.scripted.iter().copied().collect());
+        Self {
+            state: Arc::new(FakeNoaaState {
+                fixtures,
+                faults,
+                scripted,
+                requests: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Number of requests received, including those answered with an injected fault.
+    pub fn requests(&self) -> usize {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route(
+                "/zones/:zone_type/:zone/observations",
+                routing::get(serve_observation),
+            )
+            .route(
+                "/zones/:zone_type/:zone/forecast",
+                routing::get(serve_forecast),
+            )
+            .route("/zones/:zone_type/:zone", routing::get(serve_zone_metadata))
+            .route("/alerts/active", routing::get(serve_active_alerts))
+            .route("/points/:point", routing::get(serve_point))
+            .route(
+                "/points/:point/stations",
+                routing::get(serve_point_stations),
+            )
+            .route(
+                "/gridpoints/:office/:cell/forecast/hourly",
+                routing::get(serve_hourly_forecast),
+            )
+            .with_state(self.state.clone())
+    }
+
+    /// Serves the fake NOAA API on the listener until the `shutdown` future completes.
+    pub async fn serve(
+        self, listener: TcpListener,
+        shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> std::io::Result<()> {
+        axum::serve(listener, self.router().into_make_service())
+            .with_graceful_shutdown(shutdown)
+            .await
+    }
+
+    /// Spawns the fake NOAA API on an ephemeral local port, e.g., for a test. The service stops
+    /// when the returned handle is dropped.
+    pub async fn spawn(self) -> std::io::Result<FakeNoaaHandle> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let address = listener.local_addr()?;
+        let base_url = Url::parse(&format!("http://{address}"))
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let shutdown = async move {
+            let _ = stop_rx.await;
+        };
+
+        let service = self.clone();
+        let join_handle = tokio::spawn(async move { service.serve(listener, shutdown).await });
+        info!(%base_url, "fake NOAA weather service started");
+
+        Ok(FakeNoaaHandle {
+            base_url,
+            service: self,
+            stop: Some(stop_tx),
+            join_handle,
+        })
+    }
+}
+
+pub struct FakeNoaaHandle {
+    pub base_url: Url,
+    service: FakeNoaa,
+    stop: Option<tokio::sync::oneshot::Sender<()>>,
+    join_handle: JoinHandle<std::io::Result<()>>,
+}
+
+impl fmt::Debug for FakeNoaaHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FakeNoaaHandle")
+            .field("base_url", &self.base_url)
+            .field("requests", &self.service.requests())
+            .finish()
+    }
+}
+
+impl FakeNoaaHandle {
+    pub fn service(&self) -> &FakeNoaa {
+        &self.service
+    }
+
+    pub async fn stop(mut self) -> std::io::Result<()> {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+
+        match (&mut self.join_handle).await {
+            Ok(outcome) => outcome,
+            Err(error) => Err(std::io::Error::new(std::io::ErrorKind::Other, error)),
+        }
+    }
+}
+
+impl Drop for FakeNoaaHandle {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+    }
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_observation(
+    Path((_zone_type, zone)): Path<(String, String)>, State(state): State<Arc<FakeNoaaState>>,
+) -> Response {
+    respond(
+        &state,
+        CaptureKey::zone(NoaaEndpoint::ZoneObservation, zone),
+    )
+    .await
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_forecast(
+    Path((_zone_type, zone)): Path<(String, String)>, State(state): State<Arc<FakeNoaaState>>,
+) -> Response {
+    respond(&state, CaptureKey::zone(NoaaEndpoint::ZoneForecast, zone)).await
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_zone_metadata(
+    Path((_zone_type, zone)): Path<(String, String)>, State(state): State<Arc<FakeNoaaState>>,
+) -> Response {
+    respond(&state, CaptureKey::zone(NoaaEndpoint::ZoneMetadata, zone)).await
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_active_alerts(State(state): State<Arc<FakeNoaaState>>) -> Response {
+    respond(&state, CaptureKey::active_alerts()).await
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_point(
+    Path(point): Path<String>, State(state): State<Arc<FakeNoaaState>>,
+) -> Response {
+    match point.parse() {
+        Ok(point) => respond(&state, CaptureKey::point(NoaaEndpoint::Point, &point)).await,
+        Err(error) => (StatusCode::BAD_REQUEST, error.to_string()).into_response(),
+    }
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_point_stations(
+    Path(point): Path<String>, State(state): State<Arc<FakeNoaaState>>,
+) -> Response {
+    match point.parse() {
+        Ok(point) => {
+            respond(
+                &state,
+                CaptureKey::point(NoaaEndpoint::PointStations, &point),
+            )
+            .await
+        },
+        Err(error) => (StatusCode::BAD_REQUEST, error.to_string()).into_response(),
+    }
+}
+
+#[instrument(level = "debug", skip(state))]
+async fn serve_hourly_forecast(
+    Path((office, cell)): Path<(String, String)>, State(state): State<Arc<FakeNoaaState>>,
+) -> Response {
+    match format!("{office}/{cell}").parse() {
+        Ok(gridpoint) => {
+            let key = CaptureKey::gridpoint(NoaaEndpoint::GridpointHourlyForecast, &gridpoint);
+            respond(&state, key).await
+        },
+        Err(error) => (StatusCode::BAD_REQUEST, error.to_string()).into_response(),
+    }
+}
+
+async fn respond(state: &FakeNoaaState, key: CaptureKey) -> Response {
+    let fault = state.next_fault();
+    if !state.faults.latency.is_zero() {
+        tokio::time::sleep(state.faults.latency).await;
+    }
+
+    let Some(body) = state.fixtures.fixture(&key) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("no fake NOAA fixture for {key:?}"),
+        )
+            .into_response();
+    };
+
+    debug!(?key, ?fault, "fake NOAA responding");
+    match fault {
+        Fault::None => (
+            [(header::CONTENT_TYPE, GEOJSON_CONTENT_TYPE)],
+            body.to_string(),
+        )
+            .into_response(),
+        Fault::ServerError => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "fake NOAA injected server error",
+        )
+            .into_response(),
+        Fault::TooManyRequests => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, "1")],
+            "fake NOAA injected rate limit",
+        )
+            .into_response(),
+        Fault::Malformed => {
+            let truncated: String = body.chars().take(body.len() / 2).collect();
+            ([(header::CONTENT_TYPE, GEOJSON_CONTENT_TYPE)], truncated).into_response()
+        },
+        Fault::NotFound => {
+            let problem = serde_json::json!({
+                "type": "https://api.weather.gov/problems/InvalidPoint",
+                "title": "Data Unavailable For Requested Point",
+                "status": 404,
+                "detail": format!("fake NOAA injected no data for {key:?}"),
+            });
+            (
+                StatusCode::NOT_FOUND,
+                [(header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE)],
+                problem.to_string(),
+            )
+                .into_response()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_load_fixtures() {
+        let fixtures = assert_ok!(FakeNoaaFixtures::load("./tests/data"));
+        let forecast = CaptureKey::zone(NoaaEndpoint::ZoneForecast, "waz558");
+        assert_some!(fixtures.fixture(&CaptureKey::zone(NoaaEndpoint::ZoneObservation, "WAZ558")));
+        assert_some!(fixtures.fixture(&forecast));
+        assert_some!(fixtures.fixture(&CaptureKey::active_alerts()));
+        assert_eq!(
+            fixtures.fixture(&CaptureKey::zone(NoaaEndpoint::ZoneForecast, "ORZ006")),
+            fixtures.fixture(&forecast),
+            "zone without fixture served recorded fixture"
+        );
+    }
+
+    #[test]
+    fn test_scripted_faults_precede_random_faults() {
+        let fake = FakeNoaa::new(
+            FakeNoaaFixtures::default(),
+            FaultInjection {
+                scripted: vec![Fault::ServerError, Fault::TooManyRequests],
+                server_error_rate: 1.0,
+                ..FaultInjection::default()
+            },
+        );
+
+        assert_eq!(fake.state.next_fault(), Fault::ServerError);
+        assert_eq!(fake.state.next_fault(), Fault::TooManyRequests);
+        assert_eq!(fake.state.next_fault(), Fault::ServerError);
+        assert_eq!(fake.requests(), 3);
+    }
+
+    #[test]
+    fn test_non_finite_fault_rates_inject_no_faults() {
+        let fake = FakeNoaa::new(
+            FakeNoaaFixtures::default(),
+            FaultInjection {
+                server_error_rate: f64::NAN,
+                too_many_requests_rate: f64::INFINITY,
+                malformed_rate: 2.0,
+                ..FaultInjection::default()
+            },
+        );
+
+        assert_eq!(fake.state.faults.server_error_rate, 0.0);
+        assert_eq!(fake.state.faults.too_many_requests_rate, 0.0);
+        assert_eq!(fake.state.faults.malformed_rate, 1.0);
+        assert_eq!(fake.state.next_fault(), Fault::Malformed);
+    }
+}
@@ -1 +1,2 @@
+pub mod fake_noaa;
 pub mod noaa;
@@ -0,0 +1,329 @@
+//! Capture of NOAA weather API response bodies.
+//!
+//! Captures are named like the recorded fixtures in `tests/data`, so a capture directory can be
+//! replayed or served by the fake NOAA service, and vice versa:
+//!
+//! * `geojson-<zone>-zone-observation-<sequence>.json`
+//! * `geojson-<zone>-zone-forecast-<sequence>.json`
+//! * `geojson-<zone>-zone-metadata-<sequence>.json`
+//! * `geojson-active_alerts-<sequence>.json`
+//! * `geojson-<lat>,<lon>-point-<sequence>.json`
+//! * `geojson-<lat>,<lon>-point-stations-<sequence>.json`
+//! * `geojson-<office>_<x>,<y>-gridpoint-hourly-forecast-<sequence>.json`
+//!
+//! A capture's sequence is the unix timestamp, in milliseconds, at which it was recorded.
+use crate::model::{GeoPoint, Gridpoint};
+use crate::settings::NoaaCaptureSettings;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use strum_macros::{Display, IntoStaticStr};
+
+/// NOAA weather API endpoint called by the weather services.
+#[derive(Debug, Display, IntoStaticStr, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[strum(serialize_all = "snake_case")]
+pub enum NoaaEndpoint {
+    ZoneObservation,
+    ZoneForecast,
+    ZoneMetadata,
+    ActiveAlerts,
+    Point,
+    PointStations,
+    GridpointHourlyForecast,
+}
+
+/// Identifies the captured responses of an endpoint, and for zone, point and gridpoint endpoints,
+/// the zone, point or gridpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CaptureKey {
+    pub endpoint: NoaaEndpoint,
+    subject: Option<String>,
+}
+
+impl CaptureKey {
+    pub fn zone(endpoint: NoaaEndpoint, zone: impl AsRef<str>) -> Self {
+        Self {
+            endpoint,
+            subject: Some(zone.as_ref().to_lowercase()),
+        }
+    }
+
+    pub fn point(endpoint: NoaaEndpoint, point: &GeoPoint) -> Self {
+        Self { endpoint, subject: Some(point.to_string()) }
+    }
+
+    /// Gridpoints are keyed as `<office>_<x>,<y>`, since their `<office>/<x>,<y>` form is not a
+    /// valid file name.
+    pub fn gridpoint(endpoint: NoaaEndpoint, gridpoint: &Gridpoint) -> Self {
+        let subject = format!(
+            "{}_{},{}",
+            gridpoint.office.to_lowercase(),
+            gridpoint.x,
+            gridpoint.y
+        );
+        Self { endpoint, subject: Some(subject) }
+    }
+
+    pub const fn active_alerts() -> Self {
+        Self {
+            endpoint: NoaaEndpoint::ActiveAlerts,
+            subject: None,
+        }
+    }
+
+    pub fn zone_code(&self) -> Option<&str> {
+        match self.endpoint {
+            NoaaEndpoint::ZoneObservation
+            | NoaaEndpoint::ZoneForecast
+            | NoaaEndpoint::ZoneMetadata => self.subject.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn file_name(&self, sequence: u64) -> String {
+        match (self.endpoint, self.subject.as_deref()) {
+            (NoaaEndpoint::ZoneObservation, Some(zone)) => {
+                format!("geojson-{zone}-zone-observation-{sequence}.json")
+            },
+            (NoaaEndpoint::ZoneForecast, Some(zone)) => {
+                format!("geojson-{zone}-zone-forecast-{sequence}.json")
+            },
+            (NoaaEndpoint::ZoneMetadata, Some(zone)) => {
+                format!("geojson-{zone}-zone-metadata-{sequence}.json")
+            },
+            (NoaaEndpoint::GridpointHourlyForecast, Some(gridpoint)) => {
+                format!("geojson-{gridpoint}-gridpoint-hourly-forecast-{sequence}.json")
+            },
+            (NoaaEndpoint::Point, Some(point)) => {
+                format!("geojson-{point}-point-{sequence}.json")
+            },
+            (NoaaEndpoint::PointStations, Some(point)) => {
+                format!("geojson-{point}-point-stations-{sequence}.json")
+            },
+            (endpoint, _) => format!("geojson-{endpoint}-{sequence}.json"),
+        }
+    }
+
+    /// Parses the key and sequence of a capture file name, or `None` if it does not name a
+    /// capture.
+    pub fn parse_file_name(file_name: &str) -> Option<(Self, u64)> {
+        let stem = file_name.strip_prefix("geojson-")?.strip_suffix(".json")?;
+        let (name, sequence) = stem.rsplit_once('-')?;
+        let sequence = sequence.parse().ok()?;
+
+        let key = if name == <&'static str>::from(NoaaEndpoint::ActiveAlerts) {
+            Self::active_alerts()
+        } else if let Some(zone) = name.strip_suffix("-zone-observation") {
+            Self::zone(NoaaEndpoint::ZoneObservation, zone)
+        } else if let Some(zone) = name.strip_suffix("-zone-metadata") {
+            Self::zone(NoaaEndpoint::ZoneMetadata, zone)
+        } else if let Some(gridpoint) = name.strip_suffix("-gridpoint-hourly-forecast") {
+            let gridpoint = gridpoint.replacen('_', "/", 1).parse().ok()?;
+            Self::gridpoint(NoaaEndpoint::GridpointHourlyForecast, &gridpoint)
+        } else if let Some(point) = name.strip_suffix("-point-stations") {
+            Self::point(NoaaEndpoint::PointStations, &point.parse().ok()?)
+        } else if let Some(point) = name.strip_suffix("-point") {
+            Self::point(NoaaEndpoint::Point, &point.parse().ok()?)
+        } else {
+            let zone = name.strip_suffix("-zone-forecast")?;
+            Self::zone(NoaaEndpoint::ZoneForecast, zone)
+        };
+
+        Some((key, sequence))
+    }
+}
+
+/// Lists the captures in the directory, ordered by sequence.
+pub fn list_captures(dir: impl AsRef<Path>) -> std::io::Result<Vec<(CaptureKey, u64, PathBuf)>> {
+    let mut captures = vec![];
+    for entry in std::fs::read_dir(dir.as_ref())? {
+        let path = entry?.path();
+        let parsed = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(CaptureKey::parse_file_name);
+        if let Some((key, sequence)) = parsed {
+            captures.push((key, sequence, path));
+        }
+    }
+
+    captures.sort_by(|lhs, rhs| (lhs.1, &lhs.2).cmp(&(rhs.1, &rhs.2)));
+    Ok(captures)
+}
+
+/// Records NOAA response bodies into the capture directory, removing the oldest captures to keep
+/// the directory under its size cap.
+#[derive(Debug, Clone)]
+pub struct NoaaCapture {
+    inner: Arc<Mutex<CaptureDirectory>>,
+}
+
+#[derive(Debug)]
+struct CaptureDirectory {
+    dir: PathBuf,
+    max_bytes: u64,
+    total_bytes: u64,
+    last_sequence: u64,
+    captures: VecDeque<(PathBuf, u64)>,
+}
+
+impl NoaaCapture {
+    #[instrument(level = "debug", err)]
+    pub fn new(settings: &NoaaCaptureSettings) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&settings.dir)?;
+
+        let mut captures = VecDeque::new();
+        let mut total_bytes = 0;
+        let mut last_sequence = 0;
+        for (_, sequence, path) in list_captures(&settings.dir)? {
+            let size = std::fs::metadata(&path)?.len();
+            total_bytes += size;
+            last_sequence = last_sequence.max(sequence);
+            captures.push_back((path, size));
+        }
+
+        info!(
+            capture_dir=?settings.dir, nr_captures=%captures.len(), %total_bytes,
+            "capturing NOAA weather API responses"
+        );
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(CaptureDirectory {
+                dir: settings.dir.clone(),
+                max_bytes: settings.max_bytes,
+                total_bytes,
+                last_sequence,
+                captures,
+            })),
+        })
+    }
+
+    /// Records the response body, returning the capture's path or `None` if the body alone
+    /// exceeds the size cap.
+    #[instrument(level = "debug", skip(self, body), fields(body_len=%body.len()), err)]
+    pub fn record(&self, key: &CaptureKey, body: &str) -> std::io::Result<Option<PathBuf>> {
+        let mut capture_dir = self
+            .inner
+            .lock()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))?;
+
+        let size = body.len() as u64;
+        if capture_dir.max_bytes < size {
+            warn!(
+                ?key, %size, max_bytes=%capture_dir.max_bytes,
+                "NOAA response exceeds capture size cap - not captured"
+            );
+            return Ok(None);
+        }
+
+        capture_dir.make_room_for(size)?;
+
+        let now = iso8601_timestamp::Timestamp::now_utc()
+            .duration_since(iso8601_timestamp::Timestamp::UNIX_EPOCH)
+            .whole_milliseconds();
+        let sequence = u64::try_from(now).unwrap_or_default().max(capture_dir.last_sequence + 1);
+        let path = capture_dir.dir.join(key.file_name(sequence));
+        std::fs::write(&path, body)?;
+
+        capture_dir.last_sequence = sequence;
+        capture_dir.total_bytes += size;
+        capture_dir.captures.push_back((path.clone(), size));
+        debug!(?path, "captured NOAA weather API response");
+        Ok(Some(path))
+    }
+}
+
+impl CaptureDirectory {
+    fn make_room_for(&mut self, size: u64) -> std::io::Result<()> {
+        while self.max_bytes < self.total_bytes + size {
+            let Some((oldest, oldest_size)) = self.captures.pop_front() else {
+                break;
+            };
+
+            match std::fs::remove_file(&oldest) {
+                Ok(()) => debug!(
+                    ?oldest,
+                    "removed oldest NOAA capture to stay under size cap"
+                ),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {},
+                Err(error) => return Err(error),
+            }
+            self.total_bytes = self.total_bytes.saturating_sub(oldest_size);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_capture_file_name_roundtrip() {
+        let keys = [
+            CaptureKey::zone(NoaaEndpoint::ZoneObservation, "WAZ558"),
+            CaptureKey::zone(NoaaEndpoint::ZoneForecast, "waz558"),
+            CaptureKey::zone(NoaaEndpoint::ZoneMetadata, "WAZ558"),
+            CaptureKey::active_alerts(),
+            CaptureKey::point(NoaaEndpoint::Point, &assert_ok!("39.7456,-97.0892".parse())),
+            CaptureKey::point(
+                NoaaEndpoint::PointStations,
+                &assert_ok!("47.61,-122.3".parse()),
+            ),
+            CaptureKey::gridpoint(
+                NoaaEndpoint::GridpointHourlyForecast,
+                &assert_ok!("SEW/126,63".parse()),
+            ),
+        ];
+
+        for key in keys {
+            let file_name = key.file_name(1_710_000_000_123);
+            assert_eq!(
+                CaptureKey::parse_file_name(&file_name),
+                Some((key, 1_710_000_000_123))
+            );
+        }
+
+        assert_eq!(
+            CaptureKey::parse_file_name("geojson-waz558-zone-observation-1.json"),
+            Some((CaptureKey::zone(NoaaEndpoint::ZoneObservation, "waz558"), 1))
+        );
+        assert_eq!(
+            CaptureKey::parse_file_name("geojson-active_alerts-1.json"),
+            Some((CaptureKey::active_alerts(), 1))
+        );
+        assert_none!(CaptureKey::parse_file_name("application.yaml"));
+        assert_none!(CaptureKey::parse_file_name("geojson-waz558-zone-1.json"));
+        assert_none!(CaptureKey::parse_file_name(
+            "geojson-active_alerts-latest.json"
+        ));
+    }
+
+    #[test]
+    fn test_capture_size_cap_removes_oldest() {
+        let dir = std::env::temp_dir().join(format!("noaa-capture-{}", cuid2::create_id()));
+        let settings = NoaaCaptureSettings { dir: dir.clone(), max_bytes: 10 };
+        let capture = assert_ok!(NoaaCapture::new(&settings));
+        let key = CaptureKey::zone(NoaaEndpoint::ZoneForecast, "WAZ558");
+
+        let first = assert_some!(assert_ok!(capture.record(&key, "123456")));
+        let second = assert_some!(assert_ok!(capture.record(&key, "7890")));
+        assert_none!(assert_ok!(capture.record(&key, "this body exceeds the cap")));
+        let third = assert_some!(assert_ok!(capture.record(&key, "abc")));
+
+        assert!(!first.exists(), "oldest capture removed to stay under cap");
+        let captures: Vec<_> = assert_ok!(list_captures(&dir))
+            .into_iter()
+            .map(|(_, _, path)| path)
+            .collect();
+        assert_eq!(captures, vec![second, third]);
+
+        let reopened = assert_ok!(NoaaCapture::new(&settings));
+        assert_eq!(assert_ok!(reopened.inner.lock()).total_bytes, 7);
+
+        assert_ok!(std::fs::remove_dir_all(dir));
+    }
+}
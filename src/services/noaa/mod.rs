@@ -0,0 +1,698 @@
+mod capture;
+mod points;
+mod replay;
+
+pub use capture::{list_captures, CaptureKey, NoaaCapture, NoaaEndpoint};
+pub use errors::NoaaWeatherError;
+pub use points::PointResolver;
+pub use replay::ReplayWeatherServices;
+
+use crate::model::zone_metadata::ZoneMetadata;
+use crate::model::{
+    transpose_result, GeoPoint, Gridpoint, HourlyForecast, LocationZone, LocationZoneCode,
+    LocationZoneType, ObservationReport, PointLocation, WeatherAlert, ZoneForecast,
+};
+use crate::settings::{AggregationSettings, NoaaSettings};
+use geojson::{Feature, FeatureCollection, GeoJson};
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest_middleware::ClientWithMiddleware;
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::RetryTransientMiddleware;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use url::Url;
+
+pub trait ZoneWeatherApi: Send + Sync {
+    async fn zone_observation(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ObservationReport, NoaaWeatherError>;
+
+    async fn zone_forecast(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneForecast, NoaaWeatherError>;
+}
+
+pub trait ZoneMetadataApi: Send + Sync {
+    /// The zone's name, state, forecast offices, time zone and boundary.
+    async fn zone_metadata(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneMetadata, NoaaWeatherError>;
+}
+
+pub trait GridpointForecastApi: Send + Sync {
+    /// The gridpoint representative of the zone: the gridpoint covering the centroid of the zone's
+    /// largest polygon.
+    async fn zone_gridpoint(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<Gridpoint, NoaaWeatherError>;
+
+    /// The gridpoint covering the point.
+    async fn point_gridpoint(&self, point: &GeoPoint) -> Result<Gridpoint, NoaaWeatherError>;
+
+    async fn hourly_forecast(
+        &self, gridpoint: &Gridpoint,
+    ) -> Result<HourlyForecast, NoaaWeatherError>;
+}
+
+pub trait AlertApi: Send + Sync {
+    async fn active_alerts(&self) -> Result<Vec<WeatherAlert>, NoaaWeatherError>;
+}
+
+pub trait PointApi: Send + Sync {
+    /// Resolves the point to the zones and gridpoint covering it and its observation stations,
+    /// nearest first.
+    async fn point_location(&self, point: &GeoPoint) -> Result<PointLocation, NoaaWeatherError>;
+}
+
+#[derive(Debug, Clone)]
+pub enum NoaaWeatherServices {
+    Noaa(NoaaWeatherApi),
+    HappyPath(HappyPathWeatherServices),
+    Replay(ReplayWeatherServices),
+}
+
+impl ZoneWeatherApi for NoaaWeatherServices {
+    async fn zone_observation(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ObservationReport, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.zone_observation(zone_type, zone).await,
+            Self::HappyPath(svc) => svc.zone_observation(zone_type, zone).await,
+            Self::Replay(svc) => svc.zone_observation(zone_type, zone).await,
+        }
+    }
+
+    async fn zone_forecast(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneForecast, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.zone_forecast(zone_type, zone).await,
+            Self::HappyPath(svc) => svc.zone_forecast(zone_type, zone).await,
+            Self::Replay(svc) => svc.zone_forecast(zone_type, zone).await,
+        }
+    }
+}
+
+impl ZoneMetadataApi for NoaaWeatherServices {
+    async fn zone_metadata(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneMetadata, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.zone_metadata(zone_type, zone).await,
+            Self::HappyPath(svc) => svc.zone_metadata(zone_type, zone).await,
+            Self::Replay(svc) => svc.zone_metadata(zone_type, zone).await,
+        }
+    }
+}
+
+impl GridpointForecastApi for NoaaWeatherServices {
+    async fn zone_gridpoint(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<Gridpoint, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.zone_gridpoint(zone_type, zone).await,
+            Self::HappyPath(svc) => svc.zone_gridpoint(zone_type, zone).await,
+            Self::Replay(svc) => svc.zone_gridpoint(zone_type, zone).await,
+        }
+    }
+
+    async fn point_gridpoint(&self, point: &GeoPoint) -> Result<Gridpoint, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.point_gridpoint(point).await,
+            Self::HappyPath(svc) => svc.point_gridpoint(point).await,
+            Self::Replay(svc) => svc.point_gridpoint(point).await,
+        }
+    }
+
+    async fn hourly_forecast(
+        &self, gridpoint: &Gridpoint,
+    ) -> Result<HourlyForecast, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.hourly_forecast(gridpoint).await,
+            Self::HappyPath(svc) => svc.hourly_forecast(gridpoint).await,
+            Self::Replay(svc) => svc.hourly_forecast(gridpoint).await,
+        }
+    }
+}
+
+impl AlertApi for NoaaWeatherServices {
+    async fn active_alerts(&self) -> Result<Vec<WeatherAlert>, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.active_alerts().await,
+            Self::HappyPath(svc) => svc.active_alerts().await,
+            Self::Replay(svc) => svc.active_alerts().await,
+        }
+    }
+}
+
+impl PointApi for NoaaWeatherServices {
+    async fn point_location(&self, point: &GeoPoint) -> Result<PointLocation, NoaaWeatherError> {
+        match self {
+            Self::Noaa(svc) => svc.point_location(point).await,
+            Self::HappyPath(svc) => svc.point_location(point).await,
+            Self::Replay(svc) => svc.point_location(point).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NoaaWeatherApi {
+    client: ClientWithMiddleware,
+    base_url: Url,
+    capture: Option<NoaaCapture>,
+    aggregation: Arc<AggregationSettings>,
+}
+
+impl NoaaWeatherApi {
+    pub fn new(settings: &NoaaSettings) -> Result<Self, NoaaWeatherError> {
+        let base_url = settings.base_url.clone();
+        if base_url.cannot_be_a_base() {
+            return Err(NoaaWeatherError::NotABaseUrl(base_url));
+        }
+
+        let client = Self::make_http_client(settings)?;
+        let capture = settings.capture.as_ref().map(NoaaCapture::new).transpose()?;
+        Ok(Self {
+            client,
+            base_url,
+            capture,
+            aggregation: Arc::new(AggregationSettings::default()),
+        })
+    }
+
+    pub fn with_aggregation(self, aggregation: AggregationSettings) -> Self {
+        Self { aggregation: Arc::new(aggregation), ..self }
+    }
+
+    fn make_http_client(settings: &NoaaSettings) -> Result<ClientWithMiddleware, NoaaWeatherError> {
+        let user_agent = HeaderValue::from_str(&settings.user_agent)?;
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, user_agent);
+
+        let client = reqwest::Client::builder()
+            .pool_idle_timeout(settings.pool_idle_timeout)
+            .default_headers(headers)
+            .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+            .timeout(settings.timeout)
+            .build()?;
+
+        let retry_policy = ExponentialBackoff::builder()
+            .retry_bounds(settings.retry.min_backoff, settings.retry.max_backoff)
+            .build_with_max_retries(settings.retry.max_retries);
+
+        Ok(reqwest_middleware::ClientBuilder::new(client)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build())
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn fetch_geojson(&self, key: CaptureKey, url: Url) -> Result<GeoJson, NoaaWeatherError> {
+        let label: &'static str = key.endpoint.into();
+        let response = self.client.get(url.clone()).send().await?;
+        log_response(label, &url, &response);
+
+        let status_code = response.status();
+        let body = response.text().await?;
+
+        // -- error responses are problem+json documents rather than GeoJSON and are not captured
+        if status_code == reqwest::StatusCode::NOT_FOUND {
+            return Err(NoaaWeatherError::NotFound { url, detail: problem_detail(&body) });
+        } else if !status_code.is_success() {
+            let detail = problem_detail(&body);
+            return Err(NoaaWeatherError::HttpStatus { status: status_code, url, detail });
+        }
+
+        if let Some(capture) = self.capture.as_ref() {
+            match capture.record(&key, &body) {
+                Ok(Some(path)) => {
+                    debug!(?status_code, %url, "captured {label} response body to: {path:?}")
+                },
+                Ok(None) => {},
+                Err(error) => warn!(?error, %url, "failed to capture {label} response body"),
+            }
+        }
+
+        let geojson = body.parse()?;
+        Ok(geojson)
+    }
+}
+
+impl ZoneWeatherApi for NoaaWeatherApi {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_observation(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ObservationReport, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("zones")
+            .push(zone_type.into())
+            .push(zone.as_ref())
+            .push("observations");
+
+        let key = CaptureKey::zone(NoaaEndpoint::ZoneObservation, zone);
+        observation_from_geojson(self.fetch_geojson(key, url).await?, &self.aggregation)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_forecast(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneForecast, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("zones")
+            .push(zone_type.into())
+            .push(zone.as_ref())
+            .push("forecast");
+
+        let key = CaptureKey::zone(NoaaEndpoint::ZoneForecast, zone);
+        forecast_from_geojson(self.fetch_geojson(key, url).await?)
+    }
+}
+
+impl ZoneMetadataApi for NoaaWeatherApi {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_metadata(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneMetadata, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("zones")
+            .push(zone_type.into())
+            .push(zone.as_ref());
+
+        let key = CaptureKey::zone(NoaaEndpoint::ZoneMetadata, zone);
+        let zone = LocationZone::new(zone.clone(), zone_type);
+        zone_metadata_from_geojson(&zone, self.fetch_geojson(key, url).await?)
+    }
+}
+
+impl GridpointForecastApi for NoaaWeatherApi {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_gridpoint(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<Gridpoint, NoaaWeatherError> {
+        let centroid = zone_centroid(&self.zone_metadata(zone_type, zone).await?)?;
+        self.point_gridpoint(&centroid).await
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn point_gridpoint(&self, point: &GeoPoint) -> Result<Gridpoint, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().unwrap().push("points").push(&point.to_string());
+        let key = CaptureKey::point(NoaaEndpoint::Point, point);
+        gridpoint_from_geojson(self.fetch_geojson(key, url).await?)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn hourly_forecast(
+        &self, gridpoint: &Gridpoint,
+    ) -> Result<HourlyForecast, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .unwrap()
+            .push("gridpoints")
+            .push(&gridpoint.office)
+            .push(&format!("{},{}", gridpoint.x, gridpoint.y))
+            .push("forecast")
+            .push("hourly");
+
+        let key = CaptureKey::gridpoint(NoaaEndpoint::GridpointHourlyForecast, gridpoint);
+        hourly_forecast_from_geojson(gridpoint, self.fetch_geojson(key, url).await?)
+    }
+}
+
+impl AlertApi for NoaaWeatherApi {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn active_alerts(&self) -> Result<Vec<WeatherAlert>, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().unwrap().push("alerts").push("active");
+
+        alerts_from_geojson(self.fetch_geojson(CaptureKey::active_alerts(), url).await?)
+    }
+}
+
+impl PointApi for NoaaWeatherApi {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn point_location(&self, point: &GeoPoint) -> Result<PointLocation, NoaaWeatherError> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().unwrap().push("points").push(&point.to_string());
+        let key = CaptureKey::point(NoaaEndpoint::Point, point);
+        let point_geojson = self.fetch_geojson(key, url.clone()).await?;
+
+        url.path_segments_mut().unwrap().push("stations");
+        let key = CaptureKey::point(NoaaEndpoint::PointStations, point);
+        let stations_geojson = self.fetch_geojson(key, url).await?;
+
+        point_location_from_geojson(point, point_geojson, stations_geojson)
+    }
+}
+
+fn observation_from_geojson(
+    geojson: GeoJson, aggregation: &AggregationSettings,
+) -> Result<ObservationReport, NoaaWeatherError> {
+    let features = FeatureCollection::try_from(geojson)?;
+    Ok(ObservationReport::from_features(
+        &features.features,
+        aggregation,
+    ))
+}
+
+fn forecast_from_geojson(geojson: GeoJson) -> Result<ZoneForecast, NoaaWeatherError> {
+    let feature = Feature::try_from(geojson)?;
+    Ok(ZoneForecast::try_from(feature)?)
+}
+
+fn zone_metadata_from_geojson(
+    zone: &LocationZone, geojson: GeoJson,
+) -> Result<ZoneMetadata, NoaaWeatherError> {
+    let feature = Feature::try_from(geojson)?;
+    Ok(ZoneMetadata::from_feature(
+        zone,
+        feature,
+        chrono::Utc::now(),
+    )?)
+}
+
+fn zone_centroid(metadata: &ZoneMetadata) -> Result<GeoPoint, NoaaWeatherError> {
+    let centroid = metadata.geometry.as_ref().and_then(GeoPoint::centroid_of);
+    centroid.ok_or_else(|| {
+        crate::errors::WeatherError::MissingFeature("zone polygon geometry".to_string()).into()
+    })
+}
+
+fn gridpoint_from_geojson(geojson: GeoJson) -> Result<Gridpoint, NoaaWeatherError> {
+    let feature = Feature::try_from(geojson)?;
+    Ok(Gridpoint::from_point_feature(&feature)?)
+}
+
+fn hourly_forecast_from_geojson(
+    gridpoint: &Gridpoint, geojson: GeoJson,
+) -> Result<HourlyForecast, NoaaWeatherError> {
+    let feature = Feature::try_from(geojson)?;
+    Ok(HourlyForecast::from_feature(gridpoint.clone(), feature)?)
+}
+
+fn point_location_from_geojson(
+    point: &GeoPoint, point_geojson: GeoJson, stations_geojson: GeoJson,
+) -> Result<PointLocation, NoaaWeatherError> {
+    let point_feature = Feature::try_from(point_geojson)?;
+    let stations = FeatureCollection::try_from(stations_geojson)?;
+    Ok(PointLocation::from_geojson(
+        *point,
+        &point_feature,
+        &stations,
+    )?)
+}
+
+fn alerts_from_geojson(geojson: GeoJson) -> Result<Vec<WeatherAlert>, NoaaWeatherError> {
+    let features = FeatureCollection::try_from(geojson)?;
+    let alerts = features.features.into_iter().map(WeatherAlert::try_from);
+    transpose_result(alerts).map_err(|error| error.into())
+}
+
+mod errors {
+    use thiserror::Error;
+    use url::Url;
+
+    #[derive(Debug, Error)]
+    pub enum NoaaWeatherError {
+        #[error("supplied Weather API url is not a base url to query: {0}")]
+        NotABaseUrl(Url),
+
+        #[error("invalid Weather API user agent: {0}")]
+        UserAgent(#[from] reqwest::header::InvalidHeaderValue),
+
+        #[error("Weather API call failed: {0}")]
+        HttpRequest(#[from] reqwest::Error),
+
+        #[error("error occurred in HTTP middleware calling Weather API: {0}")]
+        HttpMiddleware(#[from] reqwest_middleware::Error),
+
+        #[error("Weather API has no data for {url}: {detail}")]
+        NotFound { url: Url, detail: String },
+
+        #[error("Weather API responded {status} for {url}: {detail}")]
+        HttpStatus {
+            status: reqwest::StatusCode,
+            url: Url,
+            detail: String,
+        },
+
+        #[error("failed to parse Weather API GeoJson response: {0}")]
+        GeoJson(#[from] geojson::Error),
+
+        #[error("{0}")]
+        Weather(#[from] crate::errors::WeatherError),
+
+        #[error("failed to capture or replay Weather API responses: {0}")]
+        Capture(#[from] std::io::Error),
+
+        #[error("no captured Weather API response to replay for {0:?}")]
+        MissingCapture(super::CaptureKey),
+    }
+}
+
+/// The `detail` of a NOAA problem+json error response, or else the response body.
+fn problem_detail(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|problem| {
+            let detail = problem.get("detail").or_else(|| problem.get("title"))?;
+            detail.as_str().map(|d| d.to_string())
+        })
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+fn log_response(label: &str, endpoint: &Url, response: &reqwest::Response) {
+    const MESSAGE: &str = "response recv from services.gov";
+    let status = response.status();
+    if status.is_success() || status.is_informational() {
+        debug!(?endpoint, ?response, "{label}: {MESSAGE}");
+    } else if status.is_client_error() {
+        warn!(?endpoint, ?response, "{label}: {MESSAGE}");
+    } else {
+        warn!(?endpoint, ?response, "{label}: {MESSAGE}");
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct HappyPathWeatherServices;
+
+impl ZoneWeatherApi for HappyPathWeatherServices {
+    async fn zone_observation(
+        &self, _zone_type: LocationZoneType, _zone: &LocationZoneCode,
+    ) -> Result<ObservationReport, NoaaWeatherError> {
+        use crate::model;
+
+        let weather = model::WeatherFrame {
+            timestamp: iso8601_timestamp::Timestamp::now_utc(),
+            temperature: Some(model::QuantitativeValue {
+                value: dec!(72.0),
+                max_value: dec!(80.0),
+                min_value: dec!(60.0),
+                unit_code: model::Unit::DegreesFahrenheit.code().into(),
+                quality_control: model::QualityControl::V,
+            }),
+            dewpoint: Some(model::QuantitativeValue {
+                value: dec!(33.2),
+                max_value: dec!(36.3),
+                min_value: dec!(26.2),
+                unit_code: model::Unit::DegreesFahrenheit.code().into(),
+                quality_control: model::QualityControl::C,
+            }),
+            wind_direction: None,
+            wind_direction_variable: false,
+            wind_speed: None,
+            wind_gust: None,
+            barometric_pressure: None,
+            sea_level_pressure: None,
+            visibility: None,
+            max_temperature_last_24_hours: None,
+            min_temperature_last_24_hours: None,
+            precipitation_last_hour: None,
+            precipitation_last_3_hours: None,
+            precipitation_last_6_hours: None,
+            relative_humidity: None,
+            wind_chill: None,
+            heat_index: None,
+        };
+
+        Ok(ObservationReport {
+            weather: weather.clone(),
+            stations: vec![model::StationObservation {
+                station_id: "KBWI".to_string(),
+                name: Some("Baltimore-Washington International Airport".to_string()),
+                location: Some(model::StationLocation {
+                    longitude: dec!(-76.68408),
+                    latitude: dec!(39.17329),
+                }),
+                weather,
+            }],
+        })
+    }
+
+    async fn zone_forecast(
+        &self, _zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneForecast, NoaaWeatherError> {
+        use crate::model::{
+            CompassPoint, ForecastDetail, TemperatureUnit, WindSpeed, WindSpeedUnit,
+        };
+
+        let now = chrono::Utc::now();
+        Ok(ZoneForecast {
+            zone_code: zone.to_string(),
+            updated: now,
+            periods: vec![ForecastDetail {
+                number: Some(1),
+                name: "Rest of Day".to_string(),
+                start_time: Some(now),
+                end_time: Some(now + chrono::Duration::hours(6)),
+                is_daytime: Some(true),
+                temperature: Some(74),
+                temperature_unit: Some(TemperatureUnit::F),
+                wind_speed: Some(WindSpeed { low: 0, high: 5, unit: WindSpeedUnit::MilesPerHour }),
+                wind_direction: Some(CompassPoint::SW),
+                short_forecast: Some("Mostly Cloudy".to_string()),
+                forecast: "Mostly cloudy. Highs in the lower to mid 70s. Light wind.".to_string(),
+                ..ForecastDetail::default()
+            }],
+        })
+    }
+}
+
+impl ZoneMetadataApi for HappyPathWeatherServices {
+    async fn zone_metadata(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneMetadata, NoaaWeatherError> {
+        let boundary = vec![vec![
+            vec![-122.45, 47.45],
+            vec![-122.2, 47.45],
+            vec![-122.2, 47.75],
+            vec![-122.45, 47.75],
+            vec![-122.45, 47.45],
+        ]];
+
+        Ok(ZoneMetadata {
+            zone: zone.clone(),
+            zone_type,
+            name: "Seattle and Vicinity".to_string(),
+            state: Some("WA".to_string()),
+            cwa: vec!["SEW".to_string()],
+            time_zone: Some("America/Los_Angeles".to_string()),
+            geometry: Some(geojson::Geometry::new(geojson::Value::Polygon(boundary))),
+            fetched_at: chrono::Utc::now(),
+        })
+    }
+}
+
+impl GridpointForecastApi for HappyPathWeatherServices {
+    async fn zone_gridpoint(
+        &self, _zone_type: LocationZoneType, _zone: &LocationZoneCode,
+    ) -> Result<Gridpoint, NoaaWeatherError> {
+        Ok(Gridpoint { office: "SEW".to_string(), x: 125, y: 68 })
+    }
+
+    async fn point_gridpoint(&self, _point: &GeoPoint) -> Result<Gridpoint, NoaaWeatherError> {
+        Ok(Gridpoint { office: "SEW".to_string(), x: 125, y: 68 })
+    }
+
+    async fn hourly_forecast(
+        &self, gridpoint: &Gridpoint,
+    ) -> Result<HourlyForecast, NoaaWeatherError> {
+        use crate::model::{
+            CompassPoint, HourlyForecastPeriod, TemperatureUnit, WindSpeed, WindSpeedUnit,
+        };
+        use chrono::{DurationRound, Utc};
+
+        let now = Utc::now();
+        let hour = now.duration_trunc(chrono::Duration::hours(1)).unwrap_or(now);
+        let periods = (0..3)
+            .map(|i| HourlyForecastPeriod {
+                start_time: hour + chrono::Duration::hours(i),
+                end_time: hour + chrono::Duration::hours(i + 1),
+                is_daytime: true,
+                temperature: 72 + i as i32,
+                temperature_unit: TemperatureUnit::F,
+                probability_of_precipitation: None,
+                dewpoint: None,
+                relative_humidity: None,
+                wind_speed: Some(WindSpeed { low: 5, high: 5, unit: WindSpeedUnit::MilesPerHour }),
+                wind_direction: Some(CompassPoint::SW),
+                icon: None,
+                short_forecast: Some("Mostly Cloudy".to_string()),
+            })
+            .collect();
+
+        Ok(HourlyForecast {
+            gridpoint: gridpoint.clone(),
+            updated: now,
+            periods,
+        })
+    }
+}
+
+impl PointApi for HappyPathWeatherServices {
+    async fn point_location(&self, point: &GeoPoint) -> Result<PointLocation, NoaaWeatherError> {
+        use crate::model;
+
+        Ok(PointLocation {
+            point: *point,
+            forecast_zone: LocationZoneCode::new("WAZ558"),
+            county_zone: Some(LocationZoneCode::new("WAC033")),
+            gridpoint: model::Gridpoint { office: "SEW".to_string(), x: 125, y: 68 },
+            time_zone: Some("America/Los_Angeles".to_string()),
+            stations: vec![model::NearbyStation {
+                station_id: "KBFI".to_string(),
+                name: Some("Seattle, Boeing Field".to_string()),
+                location: Some(model::StationLocation {
+                    longitude: dec!(-122.31442),
+                    latitude: dec!(47.53),
+                }),
+            }],
+        })
+    }
+}
+
+impl AlertApi for HappyPathWeatherServices {
+    async fn active_alerts(&self) -> Result<Vec<WeatherAlert>, NoaaWeatherError> {
+        use crate::model;
+        use chrono::Utc;
+        use trim_margin::MarginTrimmable;
+
+        Ok(vec![
+            WeatherAlert {
+                id: "urn:oid:2.49.0.1.840.0.happy-path.001.1".to_string(),
+                affected_zones: vec![
+                    LocationZoneCode::new("MDC031".to_string())
+                ],
+                status: model::AlertStatus::Actual,
+                message_type: model::AlertMessageType::Alert,
+                sent: Utc::now() - chrono::Duration::hours(1),
+                effective: Utc::now() - chrono::Duration::minutes(55),
+                onset: Some(Utc::now() - chrono::Duration::minutes(30)),
+                expires: Utc::now() + chrono::Duration::minutes(55),
+                ends: Some(Utc::now() + chrono::Duration::hours(1)),
+                category: model::AlertCategory::Met,
+                severity: model::AlertSeverity::Severe,
+                certainty: model::AlertCertainty::Possible,
+                urgency: model::AlertUrgency::Immediate,
+                event: "High Wind Watch".to_string(),
+                headline: Some("High Wind Watch issued".to_string()),
+                description: r##"* WHAT...South winds 30 to 40 mph with gusts up to 50 mph possible.
+                    |* WHERE...Portions of southeast Louisiana and southeast and southern Mississippi.
+                    |* WHEN...From Tuesday afternoon through late Tuesday night.
+                    |* IMPACTS...Damaging winds could blow down trees and power lines.
+                    |Widespread power outages are possible.
+                    |Travel could be difficult, especially for high profile vehicles.
+                    |"##.trim_margin().unwrap(),
+                instruction: r##"Monitor the latest forecasts and warnings for updates on this
+                    |situation. Fasten loose objects or shelter objects in a safe location prior
+                    |to the onset of winds."##.trim_margin(),
+                response: model::AlertResponse::Prepare,
+            }
+        ])
+    }
+}
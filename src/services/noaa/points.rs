@@ -0,0 +1,140 @@
+use super::{NoaaWeatherError, NoaaWeatherServices, PointApi};
+use crate::model::{GeoPoint, PointLocation};
+use crate::settings::NoaaPointsSettings;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Resolves latitude/longitude points to their NOAA zones, gridpoint and nearest stations. A
+/// point's resolution rarely changes, so resolutions are cached for the configured time to live.
+#[derive(Debug, Clone)]
+pub struct PointResolver {
+    noaa: NoaaWeatherServices,
+    max_stations: usize,
+    cache: Arc<Mutex<PointCache>>,
+}
+
+impl PointResolver {
+    pub fn new(noaa: NoaaWeatherServices, settings: &NoaaPointsSettings) -> Self {
+        Self {
+            noaa,
+            max_stations: settings.max_stations,
+            cache: Arc::new(Mutex::new(PointCache::new(
+                settings.cache_ttl,
+                settings.cache_capacity,
+            ))),
+        }
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    pub async fn resolve(&self, point: &GeoPoint) -> Result<PointLocation, NoaaWeatherError> {
+        if let Some(location) = self.cache().get(point, Instant::now()) {
+            debug!(%point, "point resolution cache hit");
+            return Ok(location);
+        }
+
+        let mut location = self.noaa.point_location(point).await?;
+        location.stations.truncate(self.max_stations);
+        self.cache().insert(location.clone(), Instant::now());
+        Ok(location)
+    }
+
+    fn cache(&self) -> MutexGuard<'_, PointCache> {
+        // -- the cache is consistent between calls, so a panic while holding it leaves it usable
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[derive(Debug)]
+struct PointCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<GeoPoint, (PointLocation, Instant)>,
+}
+
+impl PointCache {
+    fn new(ttl: Duration, capacity: usize) -> Self {
+        Self { ttl, capacity, entries: HashMap::new() }
+    }
+
+    fn get(&self, point: &GeoPoint, now: Instant) -> Option<PointLocation> {
+        self.entries
+            .get(point)
+            .filter(|(_, resolved_at)| now.saturating_duration_since(*resolved_at) < self.ttl)
+            .map(|(location, _)| location.clone())
+    }
+
+    fn insert(&mut self, location: PointLocation, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, (_, resolved_at)| now.saturating_duration_since(*resolved_at) < ttl);
+
+        if !self.entries.contains_key(&location.point) && self.capacity <= self.entries.len() {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, resolved_at))| *resolved_at)
+                .map(|(point, _)| *point);
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.entries.insert(location.point, (location, now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::noaa::HappyPathWeatherServices;
+    use claims::{assert_none, assert_ok, assert_some};
+    use pretty_assertions::assert_eq;
+
+    fn location(point: &str) -> PointLocation {
+        let point = assert_ok!(point.parse());
+        assert_ok!(tokio_test::block_on(
+            HappyPathWeatherServices.point_location(&point)
+        ))
+    }
+
+    #[test]
+    fn test_point_cache_expires_and_evicts_oldest() {
+        let start = Instant::now();
+        let mut cache = PointCache::new(Duration::from_secs(60), 2);
+        let (seattle, tacoma, everett) = (
+            location("47.6062,-122.3321"),
+            location("47.2529,-122.4443"),
+            location("47.979,-122.2"),
+        );
+
+        cache.insert(seattle.clone(), start);
+        cache.insert(tacoma.clone(), start + Duration::from_secs(10));
+        assert_eq!(assert_some!(cache.get(&seattle.point, start)), seattle);
+        assert_none!(cache.get(&seattle.point, start + Duration::from_secs(60)));
+
+        cache.insert(everett.clone(), start + Duration::from_secs(20));
+        assert_none!(cache.get(&seattle.point, start + Duration::from_secs(20)));
+        assert_some!(cache.get(&tacoma.point, start + Duration::from_secs(20)));
+        assert_some!(cache.get(&everett.point, start + Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_resolver_keeps_nearest_stations() {
+        let settings = NoaaPointsSettings { max_stations: 0, ..NoaaPointsSettings::default() };
+        let resolver = PointResolver::new(
+            NoaaWeatherServices::HappyPath(HappyPathWeatherServices),
+            &settings,
+        );
+        let point = assert_ok!("47.6062,-122.3321".parse());
+
+        let resolved = assert_ok!(tokio_test::block_on(resolver.resolve(&point)));
+        assert_eq!(resolved.forecast_zone.as_ref(), "WAZ558");
+        assert!(resolved.stations.is_empty());
+        assert_eq!(resolver.cache().get(&point, Instant::now()), Some(resolved));
+    }
+}
@@ -0,0 +1,182 @@
+use super::capture::{self, CaptureKey, NoaaEndpoint};
+use super::{
+    AlertApi, GridpointForecastApi, NoaaWeatherError, PointApi, ZoneMetadataApi, ZoneWeatherApi,
+};
+use crate::model::zone_metadata::ZoneMetadata;
+use crate::model::{
+    GeoPoint, Gridpoint, HourlyForecast, LocationZone, LocationZoneCode, LocationZoneType,
+    ObservationReport, PointLocation, WeatherAlert, ZoneForecast,
+};
+use crate::settings::AggregationSettings;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Serves NOAA weather API responses from a capture directory, e.g., to reproduce an incident
+/// locally with the exact payloads that caused it. Each endpoint and zone replays its captures in
+/// recorded order, and repeats its last capture once they are exhausted.
+#[derive(Debug, Clone)]
+pub struct ReplayWeatherServices {
+    captures: Arc<BTreeMap<CaptureKey, Vec<Arc<str>>>>,
+    cursors: Arc<Mutex<HashMap<CaptureKey, usize>>>,
+    aggregation: Arc<AggregationSettings>,
+}
+
+impl ReplayWeatherServices {
+    #[instrument(level = "debug", err)]
+    pub fn load(dir: impl AsRef<Path> + std::fmt::Debug) -> Result<Self, NoaaWeatherError> {
+        let mut captures: BTreeMap<CaptureKey, Vec<Arc<str>>> = BTreeMap::new();
+        for (key, _, path) in capture::list_captures(dir.as_ref())? {
+            let body = std::fs::read_to_string(&path)?;
+            captures.entry(key).or_default().push(body.into());
+        }
+
+        info!(replay_dir=?dir, nr_replayed=%captures.len(), "replaying NOAA weather API captures");
+        Ok(Self {
+            captures: Arc::new(captures),
+            cursors: Arc::new(Mutex::new(HashMap::new())),
+            aggregation: Arc::new(AggregationSettings::default()),
+        })
+    }
+
+    pub fn with_aggregation(self, aggregation: AggregationSettings) -> Self {
+        Self { aggregation: Arc::new(aggregation), ..self }
+    }
+
+    fn next_body(&self, key: CaptureKey) -> Result<Arc<str>, NoaaWeatherError> {
+        let captures = match self.captures.get(&key) {
+            Some(captures) if !captures.is_empty() => captures,
+            _ => return Err(NoaaWeatherError::MissingCapture(key)),
+        };
+
+        let mut cursors = self.cursors.lock().map_err(|err| {
+            NoaaWeatherError::Capture(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                err.to_string(),
+            ))
+        })?;
+        let cursor = cursors.entry(key).or_default();
+        let body = captures[(*cursor).min(captures.len() - 1)].clone();
+        *cursor += 1;
+        Ok(body)
+    }
+}
+
+impl ZoneWeatherApi for ReplayWeatherServices {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_observation(
+        &self, _zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ObservationReport, NoaaWeatherError> {
+        let body = self.next_body(CaptureKey::zone(NoaaEndpoint::ZoneObservation, zone))?;
+        super::observation_from_geojson(body.parse()?, &self.aggregation)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_forecast(
+        &self, _zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneForecast, NoaaWeatherError> {
+        let body = self.next_body(CaptureKey::zone(NoaaEndpoint::ZoneForecast, zone))?;
+        super::forecast_from_geojson(body.parse()?)
+    }
+}
+
+impl ZoneMetadataApi for ReplayWeatherServices {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_metadata(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<ZoneMetadata, NoaaWeatherError> {
+        let body = self.next_body(CaptureKey::zone(NoaaEndpoint::ZoneMetadata, zone))?;
+        let zone = LocationZone::new(zone.clone(), zone_type);
+        super::zone_metadata_from_geojson(&zone, body.parse()?)
+    }
+}
+
+impl GridpointForecastApi for ReplayWeatherServices {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn zone_gridpoint(
+        &self, zone_type: LocationZoneType, zone: &LocationZoneCode,
+    ) -> Result<Gridpoint, NoaaWeatherError> {
+        let centroid = super::zone_centroid(&self.zone_metadata(zone_type, zone).await?)?;
+        self.point_gridpoint(&centroid).await
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn point_gridpoint(&self, point: &GeoPoint) -> Result<Gridpoint, NoaaWeatherError> {
+        let body = self.next_body(CaptureKey::point(NoaaEndpoint::Point, point))?;
+        super::gridpoint_from_geojson(body.parse()?)
+    }
+
+    #[instrument(level = "debug", skip(self), err)]
+    async fn hourly_forecast(
+        &self, gridpoint: &Gridpoint,
+    ) -> Result<HourlyForecast, NoaaWeatherError> {
+        let key = CaptureKey::gridpoint(NoaaEndpoint::GridpointHourlyForecast, gridpoint);
+        let body = self.next_body(key)?;
+        super::hourly_forecast_from_geojson(gridpoint, body.parse()?)
+    }
+}
+
+impl AlertApi for ReplayWeatherServices {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn active_alerts(&self) -> Result<Vec<WeatherAlert>, NoaaWeatherError> {
+        let body = self.next_body(CaptureKey::active_alerts())?;
+        super::alerts_from_geojson(body.parse()?)
+    }
+}
+
+impl PointApi for ReplayWeatherServices {
+    #[instrument(level = "debug", skip(self), err)]
+    async fn point_location(&self, point: &GeoPoint) -> Result<PointLocation, NoaaWeatherError> {
+        let point_body = self.next_body(CaptureKey::point(NoaaEndpoint::Point, point))?;
+        let stations_body =
+            self.next_body(CaptureKey::point(NoaaEndpoint::PointStations, point))?;
+        super::point_location_from_geojson(point, point_body.parse()?, stations_body.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claims::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_replay_recorded_fixtures() {
+        let replay = assert_ok!(ReplayWeatherServices::load("./tests/data"));
+        let zone = LocationZoneCode::new("WAZ558");
+
+        tokio_test::block_on(async {
+            let forecast =
+                assert_ok!(replay.zone_forecast(LocationZoneType::Forecast, &zone).await);
+            assert!(!forecast.periods.is_empty());
+
+            let again = assert_ok!(replay.zone_forecast(LocationZoneType::Forecast, &zone).await);
+            assert_eq!(again, forecast, "last capture repeats once exhausted");
+
+            assert_ok!(replay.zone_observation(LocationZoneType::Forecast, &zone).await);
+
+            let point = assert_ok!("47.6062,-122.3321".parse());
+            let location = assert_ok!(replay.point_location(&point).await);
+            assert_eq!(location.forecast_zone, zone);
+            assert_eq!(location.stations.len(), 3);
+            assert_eq!(assert_ok!(replay.active_alerts().await).len(), 326);
+
+            let metadata =
+                assert_ok!(replay.zone_metadata(LocationZoneType::Forecast, &zone).await);
+            assert_eq!(metadata.name, "Seattle and Vicinity");
+
+            let gridpoint =
+                assert_ok!(replay.zone_gridpoint(LocationZoneType::Forecast, &zone).await);
+            assert_eq!(gridpoint.to_string(), "SEW/126,63");
+            let hourly = assert_ok!(replay.hourly_forecast(&gridpoint).await);
+            assert_eq!(hourly.periods.len(), 24);
+
+            let other = LocationZoneCode::new("ORZ006");
+            let missing = replay.zone_forecast(LocationZoneType::Forecast, &other).await;
+            assert!(matches!(
+                assert_err!(missing),
+                NoaaWeatherError::MissingCapture(_)
+            ));
+        });
+    }
+}
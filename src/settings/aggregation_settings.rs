@@ -0,0 +1,77 @@
+use crate::model::{AggregationMethod, QualityControl, QuantitativeProperty};
+use serde_with::{serde_as, DisplayFromStr};
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+/// Settings for folding the station observations reported for a zone into the zone's weather.
+///
+/// Each property is aggregated with the method it is mapped to in `properties`, or else the
+/// `default_method`. Configuring `properties` replaces the default mapping, which aggregates
+/// wind direction with a circular mean.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AggregationSettings {
+    #[serde(default = "AggregationSettings::default_method")]
+    pub default_method: AggregationMethod,
+
+    /// Observations below this quality control level are excluded from aggregation; none are
+    /// excluded if not set.
+    #[serde(default)]
+    pub quality_floor: Option<QualityControl>,
+
+    /// Aggregation method by property name, e.g., `windSpeed: median`.
+    #[serde(default = "AggregationSettings::default_properties")]
+    #[serde_as(as = "HashMap<DisplayFromStr, _>")]
+    pub properties: HashMap<QuantitativeProperty, AggregationMethod>,
+}
+
+impl Default for AggregationSettings {
+    fn default() -> Self {
+        Self {
+            default_method: Self::default_method(),
+            quality_floor: None,
+            properties: Self::default_properties(),
+        }
+    }
+}
+
+impl AggregationSettings {
+    pub const fn default_method() -> AggregationMethod {
+        AggregationMethod::HighestQuality
+    }
+
+    pub fn default_properties() -> HashMap<QuantitativeProperty, AggregationMethod> {
+        maplit::hashmap! {
+            QuantitativeProperty::WindDirection => AggregationMethod::CircularMean,
+        }
+    }
+
+    /// Aggregation method of the property. A directional property mapped to a linear method is
+    /// aggregated with a circular mean instead, since linear methods misrepresent directions.
+    pub fn method_for(&self, property: QuantitativeProperty) -> AggregationMethod {
+        match self.configured_method_for(property) {
+            method if property.is_directional() && method.is_linear() => {
+                AggregationMethod::CircularMean
+            },
+            method => method,
+        }
+    }
+
+    /// Warns of directional properties mapped to a linear method, once when settings are loaded
+    /// rather than on every aggregation.
+    pub fn warn_of_linear_directions(&self) {
+        for property in QuantitativeProperty::iter().filter(|p| p.is_directional()) {
+            let method = self.configured_method_for(property);
+            if method.is_linear() {
+                warn!(
+                    %property, %method,
+                    "linear aggregation misrepresents directions - using circular mean"
+                );
+            }
+        }
+    }
+
+    fn configured_method_for(&self, property: QuantitativeProperty) -> AggregationMethod {
+        self.properties.get(&property).copied().unwrap_or(self.default_method)
+    }
+}
@@ -28,6 +28,36 @@ pub struct CliOptions {
     /// Default path is "./resources".
     #[clap(short = 's', long = "search-path", value_name = "SETTINGS_SEARCH_PATH")]
     pub settings_search_path: Option<String>,
+
+    /// Override the base url of the NOAA weather API; e.g., to point at a mock server.
+    /// Optionally overrides the noaa.base_url setting.
+    #[clap(long = "noaa-base-url", value_name = "URL")]
+    pub noaa_base_url: Option<String>,
+
+    /// Override the User-Agent identifying the application and contact to the NOAA weather API.
+    /// Optionally overrides the noaa.user_agent setting.
+    #[clap(long = "noaa-user-agent", value_name = "USER_AGENT")]
+    pub noaa_user_agent: Option<String>,
+
+    /// Override the timeout of each NOAA weather API request.
+    /// Optionally overrides the noaa.timeout_secs setting.
+    #[clap(long = "noaa-timeout-secs", value_name = "SECONDS")]
+    pub noaa_timeout_secs: Option<u32>,
+
+    /// Override the maximum number of retries of a failed NOAA weather API request.
+    /// Optionally overrides the noaa.retry.max_retries setting.
+    #[clap(long = "noaa-max-retries", value_name = "RETRIES")]
+    pub noaa_max_retries: Option<u32>,
+
+    /// Capture NOAA weather API response bodies into the directory.
+    /// Optionally overrides the noaa.capture.dir setting.
+    #[clap(long = "noaa-capture-dir", value_name = "PATH_TO_CAPTURE_DIR")]
+    pub noaa_capture_dir: Option<PathBuf>,
+
+    /// Serve NOAA weather API responses from a capture directory instead of calling the API.
+    /// Optionally overrides the noaa.replay_dir setting.
+    #[clap(long = "noaa-replay-dir", value_name = "PATH_TO_CAPTURE_DIR")]
+    pub noaa_replay_dir: Option<PathBuf>,
     // /// Specify the machine id [0, 31) used in correlation id generation, overriding what may be set
     // /// in an environment variable. This id should be unique for the entity type within a cluster
     // /// environment. Different entity types can use the same machine id.
@@ -64,6 +94,36 @@ impl LoadingOptions for CliOptions {
     fn load_overrides(
         &self, config: ConfigBuilder<DefaultState>,
     ) -> Result<ConfigBuilder<DefaultState>, Self::Error> {
+        let config = match self.noaa_base_url.as_deref() {
+            None => config,
+            Some(base_url) => config.set_override("noaa.base_url", base_url)?,
+        };
+
+        let config = match self.noaa_user_agent.as_deref() {
+            None => config,
+            Some(user_agent) => config.set_override("noaa.user_agent", user_agent)?,
+        };
+
+        let config = match self.noaa_timeout_secs {
+            None => config,
+            Some(timeout) => config.set_override("noaa.timeout_secs", i64::from(timeout))?,
+        };
+
+        let config = match self.noaa_max_retries {
+            None => config,
+            Some(retries) => config.set_override("noaa.retry.max_retries", i64::from(retries))?,
+        };
+
+        let config = match self.noaa_capture_dir.as_ref() {
+            None => config,
+            Some(dir) => config.set_override("noaa.capture.dir", dir.to_string_lossy().as_ref())?,
+        };
+
+        let config = match self.noaa_replay_dir.as_ref() {
+            None => config,
+            Some(dir) => config.set_override("noaa.replay_dir", dir.to_string_lossy().as_ref())?,
+        };
+
         // let config = match self.machine_id {
         //     None => config,
         //     Some(machine_id) => config.set_override("machine_id", i64::from(machine_id))?,
@@ -1,10 +1,22 @@
+mod aggregation_settings;
 mod cli_options;
 mod http_api_settings;
+mod noaa_settings;
 #[cfg(test)]
 mod tests;
+mod update_schedule_settings;
+mod update_weather_settings;
+mod webhook_settings;
+mod zone_metadata_settings;
 
+pub use aggregation_settings::AggregationSettings;
 pub use cli_options::CliOptions;
 pub use http_api_settings::HttpApiSettings;
+pub use noaa_settings::{NoaaCaptureSettings, NoaaPointsSettings, NoaaRetrySettings, NoaaSettings};
+pub use update_schedule_settings::{CadenceSettings, UpdateScheduleSettings};
+pub use update_weather_settings::{UpdateWeatherSettings, ZoneRetrySettings};
+pub use webhook_settings::WebhookSettings;
+pub use zone_metadata_settings::ZoneMetadataSettings;
 
 use settings_loader::common::database::DatabaseSettings;
 use settings_loader::SettingsLoader;
@@ -13,6 +25,24 @@ use settings_loader::SettingsLoader;
 pub struct Settings {
     pub http_api: HttpApiSettings,
     pub database: DatabaseSettings,
+
+    #[serde(default)]
+    pub noaa: NoaaSettings,
+
+    #[serde(default)]
+    pub schedule: UpdateScheduleSettings,
+
+    #[serde(default)]
+    pub update_weather: UpdateWeatherSettings,
+
+    #[serde(default)]
+    pub webhook: WebhookSettings,
+
+    #[serde(default)]
+    pub aggregation: AggregationSettings,
+
+    #[serde(default)]
+    pub zone_metadata: ZoneMetadataSettings,
     // pub registrar: DomainSettings,
     // pub weather: DomainSettings,
     // pub zone: AggregateSettings,
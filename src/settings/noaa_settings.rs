@@ -0,0 +1,201 @@
+use serde_with::serde_as;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+
+/// Settings for the NOAA weather API client.
+///
+/// NOAA asks API clients to identify themselves via the `User-Agent` header with the application
+/// and a contact, e.g., `"(myweatherapp.com, contact@myweatherapp.com)"`.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NoaaSettings {
+    #[serde(default = "NoaaSettings::default_base_url")]
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub base_url: Url,
+
+    #[serde(default = "NoaaSettings::default_user_agent")]
+    pub user_agent: String,
+
+    /// Timeout of each attempt of a request to the API.
+    #[serde(default = "NoaaSettings::default_timeout", alias = "timeout_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub timeout: Duration,
+
+    #[serde(
+        default = "NoaaSettings::default_pool_idle_timeout",
+        alias = "pool_idle_timeout_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub pool_idle_timeout: Duration,
+
+    #[serde(default = "NoaaSettings::default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+
+    #[serde(default)]
+    pub retry: NoaaRetrySettings,
+
+    #[serde(default)]
+    pub points: NoaaPointsSettings,
+
+    /// Records NOAA API response bodies, e.g., to reproduce a production incident locally.
+    #[serde(default)]
+    pub capture: Option<NoaaCaptureSettings>,
+
+    /// Serves NOAA API responses from a capture directory instead of calling the API.
+    #[serde(default)]
+    pub replay_dir: Option<PathBuf>,
+}
+
+impl Default for NoaaSettings {
+    fn default() -> Self {
+        Self {
+            base_url: Self::default_base_url(),
+            user_agent: Self::default_user_agent(),
+            timeout: Self::default_timeout(),
+            pool_idle_timeout: Self::default_pool_idle_timeout(),
+            pool_max_idle_per_host: Self::default_pool_max_idle_per_host(),
+            retry: NoaaRetrySettings::default(),
+            points: NoaaPointsSettings::default(),
+            capture: None,
+            replay_dir: None,
+        }
+    }
+}
+
+impl NoaaSettings {
+    pub fn default_base_url() -> Url {
+        Url::from_str("https://api.weather.gov").unwrap()
+    }
+
+    pub fn default_user_agent() -> String {
+        "(here.com, contact@example.com)".to_string()
+    }
+
+    pub const fn default_timeout() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    pub const fn default_pool_idle_timeout() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    pub const fn default_pool_max_idle_per_host() -> usize {
+        5
+    }
+}
+
+/// Exponential backoff retry of transient NOAA API failures.
+#[serde_as]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub struct NoaaRetrySettings {
+    #[serde(default = "NoaaRetrySettings::default_max_retries")]
+    pub max_retries: u32,
+
+    #[serde(
+        default = "NoaaRetrySettings::default_min_backoff",
+        alias = "min_backoff_millis"
+    )]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    pub min_backoff: Duration,
+
+    #[serde(
+        default = "NoaaRetrySettings::default_max_backoff",
+        alias = "max_backoff_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub max_backoff: Duration,
+}
+
+impl Default for NoaaRetrySettings {
+    fn default() -> Self {
+        Self {
+            max_retries: Self::default_max_retries(),
+            min_backoff: Self::default_min_backoff(),
+            max_backoff: Self::default_max_backoff(),
+        }
+    }
+}
+
+impl NoaaRetrySettings {
+    pub const fn default_max_retries() -> u32 {
+        3
+    }
+
+    pub const fn default_min_backoff() -> Duration {
+        Duration::from_millis(1_000)
+    }
+
+    pub const fn default_max_backoff() -> Duration {
+        Duration::from_secs(300)
+    }
+}
+
+/// Resolution of latitude/longitude points to their NOAA zones, gridpoint and nearest stations.
+/// Resolutions rarely change, so they are cached.
+#[serde_as]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub struct NoaaPointsSettings {
+    #[serde(
+        default = "NoaaPointsSettings::default_cache_ttl",
+        alias = "cache_ttl_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub cache_ttl: Duration,
+
+    /// Maximum number of resolutions cached; the least recently resolved are evicted first.
+    #[serde(default = "NoaaPointsSettings::default_cache_capacity")]
+    pub cache_capacity: usize,
+
+    /// Maximum number of nearest observation stations kept for a point.
+    #[serde(default = "NoaaPointsSettings::default_max_stations")]
+    pub max_stations: usize,
+}
+
+impl Default for NoaaPointsSettings {
+    fn default() -> Self {
+        Self {
+            cache_ttl: Self::default_cache_ttl(),
+            cache_capacity: Self::default_cache_capacity(),
+            max_stations: Self::default_max_stations(),
+        }
+    }
+}
+
+impl NoaaPointsSettings {
+    pub const fn default_cache_ttl() -> Duration {
+        Duration::from_secs(24 * 60 * 60)
+    }
+
+    pub const fn default_cache_capacity() -> usize {
+        1_000
+    }
+
+    pub const fn default_max_stations() -> usize {
+        3
+    }
+}
+
+/// Capture of NOAA API response bodies into a directory. Once the directory's captures reach the
+/// size cap, the oldest captures are removed to make room.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NoaaCaptureSettings {
+    pub dir: PathBuf,
+
+    #[serde(default = "NoaaCaptureSettings::default_max_bytes")]
+    pub max_bytes: u64,
+}
+
+impl NoaaCaptureSettings {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: Self::default_max_bytes(),
+        }
+    }
+
+    pub const fn default_max_bytes() -> u64 {
+        100 * 1024 * 1024
+    }
+}
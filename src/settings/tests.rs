@@ -8,6 +8,7 @@ mod loading {
     use pretty_assertions::assert_eq;
     use secrecy::{ExposeSecret, Secret};
     use settings_loader::common::http::HttpServerSettings;
+    use std::collections::HashMap;
     use std::time::Duration;
 
     static SETTINGS: once_cell::sync::Lazy<Settings> = once_cell::sync::Lazy::new(|| Settings {
@@ -32,6 +33,20 @@ mod loading {
             idle_timeout: Some(Duration::from_secs(300)),
             max_lifetime: Some(Duration::from_secs(1_800)),
         },
+        noaa: NoaaSettings::default(),
+        schedule: UpdateScheduleSettings {
+            enabled: false,
+            cadence: CadenceSettings {
+                interval: Some(Duration::from_secs(15 * 60)),
+                cron: None,
+            },
+            jitter: Duration::from_secs(30),
+            zones: HashMap::default(),
+        },
+        update_weather: UpdateWeatherSettings::default(),
+        webhook: WebhookSettings::default(),
+        aggregation: AggregationSettings::default(),
+        zone_metadata: ZoneMetadataSettings::default(),
         // registrar: DomainSettings::default(),
         // weather: DomainSettings::default(),
         // correlation: CorrelationSettings::default(),
@@ -83,6 +98,13 @@ mod loading {
                 idle_timeout: Some(Duration::from_secs(300)),
                 max_lifetime: None,
             },
+            noaa: NoaaSettings::default(),
+            schedule: UpdateScheduleSettings::default(),
+            update_weather: UpdateWeatherSettings::default(),
+            webhook: WebhookSettings::default(),
+            aggregation: AggregationSettings::default(),
+            zone_metadata: ZoneMetadataSettings::default(),
+            zone_metadata: ZoneMetadataSettings::default(),
             // registrar: DomainSettings::default(),
             // weather: DomainSettings::default(),
             // correlation: CorrelationSettings { machine_id: 1, node_id: 1 },
@@ -92,6 +114,109 @@ mod loading {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_noaa_settings_serde() {
+        let yaml = r##"|---
+            |base_url: "http://localhost:8088/noaa"
+            |user_agent: "(weather.example.com, ops@weather.example.com)"
+            |timeout_secs: 10
+            |retry:
+            |  max_retries: 1
+            |  min_backoff_millis: 250
+            |points:
+            |  cache_ttl_secs: 3600
+            |  max_stations: 5
+            |capture:
+            |  dir: ./captures
+            |"##
+        .trim_margin()
+        .unwrap();
+
+        let actual: NoaaSettings = assert_ok!(serde_yaml::from_str(&yaml));
+        assert_eq!(
+            actual,
+            NoaaSettings {
+                base_url: url::Url::parse("http://localhost:8088/noaa").unwrap(),
+                user_agent: "(weather.example.com, ops@weather.example.com)".to_string(),
+                timeout: Duration::from_secs(10),
+                retry: NoaaRetrySettings {
+                    max_retries: 1,
+                    min_backoff: Duration::from_millis(250),
+                    ..NoaaRetrySettings::default()
+                },
+                points: NoaaPointsSettings {
+                    cache_ttl: Duration::from_secs(3600),
+                    max_stations: 5,
+                    ..NoaaPointsSettings::default()
+                },
+                capture: Some(NoaaCaptureSettings::new("./captures")),
+                ..NoaaSettings::default()
+            }
+        );
+
+        let actual: NoaaSettings = assert_ok!(serde_yaml::from_str("{}"));
+        assert_eq!(actual, NoaaSettings::default());
+    }
+
+    #[test]
+    fn test_aggregation_settings_serde() {
+        use crate::model::{AggregationMethod, QualityControl, QuantitativeProperty};
+
+        let yaml = r##"|---
+            |default_method: mean
+            |quality_floor: C
+            |properties:
+            |  windDirection: circular_mean
+            |  windSpeed: median
+            |"##
+        .trim_margin()
+        .unwrap();
+
+        let actual: AggregationSettings = assert_ok!(serde_yaml::from_str(&yaml));
+        assert_eq!(
+            actual,
+            AggregationSettings {
+                default_method: AggregationMethod::Mean,
+                quality_floor: Some(QualityControl::C),
+                properties: maplit::hashmap! {
+                    QuantitativeProperty::WindDirection => AggregationMethod::CircularMean,
+                    QuantitativeProperty::WindSpeed => AggregationMethod::Median,
+                },
+            }
+        );
+        assert_eq!(
+            actual.method_for(QuantitativeProperty::Temperature),
+            AggregationMethod::Mean
+        );
+
+        let actual: AggregationSettings = assert_ok!(serde_yaml::from_str("{}"));
+        assert_eq!(actual, AggregationSettings::default());
+    }
+
+    #[test]
+    fn test_aggregation_settings_linear_directions() {
+        use crate::model::{AggregationMethod, QuantitativeProperty};
+
+        let actual: AggregationSettings = assert_ok!(serde_yaml::from_str(
+            "{ properties: { windDirection: mean } }"
+        ));
+        assert_eq!(
+            actual.method_for(QuantitativeProperty::WindDirection),
+            AggregationMethod::CircularMean
+        );
+
+        let yaml = "{ default_method: mean, properties: { windSpeed: median } }";
+        let actual: AggregationSettings = assert_ok!(serde_yaml::from_str(yaml));
+        assert_eq!(
+            actual.method_for(QuantitativeProperty::WindDirection),
+            AggregationMethod::CircularMean
+        );
+        assert_eq!(
+            actual.method_for(QuantitativeProperty::Temperature),
+            AggregationMethod::Mean
+        );
+    }
+
     #[test]
     fn test_basic_load() {
         let c = assert_ok!(config::Config::builder()
@@ -120,6 +245,7 @@ mod loading {
                 max_lifetime: Some(Duration::from_secs(1800)),
                 ..SETTINGS.database.clone()
             },
+            schedule: UpdateScheduleSettings::default(),
             ..SETTINGS.clone()
         };
 
@@ -180,6 +306,41 @@ mod loading {
         Ok(())
     }
 
+    #[test]
+    fn test_noaa_options_override_load() -> anyhow::Result<()> {
+        once_cell::sync::Lazy::force(&crate::setup_tracing::TEST_TRACING);
+        let main_span = info_span!("test_noaa_options_override_load");
+        let _ = main_span.enter();
+
+        let options = CliOptions {
+            settings_search_path: Some("./resources".into()),
+            secrets: Some("./resources/secrets.yaml".into()),
+            noaa_base_url: Some("http://127.0.0.1:8088".to_string()),
+            noaa_user_agent: Some("(staging.example.com, ops@example.com)".to_string()),
+            noaa_timeout_secs: Some(5),
+            noaa_max_retries: Some(0),
+            noaa_replay_dir: Some("./tests/data".into()),
+            ..CliOptions::default()
+        };
+
+        temp_env::with_vars(vec![("APP_ENVIRONMENT", None::<&str>)], || {
+            let actual: Settings = assert_ok!(Settings::load(&options));
+            assert_eq!(
+                actual.noaa,
+                NoaaSettings {
+                    base_url: url::Url::parse("http://127.0.0.1:8088").unwrap(),
+                    user_agent: "(staging.example.com, ops@example.com)".to_string(),
+                    timeout: Duration::from_secs(5),
+                    retry: NoaaRetrySettings { max_retries: 0, ..SETTINGS.noaa.retry },
+                    replay_dir: Some("./tests/data".into()),
+                    ..SETTINGS.noaa.clone()
+                }
+            );
+        });
+
+        Ok(())
+    }
+
     #[test]
     fn test_local_load() -> anyhow::Result<()> {
         once_cell::sync::Lazy::force(&crate::setup_tracing::TEST_TRACING);
@@ -223,6 +384,7 @@ mod loading {
                         require_ssl: false,
                         ..SETTINGS.database.clone()
                     },
+                    schedule: UpdateScheduleSettings::default(),
                     ..SETTINGS.clone()
                 };
 
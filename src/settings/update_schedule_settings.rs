@@ -0,0 +1,40 @@
+use crate::model::LocationZoneCode;
+use serde_with::serde_as;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Settings for the built-in scheduler that periodically updates weather for monitored zones.
+///
+/// The global cadence applies to every monitored zone that does not have its own entry in
+/// `zones`. A cadence is either a fixed `interval` or a `cron` expression (with a leading seconds
+/// field, e.g., `"0 */15 * * * *"`); if both are given the cron expression wins.
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpdateScheduleSettings {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(flatten)]
+    pub cadence: CadenceSettings,
+
+    /// Upper bound of the random delay added to each scheduled run, so that several instances
+    /// (or several zone schedules) do not hit the NOAA API at the same instant.
+    #[serde(default, alias = "jitter_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub jitter: Duration,
+
+    /// Per-zone cadence overrides.
+    #[serde(default)]
+    pub zones: HashMap<LocationZoneCode, CadenceSettings>,
+}
+
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct CadenceSettings {
+    #[serde(alias = "interval_secs")]
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    pub interval: Option<Duration>,
+
+    #[serde(default)]
+    pub cron: Option<String>,
+}
@@ -0,0 +1,118 @@
+use serde_with::serde_as;
+use std::time::Duration;
+
+/// Settings for the update weather saga.
+///
+/// An update that is still active after its `deadline` is expired by the watchdog: its zones still
+/// in progress are noted as failed and the update is finished.
+///
+/// Alerts are reconciled when their zone is updated, so monitored zones holding alerts no longer
+/// in force are updated every `alert_expiry_interval` to note those alerts as expired.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UpdateWeatherSettings {
+    #[serde(
+        default = "UpdateWeatherSettings::default_deadline",
+        alias = "deadline_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub deadline: Duration,
+
+    /// How often the watchdog checks for updates past their deadline.
+    #[serde(
+        default = "UpdateWeatherSettings::default_watchdog_interval",
+        alias = "watchdog_interval_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub watchdog_interval: Duration,
+
+    /// How often monitored zones are checked for alerts past their expiry or end; zero disables
+    /// the check, leaving those alerts to expire with the zone's next update.
+    #[serde(
+        default = "UpdateWeatherSettings::default_alert_expiry_interval",
+        alias = "alert_expiry_interval_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub alert_expiry_interval: Duration,
+
+    /// Timeout of a zone's hourly forecast fetch, beyond which the update goes on without it.
+    #[serde(
+        default = "UpdateWeatherSettings::default_hourly_forecast_timeout",
+        alias = "hourly_forecast_timeout_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub hourly_forecast_timeout: Duration,
+
+    #[serde(default)]
+    pub zone_retry: ZoneRetrySettings,
+}
+
+impl Default for UpdateWeatherSettings {
+    fn default() -> Self {
+        Self {
+            deadline: Self::default_deadline(),
+            watchdog_interval: Self::default_watchdog_interval(),
+            alert_expiry_interval: Self::default_alert_expiry_interval(),
+            hourly_forecast_timeout: Self::default_hourly_forecast_timeout(),
+            zone_retry: ZoneRetrySettings::default(),
+        }
+    }
+}
+
+impl UpdateWeatherSettings {
+    pub const fn default_deadline() -> Duration {
+        Duration::from_secs(5 * 60)
+    }
+
+    pub const fn default_watchdog_interval() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    pub const fn default_alert_expiry_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    pub const fn default_hourly_forecast_timeout() -> Duration {
+        Duration::from_secs(10)
+    }
+}
+
+/// Retries of a zone's observation or forecast within an update, before the zone is noted as
+/// failed.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ZoneRetrySettings {
+    /// Attempts of each zone update, including the first; `1` does not retry.
+    #[serde(default = "ZoneRetrySettings::default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, which doubles on each further retry.
+    #[serde(default = "ZoneRetrySettings::default_backoff", alias = "backoff_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub backoff: Duration,
+}
+
+impl Default for ZoneRetrySettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: Self::default_max_attempts(),
+            backoff: Self::default_backoff(),
+        }
+    }
+}
+
+impl ZoneRetrySettings {
+    pub const fn default_max_attempts() -> u32 {
+        1
+    }
+
+    pub const fn default_backoff() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    /// The delay before retrying the given, failed attempt.
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+}
@@ -0,0 +1,70 @@
+use serde_with::serde_as;
+use std::time::Duration;
+
+/// Settings for delivering alert notices to webhook subscriptions.
+///
+/// A delivery failing with a network error, a timeout or a `5xx`, `408` or `429` response is
+/// retried with exponential backoff; once `max_attempts` are exhausted it is dead-lettered.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WebhookSettings {
+    /// Timeout of each delivery attempt.
+    #[serde(default = "WebhookSettings::default_timeout", alias = "timeout_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub timeout: Duration,
+
+    /// Attempts of each delivery, including the first.
+    #[serde(default = "WebhookSettings::default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, which doubles on each further retry up to `max_backoff`.
+    #[serde(
+        default = "WebhookSettings::default_min_backoff",
+        alias = "min_backoff_millis"
+    )]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    pub min_backoff: Duration,
+
+    #[serde(
+        default = "WebhookSettings::default_max_backoff",
+        alias = "max_backoff_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub max_backoff: Duration,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            timeout: Self::default_timeout(),
+            max_attempts: Self::default_max_attempts(),
+            min_backoff: Self::default_min_backoff(),
+            max_backoff: Self::default_max_backoff(),
+        }
+    }
+}
+
+impl WebhookSettings {
+    pub const fn default_timeout() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    pub const fn default_max_attempts() -> u32 {
+        5
+    }
+
+    pub const fn default_min_backoff() -> Duration {
+        Duration::from_millis(1_000)
+    }
+
+    pub const fn default_max_backoff() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    /// The delay before retrying the given, failed attempt.
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        self.min_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
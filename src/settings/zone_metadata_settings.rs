@@ -0,0 +1,43 @@
+use serde_with::serde_as;
+use std::time::Duration;
+
+/// Settings for keeping the geometry and properties of monitored zones, which NOAA rarely
+/// changes, so each zone's metadata is fetched once and then refreshed once it is `max_age` old.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ZoneMetadataSettings {
+    /// How often monitored zones are checked for missing or stale metadata.
+    #[serde(
+        default = "ZoneMetadataSettings::default_check_interval",
+        alias = "check_interval_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub check_interval: Duration,
+
+    /// Age after which a zone's metadata is fetched again.
+    #[serde(
+        default = "ZoneMetadataSettings::default_max_age",
+        alias = "max_age_secs"
+    )]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub max_age: Duration,
+}
+
+impl Default for ZoneMetadataSettings {
+    fn default() -> Self {
+        Self {
+            check_interval: Self::default_check_interval(),
+            max_age: Self::default_max_age(),
+        }
+    }
+}
+
+impl ZoneMetadataSettings {
+    pub const fn default_check_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    pub const fn default_max_age() -> Duration {
+        Duration::from_secs(7 * 24 * 60 * 60)
+    }
+}
@@ -0,0 +1,167 @@
+use claims::*;
+use pretty_assertions::assert_eq;
+use std::time::Duration;
+use weather_disintegrate::model::{GeoPoint, LocationZoneCode, LocationZoneType};
+use weather_disintegrate::services::fake_noaa::{
+    FakeNoaa, FakeNoaaFixtures, FakeNoaaHandle, Fault, FaultInjection,
+};
+use weather_disintegrate::services::noaa::{
+    AlertApi, GridpointForecastApi, NoaaWeatherApi, NoaaWeatherError, PointApi, ZoneWeatherApi,
+};
+use weather_disintegrate::{NoaaRetrySettings, NoaaSettings};
+
+async fn spawn_fake_noaa(faults: FaultInjection) -> FakeNoaaHandle {
+    let fixtures = assert_ok!(FakeNoaaFixtures::load("./tests/data"));
+    assert_ok!(FakeNoaa::new(fixtures, faults).spawn().await)
+}
+
+fn noaa_api(fake: &FakeNoaaHandle, timeout: Duration, max_retries: u32) -> NoaaWeatherApi {
+    let settings = NoaaSettings {
+        base_url: fake.base_url.clone(),
+        timeout,
+        retry: NoaaRetrySettings {
+            max_retries,
+            min_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        },
+        ..NoaaSettings::default()
+    };
+
+    assert_ok!(NoaaWeatherApi::new(&settings))
+}
+
+#[test]
+fn test_fake_noaa_serves_fixtures() {
+    once_cell::sync::Lazy::force(&weather_disintegrate::setup_tracing::TEST_TRACING);
+    let main_span = tracing::info_span!("test_fake_noaa_serves_fixtures");
+    let _main_span_guard = main_span.enter();
+
+    tokio_test::block_on(async {
+        let fake = spawn_fake_noaa(FaultInjection::default()).await;
+        let api = noaa_api(&fake, Duration::from_secs(5), 0);
+        let zone = LocationZoneCode::new("WAZ558");
+
+        let observation = assert_ok!(api.zone_observation(LocationZoneType::Forecast, &zone).await);
+        assert_some!(observation.weather.temperature);
+        assert!(!observation.stations.is_empty());
+
+        let forecast = assert_ok!(api.zone_forecast(LocationZoneType::Forecast, &zone).await);
+        assert_eq!(
+            forecast.zone_code,
+            "https://api.weather.gov/zones/forecast/WAZ558"
+        );
+        assert!(!forecast.periods.is_empty());
+
+        let alerts = assert_ok!(api.active_alerts().await);
+        assert_eq!(alerts.len(), 326);
+
+        let point: GeoPoint = assert_ok!("47.6062,-122.3321".parse());
+        let location = assert_ok!(api.point_location(&point).await);
+        assert_eq!(location.forecast_zone, zone);
+        assert_eq!(location.gridpoint.to_string(), "SEW/125,68");
+        assert_eq!(location.stations.len(), 3);
+
+        let gridpoint = assert_ok!(api.zone_gridpoint(LocationZoneType::Forecast, &zone).await);
+        assert_eq!(gridpoint.to_string(), "SEW/126,63");
+        let hourly = assert_ok!(api.hourly_forecast(&gridpoint).await);
+        assert_eq!(hourly.gridpoint, gridpoint);
+        assert_eq!(hourly.periods.len(), 24);
+
+        assert_eq!(fake.service().requests(), 8);
+        assert_ok!(fake.stop().await);
+    });
+}
+
+#[test]
+fn test_fake_noaa_transient_faults_are_retried() {
+    once_cell::sync::Lazy::force(&weather_disintegrate::setup_tracing::TEST_TRACING);
+    let main_span = tracing::info_span!("test_fake_noaa_transient_faults_are_retried");
+    let _main_span_guard = main_span.enter();
+
+    tokio_test::block_on(async {
+        let faults = FaultInjection {
+            scripted: vec![Fault::ServerError, Fault::TooManyRequests],
+            ..FaultInjection::default()
+        };
+        let fake = spawn_fake_noaa(faults).await;
+        let api = noaa_api(&fake, Duration::from_secs(5), 3);
+
+        let forecast = api
+            .zone_forecast(LocationZoneType::Forecast, &LocationZoneCode::new("WAZ558"))
+            .await;
+        assert_ok!(forecast);
+        assert_eq!(fake.service().requests(), 3);
+    });
+}
+
+#[test]
+fn test_fake_noaa_malformed_payload() {
+    once_cell::sync::Lazy::force(&weather_disintegrate::setup_tracing::TEST_TRACING);
+    let main_span = tracing::info_span!("test_fake_noaa_malformed_payload");
+    let _main_span_guard = main_span.enter();
+
+    tokio_test::block_on(async {
+        let faults = FaultInjection {
+            scripted: vec![Fault::Malformed],
+            ..FaultInjection::default()
+        };
+        let fake = spawn_fake_noaa(faults).await;
+        let api = noaa_api(&fake, Duration::from_secs(5), 3);
+
+        let alerts = api.active_alerts().await;
+        assert!(matches!(assert_err!(alerts), NoaaWeatherError::GeoJson(_)));
+        assert_eq!(
+            fake.service().requests(),
+            1,
+            "malformed payload is not retried"
+        );
+    });
+}
+
+#[test]
+fn test_fake_noaa_point_outside_coverage_is_not_found() {
+    once_cell::sync::Lazy::force(&weather_disintegrate::setup_tracing::TEST_TRACING);
+    let main_span = tracing::info_span!("test_fake_noaa_point_outside_coverage_is_not_found");
+    let _main_span_guard = main_span.enter();
+
+    tokio_test::block_on(async {
+        let faults = FaultInjection {
+            scripted: vec![Fault::NotFound],
+            ..FaultInjection::default()
+        };
+        let fake = spawn_fake_noaa(faults).await;
+        let api = noaa_api(&fake, Duration::from_secs(5), 3);
+
+        let point: GeoPoint = assert_ok!("0,0".parse());
+        let location = api.point_location(&point).await;
+        match assert_err!(location) {
+            NoaaWeatherError::NotFound { url, detail } => {
+                assert_eq!(url.path(), format!("/points/{point}"));
+                assert!(detail.contains("no data"), "detail: {detail}");
+            },
+            error => panic!("expected NotFound but was {error:?}"),
+        }
+        assert_eq!(fake.service().requests(), 1, "not found is not retried");
+    });
+}
+
+#[test]
+fn test_fake_noaa_latency_exceeds_timeout() {
+    once_cell::sync::Lazy::force(&weather_disintegrate::setup_tracing::TEST_TRACING);
+    let main_span = tracing::info_span!("test_fake_noaa_latency_exceeds_timeout");
+    let _main_span_guard = main_span.enter();
+
+    tokio_test::block_on(async {
+        let faults = FaultInjection {
+            latency: Duration::from_millis(500),
+            ..FaultInjection::default()
+        };
+        let fake = spawn_fake_noaa(faults).await;
+        let api = noaa_api(&fake, Duration::from_millis(100), 0);
+
+        let observation = api
+            .zone_observation(LocationZoneType::Forecast, &LocationZoneCode::new("WAZ558"))
+            .await;
+        assert_err!(observation);
+    });
+}
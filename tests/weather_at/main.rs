@@ -0,0 +1,67 @@
+use claims::*;
+use pretty_assertions::assert_eq;
+use std::net::TcpListener;
+use tokio_util::task::TaskTracker;
+use weather_disintegrate::model::registrar;
+use weather_disintegrate::model::{LocationZone, LocationZoneCode};
+use weather_disintegrate::server::{self, AppState, RunParameters};
+use weather_disintegrate::services::fake_noaa::{FakeNoaa, FakeNoaaFixtures, FaultInjection};
+
+#[test]
+fn test_weather_at_concurrent_requests_monitor_zone_once() {
+    once_cell::sync::Lazy::force(&weather_disintegrate::setup_tracing::TEST_TRACING);
+    let main_span = tracing::info_span!("test_weather_at_concurrent_requests_monitor_zone_once");
+    let _main_span_guard = main_span.enter();
+
+    tokio_test::block_on(async {
+        let fixtures = assert_ok!(FakeNoaaFixtures::load("./tests/data"));
+        let fake = assert_ok!(FakeNoaa::new(fixtures, FaultInjection::default()).spawn().await);
+
+        let options = weather_disintegrate::CliOptions {
+            config: Some("./resources/application.yaml".into()),
+            secrets: Some("./resources/secrets.yaml".into()),
+            environment: Some("local".into()),
+            ..Default::default()
+        };
+        let mut settings = assert_ok!(weather_disintegrate::Settings::load(&options));
+        settings.noaa.base_url = fake.base_url.clone();
+
+        let task_tracker = TaskTracker::new();
+        let state = assert_ok!(AppState::new(&settings, &task_tracker).await);
+        let registrar_dm = state.registrar_support.decision_maker.clone();
+
+        // the point fixture resolves to WAZ558, which earlier runs may have left monitored
+        let zone = LocationZone::forecast(LocationZoneCode::new("WAZ558"));
+        assert_ok!(registrar::ignore_zone(zone.code.clone(), &registrar_dm).await);
+
+        let listener = assert_ok!(TcpListener::bind("127.0.0.1:0"));
+        assert_ok!(listener.set_nonblocking(true));
+        let port = assert_ok!(listener.local_addr()).port();
+        let run_params = RunParameters::from_settings(&settings);
+        let _server = assert_ok!(server::run_http_server(listener, state, &run_params).await);
+
+        let client = reqwest::Client::new();
+        let url = format!("http://127.0.0.1:{port}/api/v1/weather/at?lat=47.6062&lon=-122.3321");
+        let (first, second) = futures::join!(client.get(&url).send(), client.get(&url).send());
+
+        let mut update_ids = 0;
+        for response in [assert_ok!(first), assert_ok!(second)] {
+            assert_eq!(response.status(), reqwest::StatusCode::OK);
+            let body: serde_json::Value = assert_ok!(response.json().await);
+            assert_eq!(body["location"]["forecastZone"], "WAZ558");
+            if body.get("updateId").is_some() {
+                update_ids += 1;
+            }
+        }
+        assert!(
+            update_ids <= 1,
+            "only the request monitoring the zone starts an update"
+        );
+
+        let registered = assert_ok!(registrar::ensure_zone_monitored(zone, &registrar_dm).await);
+        assert!(
+            !registered,
+            "zone is monitored once either request is answered"
+        );
+    });
+}
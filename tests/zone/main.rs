@@ -13,7 +13,7 @@ use std::time::Duration;
 use tracing::Instrument;
 use weather::zone::WeatherRepository;
 use weather_disintegrate::model::weather::{self, WeatherEvent, WeatherEventSerde, WeatherSupport};
-use weather_disintegrate::model::{LocationZoneCode, WeatherFrame, ZoneForecast};
+use weather_disintegrate::model::{LocationZoneCode, ObservationReport, ZoneForecast};
 
 #[test]
 fn test_note_current_weather() -> anyhow::Result<()> {
@@ -33,9 +33,16 @@ fn test_note_current_weather() -> anyhow::Result<()> {
 
     let update_id = weather::update::next_id();
     let zone = LocationZoneCode::random();
-    let mut observation: WeatherFrame = actual_features.into();
-    let time_rep = observation.timestamp.format();
-    observation.timestamp = assert_some!(Timestamp::parse(time_rep.as_ref()));
+    let mut observation: ObservationReport = actual_features.into();
+    let time_rep = observation.weather.timestamp.format();
+    observation.weather.timestamp = assert_some!(Timestamp::parse(time_rep.as_ref()));
+    assert_eq!(time_rep.as_ref(), "2024-03-07T00:15:00.000Z");
+    let station = assert_some!(observation.stations.iter().find(|s| s.station_id == "KPLU"));
+    assert_some!(station.location);
+    assert_eq!(
+        station.weather.timestamp.format().as_ref(),
+        "2024-03-07T00:15:00.000Z"
+    );
     let command = weather::zone::protocol::NoteObservation::new(
         zone.clone(),
         update_id.clone(),
@@ -76,12 +83,15 @@ fn test_note_current_weather() -> anyhow::Result<()> {
                 vec![WeatherEvent::ObservationUpdated {
                     zone: zone_cmd,
                     update_id: update_id.clone(),
-                    weather: Arc::new(observation_cmd),
+                    weather: Arc::new(observation_cmd.weather),
+                    stations: Arc::new(observation_cmd.stations),
                 }]
             );
 
-            let weather_projection =
-                assert_ok!(weather::zone::read_model::ZoneWeatherProjection::new(pool_cmd).await);
+            let (changes, _) = tokio::sync::broadcast::channel(16);
+            let weather_projection = assert_ok!(
+                weather::zone::read_model::ZoneWeatherProjection::new(pool_cmd, changes).await
+            );
 
             let listener_config =
                 disintegrate_postgres::PgEventListenerConfig::poller(Duration::from_millis(50));
@@ -112,7 +122,11 @@ fn test_note_current_weather() -> anyhow::Result<()> {
             let weather_repository = WeatherRepository::new(pool.clone());
             let actual = assert_some!(assert_ok!(weather_repository.weather_by_zone(&zone).await));
             assert_eq!(actual.zone, zone);
-            assert_eq!(assert_some!(actual.current), observation);
+            assert_eq!(assert_some!(actual.current), observation.weather);
+
+            let stations =
+                assert_some!(assert_ok!(weather_repository.stations_by_zone(&zone).await));
+            assert_eq!(stations, observation.stations);
         }
         .instrument(info_span!("ASYNC_BLOCK")),
     );
@@ -138,6 +152,7 @@ fn test_note_weather_forecast() -> anyhow::Result<()> {
     let update_id = weather::update::next_id();
     let zone = LocationZoneCode::random();
     let forecast: ZoneForecast = assert_ok!(actual_feature.try_into());
+    assert_eq!(forecast.updated.to_rfc3339(), "2024-03-13T21:35:00+00:00");
     let command = weather::zone::protocol::NoteForecast::new(
         zone.clone(),
         update_id.clone(),
@@ -182,8 +197,10 @@ fn test_note_weather_forecast() -> anyhow::Result<()> {
                 }]
             );
 
-            let weather_projection =
-                assert_ok!(weather::zone::read_model::ZoneWeatherProjection::new(pool_cmd).await);
+            let (changes, _) = tokio::sync::broadcast::channel(16);
+            let weather_projection = assert_ok!(
+                weather::zone::read_model::ZoneWeatherProjection::new(pool_cmd, changes).await
+            );
 
             let listener_config =
                 disintegrate_postgres::PgEventListenerConfig::poller(Duration::from_millis(50));
@@ -215,6 +232,21 @@ fn test_note_weather_forecast() -> anyhow::Result<()> {
             let actual = assert_some!(assert_ok!(weather_repository.weather_by_zone(&zone).await));
             assert_eq!(actual.zone, zone);
             assert_eq!(assert_some!(actual.forecast), forecast);
+
+            let periods = assert_some!(assert_ok!(
+                weather_repository.forecast_periods(&zone, None, None).await
+            ));
+            assert_eq!(periods.periods, forecast.periods);
+            assert!(periods.untimed);
+
+            // -- zone forecast periods are not timed, so none overlap a bounded range
+            let periods = assert_some!(assert_ok!(
+                weather_repository
+                    .forecast_periods(&zone, Some(forecast.updated), None)
+                    .await
+            ));
+            assert!(periods.periods.is_empty());
+            assert!(periods.untimed);
         }
         .instrument(info_span!("ASYNC_BLOCK")),
     );